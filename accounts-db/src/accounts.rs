@@ -108,6 +108,8 @@ pub struct LoadedTransaction {
     pub program_indices: TransactionProgramIndices,
     pub rent: TransactionRent,
     pub rent_debits: RentDebits,
+    /// Size of the account data loaded for the transaction, programs included
+    pub loaded_accounts_data_size: usize,
 }
 
 pub type TransactionLoadResult = (Result<LoadedTransaction>, Option<NonceFull>);
@@ -627,6 +629,25 @@ impl Accounts {
             .collect()
     }
 
+    /// Returns the accounts of `tx` currently locked by other transactions in a way
+    /// that conflicts with the locks `tx` needs
+    pub fn get_conflicting_locks(&self, tx: &SanitizedTransaction) -> Vec<Pubkey> {
+        let TransactionAccountLocks { readonly, writable } = tx.get_account_locks_unchecked();
+        let account_locks = self.account_locks.lock().unwrap();
+        writable
+            .into_iter()
+            .filter(|key| {
+                account_locks.is_locked_write(key) || account_locks.is_locked_readonly(key)
+            })
+            .chain(
+                readonly
+                    .into_iter()
+                    .filter(|key| account_locks.is_locked_write(key)),
+            )
+            .copied()
+            .collect()
+    }
+
     /// Once accounts are unlocked, new transactions that modify that state can enter the pipeline
     #[allow(clippy::needless_collect)]
     pub fn unlock_accounts<'a>(
@@ -862,6 +883,8 @@ mod tests {
                 return_data: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
+                loaded_accounts_data_size: 0,
+                execution_trace: None,
             },
             programs_modified_by_tx: Box::<LoadedProgramsForTxBatch>::default(),
         }
@@ -1550,6 +1573,7 @@ mod tests {
                 program_indices: vec![],
                 rent: 0,
                 rent_debits: RentDebits::default(),
+                loaded_accounts_data_size: 0,
             }),
             None,
         );
@@ -1560,6 +1584,7 @@ mod tests {
                 program_indices: vec![],
                 rent: 0,
                 rent_debits: RentDebits::default(),
+                loaded_accounts_data_size: 0,
             }),
             None,
         );
@@ -1941,6 +1966,7 @@ mod tests {
                 program_indices: vec![],
                 rent: 0,
                 rent_debits: RentDebits::default(),
+                loaded_accounts_data_size: 0,
             }),
             nonce.clone(),
         );
@@ -2050,6 +2076,7 @@ mod tests {
                 program_indices: vec![],
                 rent: 0,
                 rent_debits: RentDebits::default(),
+                loaded_accounts_data_size: 0,
             }),
             nonce.clone(),
         );
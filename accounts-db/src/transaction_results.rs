@@ -9,7 +9,9 @@ use {
         nonce_info::{NonceFull, NonceInfo, NoncePartial},
         rent_debits::RentDebits,
     },
-    solana_program_runtime::loaded_programs::LoadedProgramsForTxBatch,
+    solana_program_runtime::{
+        execution_trace::TracedInstruction, loaded_programs::LoadedProgramsForTxBatch,
+    },
     solana_sdk::{
         instruction::{CompiledInstruction, TRANSACTION_LEVEL_STACK_HEIGHT},
         transaction::{self, TransactionError},
@@ -85,6 +87,11 @@ pub struct TransactionExecutionDetails {
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
     pub accounts_data_len_delta: i64,
+    /// Size of the account data loaded for this transaction, programs included
+    pub loaded_accounts_data_size: usize,
+    /// The invoked instructions with their account changes, only recorded for
+    /// simulations that request it
+    pub execution_trace: Option<Vec<TracedInstruction>>,
 }
 
 #[derive(Debug, Clone)]
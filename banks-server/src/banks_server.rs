@@ -2,21 +2,22 @@ use {
     bincode::{deserialize, serialize},
     crossbeam_channel::{unbounded, Receiver, Sender},
     futures::{future, prelude::stream::StreamExt},
-    solana_accounts_db::transaction_results::TransactionExecutionResult,
+    solana_accounts_db::transaction_results::{TransactionExecutionResult, TransactionResults},
     solana_banks_interface::{
         Banks, BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
         BanksTransactionResultWithSimulation, TransactionConfirmationStatus, TransactionMetadata,
         TransactionSimulationDetails, TransactionStatus,
     },
     solana_client::connection_cache::ConnectionCache,
+    solana_program_runtime::timings::ExecuteTimings,
     solana_runtime::{
-        bank::{Bank, TransactionSimulationResult},
+        bank::{Bank, TransactionBalancesSet, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::BlockCommitmentCache,
     },
     solana_sdk::{
         account::Account,
-        clock::Slot,
+        clock::{Slot, MAX_PROCESSING_AGE},
         commitment_config::CommitmentLevel,
         feature_set::FeatureSet,
         fee_calculator::FeeCalculator,
@@ -49,12 +50,58 @@ use {
     tokio_serde::formats::Bincode,
 };
 
+/// Notified of the transactions a local server commits to the working bank
+pub trait TransactionCommitNotifier: Send + Sync {
+    fn notify_committed_transactions(
+        &self,
+        bank: &Bank,
+        transactions: &[SanitizedTransaction],
+        execution_results: &[TransactionExecutionResult],
+        balances: &TransactionBalancesSet,
+    );
+}
+
+pub type TransactionCommitNotifierArc = Arc<dyn TransactionCommitNotifier>;
+
+/// Execute and commit `transactions` to `bank`, recording everything the
+/// notifier may need about them
+fn process_and_notify_transactions(
+    bank: &Bank,
+    transactions: Vec<VersionedTransaction>,
+    transaction_commit_notifier: &dyn TransactionCommitNotifier,
+) -> transaction::Result<Vec<TransactionExecutionResult>> {
+    let batch = bank.prepare_entry_batch(transactions)?;
+    let (
+        TransactionResults {
+            execution_results, ..
+        },
+        balances,
+    ) = bank.load_execute_and_commit_transactions(
+        &batch,
+        MAX_PROCESSING_AGE,
+        true, // collect_balances
+        true, // enable_cpi_recording
+        true, // enable_log_recording
+        true, // enable_return_data_recording
+        &mut ExecuteTimings::default(),
+        Some(1000 * 1000),
+    );
+    transaction_commit_notifier.notify_committed_transactions(
+        bank,
+        batch.sanitized_transactions(),
+        &execution_results,
+        &balances,
+    );
+    Ok(execution_results)
+}
+
 #[derive(Clone)]
 struct BanksServer {
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     transaction_sender: Sender<TransactionInfo>,
     poll_signature_status_sleep_duration: Duration,
+    transaction_commit_notifier: Option<TransactionCommitNotifierArc>,
 }
 
 impl BanksServer {
@@ -67,16 +114,22 @@ impl BanksServer {
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         transaction_sender: Sender<TransactionInfo>,
         poll_signature_status_sleep_duration: Duration,
+        transaction_commit_notifier: Option<TransactionCommitNotifierArc>,
     ) -> Self {
         Self {
             bank_forks,
             block_commitment_cache,
             transaction_sender,
             poll_signature_status_sleep_duration,
+            transaction_commit_notifier,
         }
     }
 
-    fn run(bank_forks: Arc<RwLock<BankForks>>, transaction_receiver: Receiver<TransactionInfo>) {
+    fn run(
+        bank_forks: Arc<RwLock<BankForks>>,
+        transaction_receiver: Receiver<TransactionInfo>,
+        transaction_commit_notifier: Option<TransactionCommitNotifierArc>,
+    ) {
         while let Ok(info) = transaction_receiver.recv() {
             let mut transaction_infos = vec![info];
             while let Ok(info) = transaction_receiver.try_recv() {
@@ -93,7 +146,15 @@ impl BanksServer {
                 // has been processed
                 let lock = bank.freeze_lock();
                 if *lock == Hash::default() {
-                    let _ = bank.try_process_entry_transactions(transactions);
+                    if let Some(transaction_commit_notifier) = &transaction_commit_notifier {
+                        let _ = process_and_notify_transactions(
+                            &bank,
+                            transactions,
+                            transaction_commit_notifier.as_ref(),
+                        );
+                    } else {
+                        let _ = bank.try_process_entry_transactions(transactions);
+                    }
                     // break out of inner loop and release bank freeze lock
                     break;
                 }
@@ -106,6 +167,7 @@ impl BanksServer {
         bank_forks: Arc<RwLock<BankForks>>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         poll_signature_status_sleep_duration: Duration,
+        transaction_commit_notifier: Option<TransactionCommitNotifierArc>,
     ) -> Self {
         let (transaction_sender, transaction_receiver) = unbounded();
         let bank = bank_forks.read().unwrap().working_bank();
@@ -116,15 +178,23 @@ impl BanksServer {
             w_block_commitment_cache.set_all_slots(slot, slot);
         }
         let server_bank_forks = bank_forks.clone();
+        let server_transaction_commit_notifier = transaction_commit_notifier.clone();
         Builder::new()
             .name("solBankForksCli".to_string())
-            .spawn(move || Self::run(server_bank_forks, transaction_receiver))
+            .spawn(move || {
+                Self::run(
+                    server_bank_forks,
+                    transaction_receiver,
+                    server_transaction_commit_notifier,
+                )
+            })
             .unwrap();
         Self::new(
             bank_forks,
             block_commitment_cache,
             transaction_sender,
             poll_signature_status_sleep_duration,
+            transaction_commit_notifier,
         )
     }
 
@@ -195,7 +265,8 @@ fn simulate_transaction(
         units_consumed,
         return_data,
         inner_instructions,
-    } = bank.simulate_transaction_unchecked(&sanitized_transaction, false);
+        execution_trace: _,
+    } = bank.simulate_transaction_unchecked(&sanitized_transaction, false, false, None);
 
     let simulation_details = TransactionSimulationDetails {
         logs,
@@ -367,7 +438,19 @@ impl Banks for BanksServer {
         transaction: VersionedTransaction,
     ) -> BanksTransactionResultWithMetadata {
         let bank = self.bank_forks.read().unwrap().working_bank();
-        match bank.process_transaction_with_metadata(transaction) {
+        let execution_result = match &self.transaction_commit_notifier {
+            Some(transaction_commit_notifier) => process_and_notify_transactions(
+                &bank,
+                vec![transaction],
+                transaction_commit_notifier.as_ref(),
+            )
+            .map_or_else(
+                TransactionExecutionResult::NotExecuted,
+                |mut execution_results| execution_results.remove(0),
+            ),
+            None => bank.process_transaction_with_metadata(transaction),
+        };
+        match execution_result {
             TransactionExecutionResult::NotExecuted(error) => BanksTransactionResultWithMetadata {
                 result: Err(error),
                 metadata: None,
@@ -427,11 +510,29 @@ pub async fn start_local_server(
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     poll_signature_status_sleep_duration: Duration,
+) -> UnboundedChannel<Response<BanksResponse>, ClientMessage<BanksRequest>> {
+    start_local_server_with_notifier(
+        bank_forks,
+        block_commitment_cache,
+        poll_signature_status_sleep_duration,
+        None,
+    )
+    .await
+}
+
+/// Start a local server which notifies `transaction_commit_notifier` of the
+/// transactions it commits
+pub async fn start_local_server_with_notifier(
+    bank_forks: Arc<RwLock<BankForks>>,
+    block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+    poll_signature_status_sleep_duration: Duration,
+    transaction_commit_notifier: Option<TransactionCommitNotifierArc>,
 ) -> UnboundedChannel<Response<BanksResponse>, ClientMessage<BanksRequest>> {
     let banks_server = BanksServer::new_loopback(
         bank_forks,
         block_commitment_cache,
         poll_signature_status_sleep_duration,
+        transaction_commit_notifier,
     );
     let (client_transport, server_transport) = transport::channel::unbounded();
     let server = server::BaseChannel::with_defaults(server_transport).execute(banks_server.serve());
@@ -481,6 +582,7 @@ pub async fn start_tcp_server(
                 block_commitment_cache.clone(),
                 sender,
                 Duration::from_millis(200),
+                None,
             );
             chan.execute(server.serve())
         })
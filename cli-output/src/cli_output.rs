@@ -23,7 +23,7 @@ use {
     solana_clap_utils::keypair::SignOnly,
     solana_rpc_client_api::response::{
         RpcAccountBalance, RpcContactInfo, RpcInflationGovernor, RpcInflationRate, RpcKeyedAccount,
-        RpcSupply, RpcVoteAccountInfo,
+        RpcPod, RpcSupply, RpcVoteAccountInfo,
     },
     solana_sdk::{
         account::ReadableAccount,
@@ -2729,6 +2729,91 @@ impl fmt::Display for CliBlock {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct CliPod {
+    #[serde(flatten)]
+    pub pod: RpcPod,
+}
+
+impl QuietDisplay for CliPod {}
+impl VerboseDisplay for CliPod {}
+
+impl fmt::Display for CliPod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header = &self.pod.pod_header;
+        writeln!(f, "Pod: {}", header.pod_number)?;
+        if let Some(parent_pod_number) = header.parent_pod_number {
+            writeln!(f, "Parent Pod: {parent_pod_number}")?;
+        }
+        writeln!(f, "Seal Reason: {:?}", header.seal_reason)?;
+        writeln!(f, "Slots: {}..={}", header.first_slot, header.last_slot)?;
+        writeln!(f, "Transactions Root: {}", header.transactions_root)?;
+        for (label, slot_state) in [
+            ("First Slot", &header.first_slot_state),
+            ("Last Slot", &header.last_slot_state),
+        ] {
+            if let Some(slot_state) = slot_state {
+                writeln!(
+                    f,
+                    "{label} Bank Hash: {} (slot {})",
+                    slot_state.bank_hash, slot_state.slot
+                )?;
+            }
+        }
+        if !self.pod.state_diff.is_empty() {
+            writeln!(f, "State Diff:")?;
+            writeln!(
+                f,
+                "  {:<44}  {:<44}  {:>20}  {:<44}",
+                "Address", "Owner", "Lamports", "Data Hash"
+            )?;
+            for account_write in &self.pod.state_diff {
+                writeln!(
+                    f,
+                    "  {:<44}  {:<44}  {:>20}  {:<44}",
+                    account_write.pubkey,
+                    account_write.owner,
+                    account_write.lamports,
+                    account_write.data_hash
+                )?;
+            }
+        }
+        for (index, confirmed_transaction) in self.pod.pod_data.iter().enumerate() {
+            writeln!(
+                f,
+                "Transaction {index} (slot {}):",
+                confirmed_transaction.slot
+            )?;
+            if let Some(transaction) = confirmed_transaction.transaction.transaction.decode() {
+                writeln_transaction(
+                    f,
+                    &transaction,
+                    confirmed_transaction.transaction.meta.as_ref(),
+                    "  ",
+                    None,
+                    confirmed_transaction.block_time,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliLatestPod {
+    pub latest_pod: u64,
+}
+
+impl QuietDisplay for CliLatestPod {}
+impl VerboseDisplay for CliLatestPod {}
+
+impl fmt::Display for CliLatestPod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.latest_pod)
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliTransaction {
@@ -1,7 +1,7 @@
 use {
     crate::{
         address_lookup_table::AddressLookupTableSubCommands, cli::*, cluster_query::*, feature::*,
-        inflation::*, nonce::*, program::*, program_v4::ProgramV4SubCommands, stake::*,
+        inflation::*, nonce::*, pod::*, program::*, program_v4::ProgramV4SubCommands, stake::*,
         validator_info::*, vote::*, wallet::*,
     },
     clap::{App, AppSettings, Arg, ArgGroup, SubCommand},
@@ -146,6 +146,7 @@ pub fn get_clap_app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> A
         .feature_subcommands()
         .inflation_subcommands()
         .nonce_subcommands()
+        .pod_subcommands()
         .program_subcommands()
         .program_v4_subcommands()
         .address_lookup_table_subcommands()
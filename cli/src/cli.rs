@@ -1,7 +1,8 @@
 use {
     crate::{
         address_lookup_table::*, clap_app::*, cluster_query::*, feature::*, inflation::*, nonce::*,
-        program::*, program_v4::*, spend_utils::*, stake::*, validator_info::*, vote::*, wallet::*,
+        pod::*, program::*, program_v4::*, spend_utils::*, stake::*, validator_info::*, vote::*,
+        wallet::*,
     },
     clap::{crate_description, crate_name, value_t_or_exit, ArgMatches, Shell},
     log::*,
@@ -58,6 +59,7 @@ pub enum CliCommand {
     ClusterVersion,
     Feature(FeatureCliCommand),
     Inflation(InflationCliCommand),
+    Pod(PodCliCommand),
     Fees {
         blockhash: Option<Hash>,
     },
@@ -639,6 +641,7 @@ pub fn parse_command(
             parse_inflation_subcommand(matches, default_signer, wallet_manager)
         }
         ("largest-accounts", Some(matches)) => parse_largest_accounts(matches),
+        ("pod", Some(matches)) => parse_pod_subcommand(matches),
         ("leader-schedule", Some(matches)) => parse_leader_schedule(matches),
         ("live-slots", Some(_matches)) => Ok(CliCommandInfo {
             command: CliCommand::LiveSlots,
@@ -922,6 +925,9 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
         CliCommand::Inflation(inflation_subcommand) => {
             process_inflation_subcommand(&rpc_client, config, inflation_subcommand)
         }
+        CliCommand::Pod(pod_subcommand) => {
+            process_pod_subcommand(&rpc_client, config, pod_subcommand)
+        }
         CliCommand::LeaderSchedule { epoch } => {
             process_leader_schedule(&rpc_client, config, *epoch)
         }
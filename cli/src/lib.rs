@@ -33,6 +33,7 @@ pub mod feature;
 pub mod inflation;
 pub mod memo;
 pub mod nonce;
+pub mod pod;
 pub mod program;
 pub mod program_v4;
 pub mod spend_utils;
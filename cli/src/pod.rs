@@ -0,0 +1,244 @@
+use {
+    crate::cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+    clap::{App, Arg, ArgMatches, SubCommand},
+    solana_clap_utils::{input_parsers::value_of, input_validators::is_parsable},
+    solana_cli_output::{CliLatestPod, CliPod},
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::{config::RpcPodConfig, response::RpcPod},
+    solana_transaction_status::UiTransactionEncoding,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PodCliCommand {
+    Get {
+        pod_number: u64,
+    },
+    Latest,
+    Export {
+        first_pod: u64,
+        last_pod: Option<u64>,
+        output_dir: PathBuf,
+    },
+}
+
+pub trait PodSubCommands {
+    fn pod_subcommands(self) -> Self;
+}
+
+impl PodSubCommands for App<'_, '_> {
+    fn pod_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("pod")
+                .about("Pod utilities")
+                .setting(clap::AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("get")
+                        .about("Get a sealed pod and its state diff")
+                        .arg(
+                            Arg::with_name("pod_number")
+                                .index(1)
+                                .value_name("POD_NUMBER")
+                                .takes_value(true)
+                                .validator(is_parsable::<u64>)
+                                .required(true)
+                                .help("Pod to fetch"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("latest").about("Get the latest sealed pod number"),
+                )
+                .subcommand(
+                    SubCommand::with_name("export")
+                        .about("Write a range of sealed pods to a directory as JSON files")
+                        .arg(
+                            Arg::with_name("output_dir")
+                                .index(1)
+                                .value_name("DIR")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Directory to write the pod-<POD_NUMBER>.json files to"),
+                        )
+                        .arg(
+                            Arg::with_name("first_pod")
+                                .long("from")
+                                .value_name("POD_NUMBER")
+                                .takes_value(true)
+                                .validator(is_parsable::<u64>)
+                                .default_value("1")
+                                .help("First pod to export"),
+                        )
+                        .arg(
+                            Arg::with_name("last_pod")
+                                .long("to")
+                                .value_name("POD_NUMBER")
+                                .takes_value(true)
+                                .validator(is_parsable::<u64>)
+                                .help("Last pod to export [default: latest sealed pod]"),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn parse_pod_subcommand(matches: &ArgMatches<'_>) -> Result<CliCommandInfo, CliError> {
+    let command = match matches.subcommand() {
+        ("get", Some(matches)) => PodCliCommand::Get {
+            pod_number: value_of(matches, "pod_number").unwrap(),
+        },
+        ("latest", Some(_matches)) => PodCliCommand::Latest,
+        ("export", Some(matches)) => PodCliCommand::Export {
+            first_pod: value_of(matches, "first_pod").unwrap(),
+            last_pod: value_of(matches, "last_pod"),
+            output_dir: value_of(matches, "output_dir").unwrap(),
+        },
+        _ => unreachable!(),
+    };
+    Ok(CliCommandInfo {
+        command: CliCommand::Pod(command),
+        signers: vec![],
+    })
+}
+
+pub fn process_pod_subcommand(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    pod_subcommand: &PodCliCommand,
+) -> ProcessResult {
+    match pod_subcommand {
+        PodCliCommand::Get { pod_number } => process_get_pod(rpc_client, config, *pod_number),
+        PodCliCommand::Latest => process_latest_pod(rpc_client, config),
+        PodCliCommand::Export {
+            first_pod,
+            last_pod,
+            output_dir,
+        } => process_export_pods(rpc_client, *first_pod, *last_pod, output_dir),
+    }
+}
+
+fn get_pod(rpc_client: &RpcClient, pod_number: u64) -> Result<RpcPod, Box<dyn std::error::Error>> {
+    Ok(rpc_client.get_pod_with_config(
+        pod_number,
+        RpcPodConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            max_supported_transaction_version: Some(0),
+        },
+    )?)
+}
+
+fn process_get_pod(rpc_client: &RpcClient, config: &CliConfig, pod_number: u64) -> ProcessResult {
+    let pod = get_pod(rpc_client, pod_number)?;
+    Ok(config.output_format.formatted_string(&CliPod { pod }))
+}
+
+fn process_latest_pod(rpc_client: &RpcClient, config: &CliConfig) -> ProcessResult {
+    let latest_pod = rpc_client.get_latest_pod()?;
+    Ok(config
+        .output_format
+        .formatted_string(&CliLatestPod { latest_pod }))
+}
+
+fn process_export_pods(
+    rpc_client: &RpcClient,
+    first_pod: u64,
+    last_pod: Option<u64>,
+    output_dir: &Path,
+) -> ProcessResult {
+    let last_pod = match last_pod {
+        Some(last_pod) => last_pod,
+        None => rpc_client.get_latest_pod()?,
+    };
+    if first_pod > last_pod {
+        return Err(format!("No pods to export, first pod {first_pod} is after {last_pod}").into());
+    }
+
+    fs::create_dir_all(output_dir)?;
+    for pod_number in first_pod..=last_pod {
+        let pod = get_pod(rpc_client, pod_number)?;
+        let path = output_dir.join(format!("pod-{pod_number}.json"));
+        fs::write(&path, serde_json::to_vec_pretty(&pod)?)
+            .map_err(|err| format!("Unable to write {}: {err}", path.display()))?;
+    }
+    Ok(format!(
+        "Exported pods {first_pod} to {last_pod} to {}",
+        output_dir.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{clap_app::get_clap_app, cli::parse_command},
+        solana_clap_utils::keypair::DefaultSigner,
+        solana_sdk::signature::{write_keypair, Keypair},
+        tempfile::NamedTempFile,
+    };
+
+    #[test]
+    fn test_parse_pod_subcommand() {
+        let test_commands = get_clap_app("test", "desc", "version");
+        let default_keypair = Keypair::new();
+        let keypair_file = NamedTempFile::new().unwrap();
+        write_keypair(&default_keypair, keypair_file.as_file()).unwrap();
+        let default_signer = DefaultSigner::new("", keypair_file.path().to_str().unwrap());
+
+        let matches = test_commands
+            .clone()
+            .get_matches_from(vec!["test", "pod", "get", "7"]);
+        assert_eq!(
+            parse_command(&matches, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Pod(PodCliCommand::Get { pod_number: 7 }),
+                signers: vec![],
+            }
+        );
+
+        let matches = test_commands
+            .clone()
+            .get_matches_from(vec!["test", "pod", "export", "pods", "--to", "3"]);
+        assert_eq!(
+            parse_command(&matches, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Pod(PodCliCommand::Export {
+                    first_pod: 1,
+                    last_pod: Some(3),
+                    output_dir: PathBuf::from("pods"),
+                }),
+                signers: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_process_pod_subcommand() {
+        let rpc_client = RpcClient::new_mock("succeeds".to_string());
+        let config = CliConfig::default();
+        assert!(process_pod_subcommand(&rpc_client, &config, &PodCliCommand::Latest).is_ok());
+        assert!(process_pod_subcommand(
+            &rpc_client,
+            &config,
+            &PodCliCommand::Get { pod_number: 1 }
+        )
+        .is_ok());
+
+        let output_dir = tempfile::tempdir().unwrap();
+        process_pod_subcommand(
+            &rpc_client,
+            &config,
+            &PodCliCommand::Export {
+                first_pod: 1,
+                last_pod: None,
+                output_dir: output_dir.path().to_path_buf(),
+            },
+        )
+        .unwrap();
+        let pod: RpcPod =
+            serde_json::from_slice(&fs::read(output_dir.path().join("pod-1.json")).unwrap())
+                .unwrap();
+        assert_eq!(pod.pod_header.pod_number, 1);
+    }
+}
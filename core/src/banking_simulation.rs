@@ -0,0 +1,479 @@
+//! Replay a banking trace recorded by [`BankingTracer`] through a `BankingStage`.
+//!
+//! The packet batches of the trace are sent to the banking stage with their traced timing,
+//! while the simulated node produces the blocks of the traced slots from a bank of the
+//! ledger. The produced blocks are compared with the hash events of the trace and with the
+//! blocks of the ledger.
+
+use {
+    crate::{
+        banking_stage::BankingStage,
+        banking_trace::{
+            BankingPacketBatch, BankingPacketSender, BankingTracer, ChannelLabel, TimedTracedEvent,
+            TracedEvent, BASENAME, TRACE_FILE_ROTATE_COUNT,
+        },
+        validator::BlockProductionMethod,
+    },
+    bincode::deserialize_from,
+    crossbeam_channel::unbounded,
+    solana_client::connection_cache::ConnectionCache,
+    solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
+    solana_poh::{
+        poh_recorder::PohRecorder,
+        poh_service::{PohService, DEFAULT_HASHES_PER_BATCH, DEFAULT_PINNED_CPU_CORE},
+    },
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache,
+    },
+    solana_sdk::{
+        clock::Slot,
+        genesis_config::GenesisConfig,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        timing::timestamp,
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    std::{
+        collections::{BTreeMap, HashMap},
+        fs::File,
+        io::{self, BufReader},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep, JoinHandle},
+        time::{Duration, Instant, SystemTime},
+    },
+    thiserror::Error,
+};
+
+/// Packets traced before the first simulated slot that are still sent to the banking stage,
+/// as the traced node was holding them when its leader slots began
+const WARMUP_DURATION: Duration = Duration::from_secs(13);
+
+#[derive(Error, Debug)]
+pub enum SimulateError {
+    #[error("IO Error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("Deserialization Error: {0}")]
+    DeserializeError(#[from] bincode::Error),
+
+    #[error("No banking trace events found in {0:?}")]
+    NoEvents(PathBuf),
+
+    #[error("The banking trace has no hash event of the parent slot {0}")]
+    MissingParentHashEvent(Slot),
+
+    #[error("No leader found for slot {0}")]
+    NoLeader(Slot),
+}
+
+/// The events of a banking trace directory, in the order they were traced
+#[derive(Default)]
+pub struct BankingTraceEvents {
+    packet_batches: Vec<(SystemTime, ChannelLabel, BankingPacketBatch)>,
+    /// Freeze time, blockhash and bank hash of the traced slots
+    hash_events: BTreeMap<Slot, (SystemTime, Hash, Hash)>,
+}
+
+impl BankingTraceEvents {
+    /// Read the rotated event files of `trace_dir`, oldest first
+    pub fn load(trace_dir: &Path) -> Result<Self, SimulateError> {
+        let mut events = Self::default();
+        let event_files = (1..TRACE_FILE_ROTATE_COUNT)
+            .rev()
+            .map(|index| trace_dir.join(format!("{BASENAME}.{index}")))
+            .chain(std::iter::once(trace_dir.join(BASENAME)));
+        for event_file in event_files {
+            match File::open(&event_file) {
+                Ok(file) => events.read_event_file(&event_file, file)?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        if events.packet_batches.is_empty() && events.hash_events.is_empty() {
+            return Err(SimulateError::NoEvents(trace_dir.to_path_buf()));
+        }
+        events
+            .packet_batches
+            .sort_by_key(|(event_time, ..)| *event_time);
+        Ok(events)
+    }
+
+    fn read_event_file(&mut self, path: &Path, file: File) -> Result<(), SimulateError> {
+        let mut reader = BufReader::new(file);
+        let mut event_count = 0;
+        loop {
+            match deserialize_from::<_, TimedTracedEvent>(&mut reader) {
+                Ok(TimedTracedEvent(event_time, event)) => {
+                    event_count += 1;
+                    match event {
+                        TracedEvent::PacketBatch(label, batch) => {
+                            self.packet_batches.push((event_time, label, batch))
+                        }
+                        TracedEvent::BlockAndBankHash(slot, blockhash, bank_hash) => {
+                            self.hash_events
+                                .insert(slot, (event_time, blockhash, bank_hash));
+                        }
+                    }
+                }
+                // The last event of a file is cut short when the validator didn't exit cleanly
+                Err(err)
+                    if matches!(
+                        &*err,
+                        bincode::ErrorKind::Io(io_err)
+                            if io_err.kind() == io::ErrorKind::UnexpectedEof
+                    ) =>
+                {
+                    break;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        info!("read {event_count} banking trace events from {path:?}");
+        Ok(())
+    }
+
+    pub fn packet_batch_count(&self) -> usize {
+        self.packet_batches.len()
+    }
+
+    /// The blockhash and bank hash the traced node froze `slot` with
+    pub fn hash_event(&self, slot: Slot) -> Option<(Hash, Hash)> {
+        self.hash_events
+            .get(&slot)
+            .map(|(_, blockhash, bank_hash)| (*blockhash, *bank_hash))
+    }
+
+    /// The last slot frozen by the traced node
+    pub fn last_hash_event_slot(&self) -> Option<Slot> {
+        self.hash_events.keys().next_back().copied()
+    }
+}
+
+/// A block produced by the simulation
+#[derive(Debug)]
+pub struct SimulatedBlock {
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    /// The leader of the slot in the leader schedule, collecting the fees of the block
+    pub leader: Pubkey,
+    pub transaction_count: u64,
+    /// Entries of the block holding transactions
+    pub entry_count: usize,
+    pub block_cost: u64,
+    pub block_cost_limit: u64,
+    pub blockhash: Hash,
+    pub bank_hash: Hash,
+    /// The blockhash and bank hash of the slot in the trace
+    pub recorded_hashes: Option<(Hash, Hash)>,
+    /// The number of transactions of the slot in the ledger
+    pub recorded_transaction_count: Option<usize>,
+}
+
+impl SimulatedBlock {
+    /// The share of the block cost limit used by the block
+    pub fn packing_efficiency(&self) -> f64 {
+        self.block_cost as f64 / self.block_cost_limit as f64
+    }
+
+    /// Whether the block differs from the traced one, if the trace has a hash event of the slot
+    pub fn diverged(&self) -> Option<bool> {
+        self.recorded_hashes.map(|(blockhash, bank_hash)| {
+            blockhash != self.blockhash || bank_hash != self.bank_hash
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SimulationReport {
+    pub blocks: Vec<SimulatedBlock>,
+    /// Packet batches and packets sent to the banking stage
+    pub packet_batch_count: usize,
+    pub packet_count: usize,
+}
+
+pub struct BankingSimulator {
+    events: BankingTraceEvents,
+    first_simulated_slot: Slot,
+    last_simulated_slot: Slot,
+}
+
+impl BankingSimulator {
+    /// Simulate the slots from `first_simulated_slot`, up to the last slot frozen in the trace
+    /// unless `num_simulated_slots` is given
+    pub fn new(
+        events: BankingTraceEvents,
+        first_simulated_slot: Slot,
+        num_simulated_slots: Option<u64>,
+    ) -> Self {
+        let last_simulated_slot = match num_simulated_slots {
+            Some(num_simulated_slots) => {
+                first_simulated_slot + num_simulated_slots.saturating_sub(1)
+            }
+            None => events
+                .last_hash_event_slot()
+                .unwrap_or_default()
+                .max(first_simulated_slot),
+        };
+        Self {
+            events,
+            first_simulated_slot,
+            last_simulated_slot,
+        }
+    }
+
+    /// Produce the simulated blocks on top of the working bank of `bank_forks`, which must be
+    /// a slot frozen in the trace
+    pub fn simulate(
+        self,
+        genesis_config: &GenesisConfig,
+        bank_forks: Arc<RwLock<BankForks>>,
+        blockstore: Arc<Blockstore>,
+        block_production_method: BlockProductionMethod,
+    ) -> Result<SimulationReport, SimulateError> {
+        let Self {
+            mut events,
+            first_simulated_slot,
+            last_simulated_slot,
+        } = self;
+        let parent_bank = bank_forks.read().unwrap().working_bank();
+        let reference_time = events
+            .hash_events
+            .get(&parent_bank.slot())
+            .map(|(event_time, ..)| *event_time)
+            .ok_or(SimulateError::MissingParentHashEvent(parent_bank.slot()))?;
+
+        let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&parent_bank));
+        let slot_leaders = (first_simulated_slot..=last_simulated_slot)
+            .map(|slot| {
+                leader_schedule_cache
+                    .slot_leader_at(slot, Some(&parent_bank))
+                    .map(|leader| (slot, leader))
+                    .ok_or(SimulateError::NoLeader(slot))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        info!(
+            "simulating slots {first_simulated_slot}..={last_simulated_slot} on top of slot {}",
+            parent_bank.slot()
+        );
+
+        // The simulated node leads every simulated slot, whoever led them when traced
+        let identity = Arc::new(Keypair::new());
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new_localhost(&identity.pubkey(), timestamp()),
+            identity,
+            SocketAddrSpace::Unspecified,
+        ));
+        let exit = Arc::new(AtomicBool::default());
+        let (poh_recorder, entry_receiver, record_receiver) = PohRecorder::new(
+            parent_bank.tick_height(),
+            parent_bank.last_blockhash(),
+            parent_bank.clone(),
+            Some((first_simulated_slot, last_simulated_slot)),
+            parent_bank.ticks_per_slot(),
+            blockstore.clone(),
+            &leader_schedule_cache,
+            &genesis_config.poh_config,
+            exit.clone(),
+        );
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+
+        let banking_tracer = BankingTracer::new_disabled();
+        let (non_vote_sender, non_vote_receiver) = banking_tracer.create_channel_non_vote();
+        let (tpu_vote_sender, tpu_vote_receiver) = banking_tracer.create_channel_tpu_vote();
+        let (gossip_vote_sender, gossip_vote_receiver) =
+            banking_tracer.create_channel_gossip_vote();
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let banking_stage = BankingStage::new(
+            block_production_method,
+            &cluster_info,
+            &poh_recorder,
+            non_vote_receiver,
+            tpu_vote_receiver,
+            gossip_vote_receiver,
+            None,
+            replay_vote_sender,
+            None,
+            Arc::new(ConnectionCache::new("connection_cache_banking_simulation")),
+            bank_forks.clone(),
+            &Arc::new(PrioritizationFeeCache::new(0u64)),
+            &Arc::default(),
+        );
+
+        let simulation_start = Instant::now();
+        let packet_sender = Self::spawn_packet_sender(
+            std::mem::take(&mut events.packet_batches),
+            reference_time,
+            simulation_start,
+            [non_vote_sender, tpu_vote_sender, gossip_vote_sender],
+            exit.clone(),
+        )?;
+        sleep(WARMUP_DURATION.saturating_sub(simulation_start.elapsed()));
+
+        let poh_service = PohService::new(
+            poh_recorder.clone(),
+            &genesis_config.poh_config,
+            exit.clone(),
+            parent_bank.ticks_per_slot(),
+            DEFAULT_PINNED_CPU_CORE,
+            DEFAULT_HASHES_PER_BATCH,
+            record_receiver,
+        );
+
+        let mut blocks = Vec::with_capacity(slot_leaders.len());
+        let mut entry_counts = HashMap::<Slot, usize>::new();
+        let mut parent = parent_bank;
+        for (slot, leader) in slot_leaders {
+            let bank = Bank::new_from_parent(parent.clone(), &leader, slot);
+            let bank = bank_forks.write().unwrap().insert(bank);
+            poh_recorder
+                .write()
+                .unwrap()
+                .set_bank(bank.clone_with_scheduler(), false);
+            while !bank.is_complete() {
+                sleep(Duration::from_millis(1));
+            }
+            bank.freeze();
+
+            for (entry_bank, (entry, _tick_height)) in entry_receiver.try_iter() {
+                if !entry.is_tick() {
+                    *entry_counts.entry(entry_bank.slot()).or_default() += 1;
+                }
+            }
+            let block = SimulatedBlock {
+                slot,
+                parent_slot: parent.slot(),
+                leader,
+                transaction_count: bank.executed_transaction_count(),
+                entry_count: entry_counts.remove(&slot).unwrap_or_default(),
+                block_cost: bank.read_cost_tracker().unwrap().block_cost(),
+                block_cost_limit: bank.read_cost_tracker().unwrap().block_cost_limit(),
+                blockhash: bank.last_blockhash(),
+                bank_hash: bank.hash(),
+                recorded_hashes: events.hash_event(slot),
+                recorded_transaction_count: blockstore
+                    .get_slot_entries(slot, 0)
+                    .ok()
+                    .filter(|entries| !entries.is_empty())
+                    .map(|entries| entries.iter().map(|entry| entry.transactions.len()).sum()),
+            };
+            info!(
+                "simulated slot {slot}: {} transactions, {:.1}% of the block cost limit",
+                block.transaction_count,
+                block.packing_efficiency() * 100.0
+            );
+            blocks.push(block);
+            parent = bank.clone_without_scheduler();
+        }
+
+        exit.store(true, Ordering::Relaxed);
+        let (packet_batch_count, packet_count) = packet_sender.join().unwrap();
+        banking_stage.join().unwrap();
+        poh_service.join().unwrap();
+
+        Ok(SimulationReport {
+            blocks,
+            packet_batch_count,
+            packet_count,
+        })
+    }
+
+    /// Send the packet batches traced from `WARMUP_DURATION` before `reference_time`, keeping
+    /// their traced offsets from `simulation_start`. Returns the number of batches and packets
+    /// sent.
+    fn spawn_packet_sender(
+        packet_batches: Vec<(SystemTime, ChannelLabel, BankingPacketBatch)>,
+        reference_time: SystemTime,
+        simulation_start: Instant,
+        [non_vote_sender, tpu_vote_sender, gossip_vote_sender]: [BankingPacketSender; 3],
+        exit: Arc<AtomicBool>,
+    ) -> Result<JoinHandle<(usize, usize)>, SimulateError> {
+        let warmup_start = reference_time - WARMUP_DURATION;
+        let thread = thread::Builder::new()
+            .name("solBankSimSndr".to_string())
+            .spawn(move || {
+                let mut packet_batch_count = 0;
+                let mut packet_count = 0;
+                for (event_time, label, batch) in packet_batches {
+                    let Ok(offset) = event_time.duration_since(warmup_start) else {
+                        continue;
+                    };
+                    let sender = match label {
+                        ChannelLabel::NonVote => &non_vote_sender,
+                        ChannelLabel::TpuVote => &tpu_vote_sender,
+                        ChannelLabel::GossipVote => &gossip_vote_sender,
+                        ChannelLabel::Dummy => continue,
+                    };
+                    while simulation_start.elapsed() < offset {
+                        if exit.load(Ordering::Relaxed) {
+                            return (packet_batch_count, packet_count);
+                        }
+                        sleep(Duration::from_millis(1));
+                    }
+                    if exit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    packet_batch_count += 1;
+                    packet_count += batch.0.iter().map(|batch| batch.len()).sum::<usize>();
+                    if sender.send(batch).is_err() {
+                        break;
+                    }
+                }
+                (packet_batch_count, packet_count)
+            })?;
+        Ok(thread)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_trace::{
+            for_test, receiving_loop_with_minimized_sender_overhead, DirByteLimit, TraceError,
+        },
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_load_banking_trace_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("banking-trace");
+        let exit = Arc::<AtomicBool>::default();
+        let (tracer, tracer_thread) =
+            BankingTracer::new(Some((&path, exit.clone(), DirByteLimit::max_value()))).unwrap();
+        let (non_vote_sender, non_vote_receiver) = tracer.create_channel_non_vote();
+        let main_thread = thread::spawn(move || {
+            receiving_loop_with_minimized_sender_overhead::<_, TraceError, 0>(
+                exit,
+                non_vote_receiver,
+                |_packet_batch| Ok(()),
+            )
+        });
+
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        let blockhash = Hash::new_unique();
+        let bank_hash = Hash::new_unique();
+        tracer.hash_event(3, &blockhash, &bank_hash);
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        for_test::terminate_tracer(tracer, tracer_thread, main_thread, non_vote_sender, None);
+
+        let events = BankingTraceEvents::load(&path).unwrap();
+        assert_eq!(events.packet_batch_count(), 2);
+        assert_eq!(events.hash_event(3), Some((blockhash, bank_hash)));
+        assert_eq!(events.last_hash_event_slot(), Some(3));
+        assert!(matches!(
+            BankingTraceEvents::load(temp_dir.path()),
+            Err(SimulateError::NoEvents(_))
+        ));
+    }
+}
@@ -36,7 +36,10 @@ use {
     solana_measure::{measure, measure_us},
     solana_perf::{data_budget::DataBudget, packet::PACKETS_PER_BATCH},
     solana_poh::poh_recorder::{PohRecorder, TransactionRecorder},
-    solana_runtime::{bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache},
+    solana_runtime::{
+        account_contention_cache::AccountContentionCache, bank_forks::BankForks,
+        prioritization_fee_cache::PrioritizationFeeCache,
+    },
     solana_sdk::timing::AtomicInterval,
     solana_vote::vote_sender_types::ReplayVoteSender,
     std::{
@@ -337,6 +340,7 @@ impl BankingStage {
         connection_cache: Arc<ConnectionCache>,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        account_contention_cache: &Arc<AccountContentionCache>,
     ) -> Self {
         Self::new_num_threads(
             block_production_method,
@@ -352,6 +356,7 @@ impl BankingStage {
             connection_cache,
             bank_forks,
             prioritization_fee_cache,
+            account_contention_cache,
         )
     }
 
@@ -370,6 +375,7 @@ impl BankingStage {
         connection_cache: Arc<ConnectionCache>,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        account_contention_cache: &Arc<AccountContentionCache>,
     ) -> Self {
         match block_production_method {
             BlockProductionMethod::ThreadLocalMultiIterator => {
@@ -386,6 +392,7 @@ impl BankingStage {
                     connection_cache,
                     bank_forks,
                     prioritization_fee_cache,
+                    account_contention_cache,
                 )
             }
             BlockProductionMethod::CentralScheduler => Self::new_central_scheduler(
@@ -401,6 +408,7 @@ impl BankingStage {
                 connection_cache,
                 bank_forks,
                 prioritization_fee_cache,
+                account_contention_cache,
             ),
         }
     }
@@ -419,6 +427,7 @@ impl BankingStage {
         connection_cache: Arc<ConnectionCache>,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        account_contention_cache: &Arc<AccountContentionCache>,
     ) -> Self {
         assert!(num_threads >= MIN_TOTAL_THREADS);
         // Single thread to generate entries from many banks.
@@ -481,6 +490,7 @@ impl BankingStage {
                     committer.clone(),
                     transaction_recorder.clone(),
                     log_messages_bytes_limit,
+                    account_contention_cache.clone(),
                     forwarder,
                     unprocessed_transaction_storage,
                 )
@@ -503,6 +513,7 @@ impl BankingStage {
         connection_cache: Arc<ConnectionCache>,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        account_contention_cache: &Arc<AccountContentionCache>,
     ) -> Self {
         assert!(num_threads >= MIN_TOTAL_THREADS);
         // Single thread to generate entries from many banks.
@@ -536,6 +547,7 @@ impl BankingStage {
                 committer.clone(),
                 transaction_recorder.clone(),
                 log_messages_bytes_limit,
+                account_contention_cache.clone(),
                 Forwarder::new(
                     poh_recorder.clone(),
                     bank_forks.clone(),
@@ -568,6 +580,7 @@ impl BankingStage {
                     poh_recorder.read().unwrap().new_recorder(),
                     QosService::new(id),
                     log_messages_bytes_limit,
+                    account_contention_cache.clone(),
                 ),
                 finished_work_sender.clone(),
                 poh_recorder.read().unwrap().new_leader_bank_notifier(),
@@ -619,6 +632,7 @@ impl BankingStage {
         committer: Committer,
         transaction_recorder: TransactionRecorder,
         log_messages_bytes_limit: Option<usize>,
+        account_contention_cache: Arc<AccountContentionCache>,
         forwarder: Forwarder,
         unprocessed_transaction_storage: UnprocessedTransactionStorage,
     ) -> JoinHandle<()> {
@@ -628,6 +642,7 @@ impl BankingStage {
             transaction_recorder,
             QosService::new(id),
             log_messages_bytes_limit,
+            account_contention_cache,
         );
 
         Builder::new()
@@ -869,6 +884,7 @@ mod tests {
                 Arc::new(ConnectionCache::new("connection_cache_test")),
                 bank_forks,
                 &Arc::new(PrioritizationFeeCache::new(0u64)),
+                &Arc::default(),
             );
             drop(non_vote_sender);
             drop(tpu_vote_sender);
@@ -924,6 +940,7 @@ mod tests {
                 Arc::new(ConnectionCache::new("connection_cache_test")),
                 bank_forks,
                 &Arc::new(PrioritizationFeeCache::new(0u64)),
+                &Arc::default(),
             );
             trace!("sending bank");
             drop(non_vote_sender);
@@ -1003,6 +1020,7 @@ mod tests {
                 Arc::new(ConnectionCache::new("connection_cache_test")),
                 bank_forks,
                 &Arc::new(PrioritizationFeeCache::new(0u64)),
+                &Arc::default(),
             );
 
             // fund another account so we can send 2 good transactions in a single batch.
@@ -1173,6 +1191,7 @@ mod tests {
                     Arc::new(ConnectionCache::new("connection_cache_test")),
                     bank_forks,
                     &Arc::new(PrioritizationFeeCache::new(0u64)),
+                    &Arc::default(),
                 );
 
                 // wait for banking_stage to eat the packets
@@ -1364,6 +1383,7 @@ mod tests {
                 Arc::new(ConnectionCache::new("connection_cache_test")),
                 bank_forks,
                 &Arc::new(PrioritizationFeeCache::new(0u64)),
+                &Arc::default(),
             );
 
             let keypairs = (0..100).map(|_| Keypair::new()).collect_vec();
@@ -189,6 +189,7 @@ impl ConsumeWorkerMetrics {
         ProcessTransactionBatchOutput {
             cost_model_throttled_transactions_count,
             cost_model_us,
+            account_contention: _,
             execute_and_commit_transactions_output,
         }: &ProcessTransactionBatchOutput,
     ) {
@@ -687,7 +688,13 @@ mod tests {
             replay_vote_sender,
             Arc::new(PrioritizationFeeCache::new(0u64)),
         );
-        let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+        let consumer = Consumer::new(
+            committer,
+            recorder,
+            QosService::new(1),
+            None,
+            Arc::default(),
+        );
 
         let (consume_sender, consume_receiver) = unbounded();
         let (consumed_sender, consumed_receiver) = unbounded();
@@ -13,6 +13,7 @@ use {
         transaction_error_metrics::TransactionErrorMetrics,
         transaction_results::TransactionCheckResult,
     },
+    solana_cost_model::{cost_model::CostModel, transaction_cost::TransactionCost},
     solana_ledger::token_balances::collect_token_balances,
     solana_measure::{measure::Measure, measure_us},
     solana_poh::poh_recorder::{
@@ -23,6 +24,9 @@ use {
         compute_budget_processor::process_compute_budget_instructions, timings::ExecuteTimings,
     },
     solana_runtime::{
+        account_contention_cache::{
+            AccountContention, AccountContentionCache, AccountContentionMap,
+        },
         accounts::validate_fee_payer,
         bank::{Bank, LoadAndExecuteTransactionsOutput},
         transaction_batch::TransactionBatch,
@@ -49,6 +53,8 @@ pub struct ProcessTransactionBatchOutput {
     pub(crate) cost_model_throttled_transactions_count: usize,
     // Amount of time spent running the cost model
     pub(crate) cost_model_us: u64,
+    // Accounts that caused transactions to be retried for lock conflicts or account cost limits
+    pub(crate) account_contention: AccountContentionMap,
     pub execute_and_commit_transactions_output: ExecuteAndCommitTransactionsOutput,
 }
 
@@ -76,6 +82,7 @@ pub struct Consumer {
     transaction_recorder: TransactionRecorder,
     qos_service: QosService,
     log_messages_bytes_limit: Option<usize>,
+    account_contention_cache: Arc<AccountContentionCache>,
 }
 
 impl Consumer {
@@ -84,12 +91,14 @@ impl Consumer {
         transaction_recorder: TransactionRecorder,
         qos_service: QosService,
         log_messages_bytes_limit: Option<usize>,
+        account_contention_cache: Arc<AccountContentionCache>,
     ) -> Self {
         Self {
             committer,
             transaction_recorder,
             qos_service,
             log_messages_bytes_limit,
+            account_contention_cache,
         }
     }
 
@@ -290,6 +299,7 @@ impl Consumer {
         let mut total_cost_model_us: u64 = 0;
         let mut total_execute_and_commit_timings = LeaderExecuteAndCommitTimings::default();
         let mut total_error_counters = TransactionErrorMetrics::default();
+        let mut total_account_contention = AccountContentionMap::default();
         let mut reached_max_poh_height = false;
         while chunk_start != transactions.len() {
             let chunk_end = std::cmp::min(
@@ -305,6 +315,7 @@ impl Consumer {
             let ProcessTransactionBatchOutput {
                 cost_model_throttled_transactions_count: new_cost_model_throttled_transactions_count,
                 cost_model_us: new_cost_model_us,
+                account_contention: new_account_contention,
                 execute_and_commit_transactions_output,
             } = process_transaction_batch_output;
             saturating_add_assign!(
@@ -312,6 +323,7 @@ impl Consumer {
                 new_cost_model_throttled_transactions_count
             );
             saturating_add_assign!(total_cost_model_us, new_cost_model_us);
+            total_account_contention.accumulate(&new_account_contention);
 
             let ExecuteAndCommitTransactionsOutput {
                 transactions_attempted_execution_count: new_transactions_attempted_execution_count,
@@ -390,6 +402,7 @@ impl Consumer {
             cost_model_us: total_cost_model_us,
             execute_and_commit_timings: total_execute_and_commit_timings,
             error_counters: total_error_counters,
+            account_contention: total_account_contention,
         }
     }
 
@@ -491,6 +504,16 @@ impl Consumer {
             })
         ));
 
+        // Lock conflicts must be looked up while the batch still holds its locks
+        let account_contention = Self::collect_account_contention(
+            bank,
+            txs,
+            &transaction_qos_cost_results,
+            batch.lock_results(),
+        );
+        self.account_contention_cache
+            .record(bank.slot(), &account_contention);
+
         // retryable_txs includes AccountInUse, WouldExceedMaxBlockCostLimit
         // WouldExceedMaxAccountCostLimit, WouldExceedMaxVoteCostLimit
         // and WouldExceedMaxAccountDataCostLimit
@@ -553,10 +576,56 @@ impl Consumer {
         ProcessTransactionBatchOutput {
             cost_model_throttled_transactions_count,
             cost_model_us,
+            account_contention,
             execute_and_commit_transactions_output,
         }
     }
 
+    /// Attributes the transactions not locked for lock conflicts or account cost limits
+    /// to the writable accounts they contend for
+    fn collect_account_contention(
+        bank: &Bank,
+        txs: &[SanitizedTransaction],
+        transaction_qos_cost_results: &[transaction::Result<TransactionCost>],
+        lock_results: &[transaction::Result<()>],
+    ) -> AccountContentionMap {
+        let mut account_contention = AccountContentionMap::default();
+        for ((tx, cost_result), lock_result) in txs
+            .iter()
+            .zip(transaction_qos_cost_results)
+            .zip(lock_results)
+        {
+            let (contended_accounts, contention) = match (lock_result, cost_result) {
+                (Err(TransactionError::AccountInUse), Ok(cost)) => (
+                    bank.get_conflicting_account_locks(tx),
+                    AccountContention {
+                        account_in_use_retries: 1,
+                        blocked_compute_units: cost.sum(),
+                        ..AccountContention::default()
+                    },
+                ),
+                (Err(TransactionError::WouldExceedMaxAccountCostLimit), _) => {
+                    let cost = CostModel::calculate_cost(tx, &bank.feature_set);
+                    (
+                        bank.read_cost_tracker()
+                            .unwrap()
+                            .get_accounts_exceeding_cost_limit(&cost),
+                        AccountContention {
+                            account_cost_limit_retries: 1,
+                            blocked_compute_units: cost.sum(),
+                            ..AccountContention::default()
+                        },
+                    )
+                }
+                _ => continue,
+            };
+            for pubkey in contended_accounts {
+                account_contention.add(pubkey, &contention);
+            }
+        }
+        account_contention
+    }
+
     fn execute_and_commit_transactions_locked(
         &self,
         bank: &Arc<Bank>,
@@ -584,6 +653,7 @@ impl Consumer {
                 transaction_status_sender_enabled,
                 transaction_status_sender_enabled,
                 transaction_status_sender_enabled,
+                false, // enable_execution_trace
                 &mut execute_and_commit_timings.execute_timings,
                 None, // account_overrides
                 self.log_messages_bytes_limit,
@@ -902,7 +972,13 @@ mod tests {
             replay_vote_sender,
             Arc::new(PrioritizationFeeCache::new(0u64)),
         );
-        let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+        let consumer = Consumer::new(
+            committer,
+            recorder,
+            QosService::new(1),
+            None,
+            Arc::default(),
+        );
         let process_transactions_summary =
             consumer.process_transactions(&bank, &Instant::now(), &transactions);
 
@@ -1075,7 +1151,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             let process_transactions_batch_output =
                 consumer.process_and_record_transactions(&bank, &transactions, 0);
@@ -1259,7 +1341,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             let process_transactions_batch_output =
                 consumer.process_and_record_transactions(&bank, &transactions, 0);
@@ -1360,7 +1448,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             let process_transactions_batch_output =
                 consumer.process_and_record_transactions(&bank, &transactions, 0);
@@ -1449,7 +1543,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             let get_block_cost = || bank.read_cost_tracker().unwrap().block_cost();
             let get_tx_count = || bank.read_cost_tracker().unwrap().transaction_count();
@@ -1603,7 +1703,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             let process_transactions_batch_output =
                 consumer.process_and_record_transactions(&bank, &transactions, 0);
@@ -1735,6 +1841,7 @@ mod tests {
             committed_transactions_with_successful_result_count,
             failed_commit_count,
             retryable_transaction_indexes,
+            account_contention,
             ..
         } = execute_transactions_with_dummy_poh_service(bank, transactions);
 
@@ -1750,6 +1857,15 @@ mod tests {
             retryable_transaction_indexes,
             (1..transactions_count - 1).collect::<Vec<usize>>()
         );
+
+        // The retried transactions were blocked by the mint's write lock
+        assert_eq!(
+            account_contention
+                .get(&mint_keypair.pubkey())
+                .unwrap()
+                .account_in_use_retries,
+            (transactions_count - 2) as u64
+        );
     }
 
     #[test]
@@ -1799,7 +1915,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder.clone(), QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder.clone(),
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             let process_transactions_summary =
                 consumer.process_transactions(&bank, &Instant::now(), &transactions);
@@ -1926,7 +2048,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             let _ = consumer.process_and_record_transactions(&bank, &transactions, 0);
 
@@ -2070,7 +2198,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             let _ = consumer.process_and_record_transactions(&bank, &[sanitized_tx.clone()], 0);
 
@@ -2130,7 +2264,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             // When the working bank in poh_recorder is None, no packets should be processed (consume will not be called)
             assert!(!poh_recorder.read().unwrap().has_bank());
@@ -2208,7 +2348,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             // When the working bank in poh_recorder is None, no packets should be processed
             assert!(!poh_recorder.read().unwrap().has_bank());
@@ -2260,7 +2406,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             // When the working bank in poh_recorder is None, no packets should be processed (consume will not be called)
             assert!(!poh_recorder.read().unwrap().has_bank());
@@ -2385,7 +2537,13 @@ mod tests {
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
             );
-            let consumer = Consumer::new(committer, recorder, QosService::new(1), None);
+            let consumer = Consumer::new(
+                committer,
+                recorder,
+                QosService::new(1),
+                None,
+                Arc::default(),
+            );
 
             // When the working bank in poh_recorder is None, no packets should be processed (consume will not be called)
             assert!(!poh_recorder.read().unwrap().has_bank());
@@ -6,10 +6,14 @@ use {
     },
     solana_accounts_db::transaction_error_metrics::*,
     solana_poh::poh_recorder::BankStart,
+    solana_runtime::account_contention_cache::AccountContentionMap,
     solana_sdk::{clock::Slot, saturating_add_assign},
     std::time::Instant,
 };
 
+// Number of most contended accounts reported for each leader slot
+const NUM_REPORTED_CONTENDED_ACCOUNTS: usize = 10;
+
 /// A summary of what happened to transactions passed to the execution pipeline.
 /// Transactions can
 /// 1) Did not even make it to execution due to being filtered out by things like AccountInUse
@@ -53,6 +57,9 @@ pub(crate) struct ProcessTransactionsSummary {
 
     // Breakdown of all the transaction errors from transactions passed for execution
     pub error_counters: TransactionErrorMetrics,
+
+    // Accounts that caused transactions to be retried for lock conflicts or account cost limits
+    pub account_contention: AccountContentionMap,
 }
 
 // Metrics describing packets ingested/processed in various parts of BankingStage during this
@@ -306,6 +313,8 @@ pub(crate) struct LeaderSlotMetrics {
 
     timing_metrics: LeaderSlotTimingMetrics,
 
+    account_contention: AccountContentionMap,
+
     // Used by tests to check if the `self.report()` method was called
     is_reported: bool,
 }
@@ -319,6 +328,7 @@ impl LeaderSlotMetrics {
             transaction_error_metrics: TransactionErrorMetrics::new(),
             vote_packet_count_metrics: VotePacketCountMetrics::new(),
             timing_metrics: LeaderSlotTimingMetrics::new(bank_creation_time),
+            account_contention: AccountContentionMap::default(),
             is_reported: false,
         }
     }
@@ -330,6 +340,36 @@ impl LeaderSlotMetrics {
         self.transaction_error_metrics.report(self.id, self.slot);
         self.packet_count_metrics.report(self.id, self.slot);
         self.vote_packet_count_metrics.report(self.id, self.slot);
+        self.report_account_contention();
+    }
+
+    fn report_account_contention(&self) {
+        for (pubkey, contention) in self
+            .account_contention
+            .most_contended(NUM_REPORTED_CONTENDED_ACCOUNTS)
+        {
+            datapoint_info!(
+                "banking_stage-leader_slot_account_contention",
+                ("id", self.id, i64),
+                ("slot", self.slot, i64),
+                ("account", pubkey.to_string(), String),
+                (
+                    "account_in_use_retries",
+                    contention.account_in_use_retries,
+                    i64
+                ),
+                (
+                    "account_cost_limit_retries",
+                    contention.account_cost_limit_retries,
+                    i64
+                ),
+                (
+                    "blocked_compute_units",
+                    contention.blocked_compute_units,
+                    i64
+                )
+            );
+        }
     }
 
     /// Returns `Some(self.slot)` if the metrics have been reported, otherwise returns None
@@ -478,6 +518,7 @@ impl LeaderSlotMetricsTracker {
                 cost_model_us,
                 ref execute_and_commit_timings,
                 error_counters,
+                account_contention,
                 ..
             } = process_transactions_summary;
 
@@ -558,6 +599,10 @@ impl LeaderSlotMetricsTracker {
                 .timing_metrics
                 .execute_and_commit_timings
                 .accumulate(execute_and_commit_timings);
+
+            leader_slot_metrics
+                .account_contention
+                .accumulate(account_contention);
         }
     }
 
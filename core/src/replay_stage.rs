@@ -3038,10 +3038,19 @@ impl ReplayStage {
                 }
                 Self::record_rewards(bank, rewards_recorder_sender);
                 if let Some(ref block_metadata_notifier) = block_metadata_notifier {
-                    let parent_blockhash = bank
-                        .parent()
+                    let parent = bank.parent();
+                    let parent_blockhash = parent
+                        .as_ref()
                         .map(|bank| bank.last_blockhash())
                         .unwrap_or_default();
+                    let parent_bank_hash =
+                        parent.as_ref().map(|bank| bank.hash()).unwrap_or_default();
+                    let accounts_delta_hash = bank
+                        .rc
+                        .accounts
+                        .accounts_db
+                        .get_accounts_delta_hash(bank.slot())
+                        .map(|accounts_delta_hash| accounts_delta_hash.0.to_string());
                     block_metadata_notifier.notify_block_metadata(
                         bank.parent_slot(),
                         &parent_blockhash.to_string(),
@@ -3052,6 +3061,9 @@ impl ReplayStage {
                         Some(bank.block_height()),
                         bank.executed_transaction_count(),
                         r_replay_progress.num_entries as u64,
+                        &bank_hash.to_string(),
+                        &parent_bank_hash.to_string(),
+                        accounts_delta_hash.as_deref(),
                     )
                 }
                 bank_complete_time.stop();
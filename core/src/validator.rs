@@ -39,7 +39,8 @@ use {
     solana_client::connection_cache::{ConnectionCache, Protocol},
     solana_entry::poh::compute_hash_time_ns,
     solana_geyser_plugin_manager::{
-        geyser_plugin_service::GeyserPluginService, GeyserPluginManagerRequest,
+        geyser_plugin_manager::InProcessGeyserPlugin, geyser_plugin_service::GeyserPluginService,
+        GeyserPluginManagerRequest,
     },
     solana_gossip::{
         cluster_info::{
@@ -87,6 +88,7 @@ use {
         transaction_status_service::TransactionStatusService,
     },
     solana_runtime::{
+        account_contention_cache::AccountContentionCache,
         accounts_background_service::{
             AbsRequestHandlers, AbsRequestSender, AccountsBackgroundService, DroppedSlotsReceiver,
             PrunedBanksRequestHandler, SnapshotRequestHandler,
@@ -209,6 +211,8 @@ pub struct ValidatorConfig {
     pub rpc_config: JsonRpcConfig,
     /// Specifies which plugins to start up with
     pub on_start_geyser_plugin_config_files: Option<Vec<PathBuf>>,
+    /// Plugins linked into the validator to start up with
+    pub on_start_in_process_geyser_plugins: Vec<InProcessGeyserPlugin>,
     pub rpc_addrs: Option<(SocketAddr, SocketAddr)>, // (JsonRpc, JsonRpcPubSub)
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: SnapshotConfig,
@@ -281,6 +285,7 @@ impl Default for ValidatorConfig {
             account_shrink_paths: None,
             rpc_config: JsonRpcConfig::default(),
             on_start_geyser_plugin_config_files: None,
+            on_start_in_process_geyser_plugins: Vec::new(),
             rpc_addrs: None,
             pubsub_config: PubSubConfig::default(),
             snapshot_config: SnapshotConfig::new_load_only(),
@@ -513,23 +518,28 @@ impl Validator {
 
         let exit = Arc::new(AtomicBool::new(false));
 
-        let geyser_plugin_service =
-            if let Some(geyser_plugin_config_files) = &config.on_start_geyser_plugin_config_files {
-                let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
-                bank_notification_senders.push(confirmed_bank_sender);
-                let rpc_to_plugin_manager_receiver_and_exit =
-                    rpc_to_plugin_manager_receiver.map(|receiver| (receiver, exit.clone()));
-                Some(
-                    GeyserPluginService::new_with_receiver(
-                        confirmed_bank_receiver,
-                        geyser_plugin_config_files,
-                        rpc_to_plugin_manager_receiver_and_exit,
-                    )
-                    .map_err(|err| format!("Failed to load the Geyser plugin: {err:?}"))?,
+        let geyser_plugin_service = if config.on_start_geyser_plugin_config_files.is_some()
+            || !config.on_start_in_process_geyser_plugins.is_empty()
+        {
+            let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
+            bank_notification_senders.push(confirmed_bank_sender);
+            let rpc_to_plugin_manager_receiver_and_exit =
+                rpc_to_plugin_manager_receiver.map(|receiver| (receiver, exit.clone()));
+            Some(
+                GeyserPluginService::new_with_receiver(
+                    confirmed_bank_receiver,
+                    config
+                        .on_start_geyser_plugin_config_files
+                        .as_deref()
+                        .unwrap_or_default(),
+                    &config.on_start_in_process_geyser_plugins,
+                    rpc_to_plugin_manager_receiver_and_exit,
                 )
-            } else {
-                None
-            };
+                .map_err(|err| format!("Failed to load the Geyser plugin: {err:?}"))?,
+            )
+        } else {
+            None
+        };
 
         if config.voting_disabled {
             warn!("voting disabled");
@@ -816,6 +826,10 @@ impl Validator {
         // (by both replay stage and banking stage)
         let prioritization_fee_cache = Arc::new(PrioritizationFeeCache::default());
 
+        // write-lock contention of recent leader slots is recorded by banking stage and
+        // read by RPC
+        let account_contention_cache = Arc::new(AccountContentionCache::default());
+
         match &config.block_verification_method {
             BlockVerificationMethod::BlockstoreProcessor => {
                 info!("no scheduler pool is installed for block verification...");
@@ -1008,6 +1022,7 @@ impl Validator {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache.clone(),
+                account_contention_cache.clone(),
             )?;
 
             (
@@ -1370,6 +1385,7 @@ impl Validator {
             tracer_thread,
             tpu_enable_udp,
             &prioritization_fee_cache,
+            &account_contention_cache,
             config.block_production_method.clone(),
             config.generator_config.clone(),
         );
@@ -137,6 +137,31 @@ impl CostTracker {
         self.block_cost
     }
 
+    /// Returns the writable accounts of `tx_cost` whose accumulated cost would exceed the
+    /// account cost limit with it. Empty if the transaction alone exceeds the limit.
+    pub fn get_accounts_exceeding_cost_limit(&self, tx_cost: &TransactionCost) -> Vec<Pubkey> {
+        let cost = tx_cost.sum();
+        if cost > self.account_cost_limit {
+            return vec![];
+        }
+        tx_cost
+            .writable_accounts()
+            .iter()
+            .filter(|account_key| {
+                self.cost_by_writable_accounts
+                    .get(account_key)
+                    .map_or(false, |chained_cost| {
+                        chained_cost.saturating_add(cost) > self.account_cost_limit
+                    })
+            })
+            .copied()
+            .collect()
+    }
+
+    pub fn block_cost_limit(&self) -> u64 {
+        self.block_cost_limit
+    }
+
     pub fn transaction_count(&self) -> u64 {
         self.transaction_count
     }
@@ -515,6 +540,10 @@ mod tests {
         // but no more sapce on the same chain (same signer account)
         {
             assert!(testee.would_fit(&tx_cost2).is_err());
+            assert_eq!(
+                testee.get_accounts_exceeding_cost_limit(&tx_cost2),
+                vec![mint_keypair.pubkey()]
+            );
         }
     }
 
@@ -5,6 +5,7 @@
 use {
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
+        hash::Hash,
         signature::Signature,
         transaction::SanitizedTransaction,
     },
@@ -159,6 +160,49 @@ pub struct ReplicaTransactionInfoV2<'a> {
     pub index: usize,
 }
 
+/// Information about a transaction, including its compute budget and fees
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaTransactionInfoV3<'a> {
+    /// The first signature of the transaction, used for identifying the transaction.
+    pub signature: &'a Signature,
+
+    /// The hash of the transaction message.
+    pub message_hash: &'a Hash,
+
+    /// Indicates if the transaction is a simple vote transaction.
+    pub is_vote: bool,
+
+    /// The sanitized transaction.
+    pub transaction: &'a SanitizedTransaction,
+
+    /// Metadata of the transaction status.
+    pub transaction_status_meta: &'a TransactionStatusMeta,
+
+    /// The transaction's index in the block
+    pub index: usize,
+
+    /// The compute unit limit requested by the transaction, or the default limit of its
+    /// instructions
+    pub compute_unit_limit: u32,
+
+    /// The compute unit price requested by the transaction, in micro-lamports
+    pub compute_unit_price: u64,
+
+    /// The prioritization fee paid by the transaction, in lamports
+    pub prioritization_fee: u64,
+
+    /// The cost of the transaction in the cost model, in compute units
+    pub cost_units: u64,
+
+    /// Size of the account data loaded for the transaction, programs included
+    pub loaded_accounts_data_size: usize,
+
+    /// The blockhash of the parent block. It is the latest blockhash of the bank while the
+    /// transaction executes, the bank only registers its own blockhash at its last tick.
+    pub parent_blockhash: &'a Hash,
+}
+
 /// A wrapper to future-proof ReplicaTransactionInfo handling.
 /// If there were a change to the structure of ReplicaTransactionInfo,
 /// there would be new enum entry for the newer version, forcing
@@ -167,6 +211,7 @@ pub struct ReplicaTransactionInfoV2<'a> {
 pub enum ReplicaTransactionInfoVersions<'a> {
     V0_0_1(&'a ReplicaTransactionInfo<'a>),
     V0_0_2(&'a ReplicaTransactionInfoV2<'a>),
+    V0_0_3(&'a ReplicaTransactionInfoV3<'a>),
 }
 
 #[derive(Clone, Debug)]
@@ -251,11 +296,33 @@ pub struct ReplicaBlockInfoV3<'a> {
     pub entry_count: u64,
 }
 
+/// Extending ReplicaBlockInfo by sending the bank hashes and the accounts delta hash.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaBlockInfoV4<'a> {
+    pub parent_slot: Slot,
+    pub parent_blockhash: &'a str,
+    pub slot: Slot,
+    pub blockhash: &'a str,
+    pub rewards: &'a [Reward],
+    pub block_time: Option<UnixTimestamp>,
+    pub block_height: Option<u64>,
+    pub executed_transaction_count: u64,
+    pub entry_count: u64,
+    /// The hash of the frozen bank
+    pub bank_hash: &'a str,
+    /// The hash of the parent bank, the state the block was executed on
+    pub parent_bank_hash: &'a str,
+    /// The hash of the accounts written in this slot
+    pub accounts_delta_hash: Option<&'a str>,
+}
+
 #[repr(u32)]
 pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_1(&'a ReplicaBlockInfo<'a>),
     V0_0_2(&'a ReplicaBlockInfoV2<'a>),
     V0_0_3(&'a ReplicaBlockInfoV3<'a>),
+    V0_0_4(&'a ReplicaBlockInfoV4<'a>),
 }
 
 /// Errors returned by plugin calls
@@ -432,6 +499,13 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         false
     }
 
+    /// Check if the plugin handles the V0_0_3 transaction info
+    /// Default is false -- transactions are notified as V0_0_2,
+    /// return true to also receive their compute budget and fees.
+    fn transaction_info_v3_enabled(&self) -> bool {
+        false
+    }
+
     /// Check if the plugin is interested in entry data
     /// Default is false -- if the plugin is interested in
     /// entry data, return true.
@@ -6,7 +6,7 @@ use {
     log::*,
     solana_accounts_db::stake_rewards::RewardInfo,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
-        ReplicaBlockInfoV3, ReplicaBlockInfoVersions,
+        ReplicaBlockInfoV4, ReplicaBlockInfoVersions,
     },
     solana_measure::measure::Measure,
     solana_metrics::*,
@@ -32,6 +32,9 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
         block_height: Option<u64>,
         executed_transaction_count: u64,
         entry_count: u64,
+        bank_hash: &str,
+        parent_bank_hash: &str,
+        accounts_delta_hash: Option<&str>,
     ) {
         let plugin_manager = self.plugin_manager.read().unwrap();
         if plugin_manager.plugins.is_empty() {
@@ -51,8 +54,11 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
                 block_height,
                 executed_transaction_count,
                 entry_count,
+                bank_hash,
+                parent_bank_hash,
+                accounts_delta_hash,
             );
-            let block_info = ReplicaBlockInfoVersions::V0_0_3(&block_info);
+            let block_info = ReplicaBlockInfoVersions::V0_0_4(&block_info);
             match plugin.notify_block_metadata(block_info) {
                 Err(err) => {
                     error!(
@@ -96,6 +102,7 @@ impl BlockMetadataNotifierImpl {
             .collect()
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn build_replica_block_info<'a>(
         parent_slot: u64,
         parent_blockhash: &'a str,
@@ -106,8 +113,11 @@ impl BlockMetadataNotifierImpl {
         block_height: Option<u64>,
         executed_transaction_count: u64,
         entry_count: u64,
-    ) -> ReplicaBlockInfoV3<'a> {
-        ReplicaBlockInfoV3 {
+        bank_hash: &'a str,
+        parent_bank_hash: &'a str,
+        accounts_delta_hash: Option<&'a str>,
+    ) -> ReplicaBlockInfoV4<'a> {
+        ReplicaBlockInfoV4 {
             parent_slot,
             parent_blockhash,
             slot,
@@ -117,6 +127,9 @@ impl BlockMetadataNotifierImpl {
             block_height,
             executed_transaction_count,
             entry_count,
+            bank_hash,
+            parent_bank_hash,
+            accounts_delta_hash,
         }
     }
 
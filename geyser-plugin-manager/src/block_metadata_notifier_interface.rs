@@ -19,6 +19,9 @@ pub trait BlockMetadataNotifier {
         block_height: Option<u64>,
         executed_transaction_count: u64,
         entry_count: u64,
+        bank_hash: &str,
+        parent_bank_hash: &str,
+        accounts_delta_hash: Option<&str>,
     );
 }
 
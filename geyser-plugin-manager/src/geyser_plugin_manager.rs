@@ -5,8 +5,10 @@ use {
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
     std::{
+        fmt,
         ops::{Deref, DerefMut},
-        path::Path,
+        path::{Path, PathBuf},
+        sync::Arc,
     },
 };
 
@@ -43,10 +45,42 @@ impl DerefMut for LoadedGeyserPlugin {
     }
 }
 
+/// Creates a plugin linked into the validator, in place of the `_create_plugin`
+/// symbol of a plugin library
+pub type GeyserPluginFactory = Arc<dyn Fn() -> Box<dyn GeyserPlugin> + Send + Sync>;
+
+/// A plugin linked into the validator, loaded at startup with its config file
+#[derive(Clone)]
+pub struct InProcessGeyserPlugin {
+    pub create_plugin: GeyserPluginFactory,
+    pub config_file: PathBuf,
+}
+
+impl InProcessGeyserPlugin {
+    pub fn new<F>(create_plugin: F, config_file: impl Into<PathBuf>) -> Self
+    where
+        F: Fn() -> Box<dyn GeyserPlugin> + Send + Sync + 'static,
+    {
+        Self {
+            create_plugin: Arc::new(create_plugin),
+            config_file: config_file.into(),
+        }
+    }
+}
+
+impl fmt::Debug for InProcessGeyserPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InProcessGeyserPlugin")
+            .field("config_file", &self.config_file)
+            .finish_non_exhaustive()
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct GeyserPluginManager {
     pub plugins: Vec<LoadedGeyserPlugin>,
-    libs: Vec<Library>,
+    /// Library of the plugin at the same index, `None` for an in-process plugin
+    libs: Vec<Option<Library>>,
 }
 
 impl GeyserPluginManager {
@@ -158,7 +192,39 @@ impl GeyserPluginManager {
             })?;
         let name = new_plugin.name().to_string();
         self.plugins.push(new_plugin);
-        self.libs.push(new_lib);
+        self.libs.push(Some(new_lib));
+
+        Ok(name)
+    }
+
+    /// Load a plugin linked into the validator. The plugin can be unloaded
+    /// through the admin RPC, but not reloaded as it has no library.
+    pub(crate) fn load_in_process_plugin(
+        &mut self,
+        in_process_plugin: &InProcessGeyserPlugin,
+    ) -> Result<String, GeyserPluginManagerError> {
+        let config_file = in_process_plugin
+            .config_file
+            .to_str()
+            .ok_or(GeyserPluginManagerError::InvalidPluginPath)?;
+        let mut new_plugin = LoadedGeyserPlugin::new((in_process_plugin.create_plugin)(), None);
+        if self
+            .plugins
+            .iter()
+            .any(|plugin| plugin.name().eq(new_plugin.name()))
+        {
+            return Err(GeyserPluginManagerError::PluginAlreadyLoaded(
+                new_plugin.name().to_string(),
+            ));
+        }
+
+        // The plugin shares the logger of the validator, no need to set it up
+        new_plugin
+            .on_load(config_file, false)
+            .map_err(|err| GeyserPluginManagerError::PluginStartError(err.to_string()))?;
+        let name = new_plugin.name().to_string();
+        self.plugins.push(new_plugin);
+        self.libs.push(None);
 
         Ok(name)
     }
@@ -201,6 +267,15 @@ impl GeyserPluginManager {
                 data: None,
             });
         };
+        if self.libs[idx].is_none() {
+            return Err(jsonrpc_core::error::Error {
+                code: ErrorCode::InvalidRequest,
+                message: String::from(
+                    "The plugin you requested to reload is linked into the validator",
+                ),
+                data: None,
+            });
+        }
 
         // Unload and drop current plugin first in case plugin requires exclusive access to resource,
         // such as a particular port or database.
@@ -238,7 +313,7 @@ impl GeyserPluginManager {
             // On success, push plugin and library
             Ok(()) => {
                 self.plugins.push(new_plugin);
-                self.libs.push(new_lib);
+                self.libs.push(Some(new_lib));
             }
 
             // On failure, return error
@@ -440,7 +515,8 @@ pub(crate) fn load_plugin_from_config(
 mod tests {
     use {
         crate::geyser_plugin_manager::{
-            GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+            GeyserPluginManager, GeyserPluginManagerError, InProcessGeyserPlugin,
+            LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
         },
         libloading::Library,
         solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
@@ -497,7 +573,7 @@ mod tests {
         let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
         plugin.on_load(config, false).unwrap();
         plugin_manager_lock.plugins.push(plugin);
-        plugin_manager_lock.libs.push(lib);
+        plugin_manager_lock.libs.push(Some(lib));
         // plugin_manager_lock.libs.push(lib);
         assert_eq!(plugin_manager_lock.plugins[0].name(), DUMMY_NAME);
         plugin_manager_lock.plugins[0].name();
@@ -532,12 +608,12 @@ mod tests {
         let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin, TESTPLUGIN_CONFIG);
         plugin.on_load(config, false).unwrap();
         plugin_manager_lock.plugins.push(plugin);
-        plugin_manager_lock.libs.push(lib);
+        plugin_manager_lock.libs.push(Some(lib));
         // Second
         let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin2, TESTPLUGIN2_CONFIG);
         plugin.on_load(config, false).unwrap();
         plugin_manager_lock.plugins.push(plugin);
-        plugin_manager_lock.libs.push(lib);
+        plugin_manager_lock.libs.push(Some(lib));
 
         // Check that both plugins are returned in the list
         let plugins = plugin_manager_lock.list_plugins().unwrap();
@@ -561,4 +637,29 @@ mod tests {
         assert!(unload_result.is_ok());
         assert_eq!(plugin_manager_lock.plugins.len(), 0);
     }
+
+    #[test]
+    fn test_in_process_plugin() {
+        let mut plugin_manager = GeyserPluginManager::new();
+        let in_process_plugin = InProcessGeyserPlugin::new(|| Box::new(TestPlugin), DUMMY_CONFIG);
+        assert_eq!(
+            plugin_manager
+                .load_in_process_plugin(&in_process_plugin)
+                .unwrap(),
+            DUMMY_NAME
+        );
+        assert!(matches!(
+            plugin_manager.load_in_process_plugin(&in_process_plugin),
+            Err(GeyserPluginManagerError::PluginAlreadyLoaded(_))
+        ));
+
+        // The plugin has no library to reload from
+        assert!(plugin_manager
+            .reload_plugin(DUMMY_NAME, TESTPLUGIN_CONFIG)
+            .is_err());
+        assert_eq!(plugin_manager.plugins.len(), 1);
+
+        assert!(plugin_manager.unload_plugin(DUMMY_NAME).is_ok());
+        assert!(plugin_manager.plugins.is_empty());
+    }
 }
@@ -4,7 +4,9 @@ use {
         block_metadata_notifier::BlockMetadataNotifierImpl,
         block_metadata_notifier_interface::BlockMetadataNotifierArc,
         entry_notifier::EntryNotifierImpl,
-        geyser_plugin_manager::{GeyserPluginManager, GeyserPluginManagerRequest},
+        geyser_plugin_manager::{
+            GeyserPluginManager, GeyserPluginManagerRequest, InProcessGeyserPlugin,
+        },
         slot_status_notifier::SlotStatusNotifierImpl,
         slot_status_observer::SlotStatusObserver,
         transaction_notifier::TransactionNotifierImpl,
@@ -56,12 +58,19 @@ impl GeyserPluginService {
         confirmed_bank_receiver: Receiver<SlotNotification>,
         geyser_plugin_config_files: &[PathBuf],
     ) -> Result<Self, GeyserPluginServiceError> {
-        Self::new_with_receiver(confirmed_bank_receiver, geyser_plugin_config_files, None)
+        Self::new_with_receiver(
+            confirmed_bank_receiver,
+            geyser_plugin_config_files,
+            &[],
+            None,
+        )
     }
 
+    /// `in_process_plugins` are loaded after the plugins of the config files
     pub fn new_with_receiver(
         confirmed_bank_receiver: Receiver<SlotNotification>,
         geyser_plugin_config_files: &[PathBuf],
+        in_process_plugins: &[InProcessGeyserPlugin],
         rpc_to_plugin_manager_receiver_and_exit: Option<(
             Receiver<GeyserPluginManagerRequest>,
             Arc<AtomicBool>,
@@ -76,6 +85,11 @@ impl GeyserPluginService {
         for geyser_plugin_config_file in geyser_plugin_config_files {
             Self::load_plugin(&mut plugin_manager, geyser_plugin_config_file)?;
         }
+        for in_process_plugin in in_process_plugins {
+            plugin_manager
+                .load_in_process_plugin(in_process_plugin)
+                .map_err(|e| GeyserPluginServiceError::FailedToLoadPlugin(e.into()))?;
+        }
 
         let account_data_notifications_enabled =
             plugin_manager.account_data_notifications_enabled();
@@ -3,12 +3,14 @@ use {
     crate::geyser_plugin_manager::GeyserPluginManager,
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
-        ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
+        ReplicaTransactionInfoV2, ReplicaTransactionInfoV3, ReplicaTransactionInfoVersions,
     },
     solana_measure::measure::Measure,
     solana_metrics::*,
-    solana_rpc::transaction_notifier_interface::TransactionNotifier,
-    solana_sdk::{clock::Slot, signature::Signature, transaction::SanitizedTransaction},
+    solana_rpc::transaction_notifier_interface::{TransactionComputeInfo, TransactionNotifier},
+    solana_sdk::{
+        clock::Slot, hash::Hash, signature::Signature, transaction::SanitizedTransaction,
+    },
     solana_transaction_status::TransactionStatusMeta,
     std::sync::{Arc, RwLock},
 };
@@ -29,6 +31,8 @@ impl TransactionNotifier for TransactionNotifierImpl {
         signature: &Signature,
         transaction_status_meta: &TransactionStatusMeta,
         transaction: &SanitizedTransaction,
+        compute_info: &TransactionComputeInfo,
+        parent_blockhash: &Hash,
     ) {
         let mut measure = Measure::start("geyser-plugin-notify_plugins_of_transaction_info");
         let transaction_log_info = Self::build_replica_transaction_info(
@@ -36,7 +40,11 @@ impl TransactionNotifier for TransactionNotifierImpl {
             signature,
             transaction_status_meta,
             transaction,
+            compute_info,
+            parent_blockhash,
         );
+        let transaction_log_info_v2 =
+            Self::build_replica_transaction_info_v2(&transaction_log_info);
 
         let plugin_manager = self.plugin_manager.read().unwrap();
 
@@ -48,10 +56,13 @@ impl TransactionNotifier for TransactionNotifierImpl {
             if !plugin.transaction_notifications_enabled() {
                 continue;
             }
-            match plugin.notify_transaction(
-                ReplicaTransactionInfoVersions::V0_0_2(&transaction_log_info),
-                slot,
-            ) {
+            // Plugins written against V0_0_2 keep receiving it
+            let transaction_info = if plugin.transaction_info_v3_enabled() {
+                ReplicaTransactionInfoVersions::V0_0_3(&transaction_log_info)
+            } else {
+                ReplicaTransactionInfoVersions::V0_0_2(&transaction_log_info_v2)
+            };
+            match plugin.notify_transaction(transaction_info, slot) {
                 Err(err) => {
                     error!(
                         "Failed to notify transaction, error: ({}) to plugin {}",
@@ -87,13 +98,34 @@ impl TransactionNotifierImpl {
         signature: &'a Signature,
         transaction_status_meta: &'a TransactionStatusMeta,
         transaction: &'a SanitizedTransaction,
-    ) -> ReplicaTransactionInfoV2<'a> {
-        ReplicaTransactionInfoV2 {
+        compute_info: &TransactionComputeInfo,
+        parent_blockhash: &'a Hash,
+    ) -> ReplicaTransactionInfoV3<'a> {
+        ReplicaTransactionInfoV3 {
             index,
             signature,
+            message_hash: transaction.message_hash(),
             is_vote: transaction.is_simple_vote_transaction(),
             transaction,
             transaction_status_meta,
+            compute_unit_limit: compute_info.compute_unit_limit,
+            compute_unit_price: compute_info.compute_unit_price,
+            prioritization_fee: compute_info.prioritization_fee,
+            cost_units: compute_info.cost_units,
+            loaded_accounts_data_size: compute_info.loaded_accounts_data_size,
+            parent_blockhash,
+        }
+    }
+
+    fn build_replica_transaction_info_v2<'a>(
+        transaction_info: &ReplicaTransactionInfoV3<'a>,
+    ) -> ReplicaTransactionInfoV2<'a> {
+        ReplicaTransactionInfoV2 {
+            index: transaction_info.index,
+            signature: transaction_info.signature,
+            is_vote: transaction_info.is_vote,
+            transaction: transaction_info.transaction,
+            transaction_status_meta: transaction_info.transaction_status_meta,
         }
     }
 }
@@ -1,6 +1,9 @@
 #![allow(clippy::arithmetic_side_effects)]
 use {
-    crate::{args::*, bigtable::*, blockstore::*, ledger_path::*, ledger_utils::*, program::*},
+    crate::{
+        args::*, bigtable::*, blockstore::*, ledger_path::*, ledger_utils::*, pods::*,
+        program::*,
+    },
     clap::{
         crate_description, crate_name, value_t, value_t_or_exit, values_t_or_exit, App,
         AppSettings, Arg, ArgMatches, SubCommand,
@@ -27,8 +30,9 @@ use {
     },
     solana_cli_output::{CliAccount, CliAccountNewConfig, OutputFormat},
     solana_core::{
+        banking_simulation::{BankingSimulator, BankingTraceEvents},
         system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
-        validator::BlockVerificationMethod,
+        validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_cost_model::{cost_model::CostModel, cost_tracker::CostTracker},
     solana_ledger::{
@@ -91,6 +95,7 @@ mod blockstore;
 mod ledger_path;
 mod ledger_utils;
 mod output;
+mod pods;
 mod program;
 
 fn parse_encoding_format(matches: &ArgMatches<'_>) -> UiAccountEncoding {
@@ -1447,7 +1452,62 @@ fn main() {
                         ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("simulate-block-production")
+                .about(
+                    "Replay a banking trace through the banking stage to produce the blocks of \
+                     the traced slots, starting from the bank before the first simulated slot",
+                )
+                .arg(&max_genesis_archive_unpacked_size_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_verify_refcounts)
+                .arg(&accounts_db_skip_initial_hash_calc_arg)
+                .arg(&accounts_db_test_skip_rewrites_but_include_in_bank_hash)
+                .arg(&use_snapshot_archives_at_startup)
+                .arg(
+                    Arg::with_name("banking_trace_events")
+                        .long("banking-trace-events")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Banking trace directory recorded by the validator"),
+                )
+                .arg(
+                    Arg::with_name("first_simulated_slot")
+                        .long("first-simulated-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .required(true)
+                        .help(
+                            "First slot to produce. The ledger is processed up to the slot \
+                             before it, whose hash event must be in the banking trace",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("num_simulated_slots")
+                        .long("num-simulated-slots")
+                        .value_name("NUM")
+                        .validator(is_parsable::<u64>)
+                        .takes_value(true)
+                        .help(
+                            "Number of slots to produce [default: up to the last slot frozen in \
+                             the banking trace]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("block_production_method")
+                        .long("block-production-method")
+                        .value_name("METHOD")
+                        .takes_value(true)
+                        .possible_values(BlockProductionMethod::cli_names())
+                        .help(BlockProductionMethod::cli_message()),
+                ),
+        )
         .program_subcommand()
+        .pods_subcommand()
         .get_matches();
 
     info!("{} {}", crate_name!(), solana_version::version!());
@@ -1467,6 +1527,7 @@ fn main() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
+        ("pods", Some(arg_matches)) => pods_process_command(&ledger_path, arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
         ("analyze-storage", Some(_))
@@ -2796,6 +2857,105 @@ fn main() {
                         }
                     }
                 }
+                ("simulate-block-production", Some(arg_matches)) => {
+                    let mut process_options = parse_process_options(&ledger_path, arg_matches);
+                    let first_simulated_slot =
+                        value_t_or_exit!(arg_matches, "first_simulated_slot", Slot);
+                    process_options.halt_at_slot = Some(first_simulated_slot.saturating_sub(1));
+                    let num_simulated_slots =
+                        value_t!(arg_matches, "num_simulated_slots", u64).ok();
+                    let block_production_method = value_t!(
+                        arg_matches,
+                        "block_production_method",
+                        BlockProductionMethod
+                    )
+                    .unwrap_or_default();
+                    let banking_trace_events = BankingTraceEvents::load(&PathBuf::from(
+                        value_t_or_exit!(arg_matches, "banking_trace_events", String),
+                    ))
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to load the banking trace: {err}");
+                        exit(1);
+                    });
+
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let blockstore = Arc::new(open_blockstore(
+                        &ledger_path,
+                        arg_matches,
+                        get_access_type(&process_options),
+                    ));
+                    let (bank_forks, _) = load_and_process_ledger_or_exit(
+                        arg_matches,
+                        &genesis_config,
+                        blockstore.clone(),
+                        process_options,
+                        snapshot_archive_path,
+                        incremental_snapshot_archive_path,
+                    );
+
+                    let simulator = BankingSimulator::new(
+                        banking_trace_events,
+                        first_simulated_slot,
+                        num_simulated_slots,
+                    );
+                    let report = simulator
+                        .simulate(
+                            &genesis_config,
+                            bank_forks,
+                            blockstore,
+                            block_production_method,
+                        )
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to simulate block production: {err}");
+                            exit(1);
+                        });
+
+                    let mut diverged_count = 0;
+                    for block in &report.blocks {
+                        let divergence = match block.diverged() {
+                            Some(true) => {
+                                diverged_count += 1;
+                                "diverged"
+                            }
+                            Some(false) => "matched",
+                            None => "not traced",
+                        };
+                        let recorded_transaction_count = block
+                            .recorded_transaction_count
+                            .map(|count| count.to_string())
+                            .unwrap_or_else(|| "-".to_string());
+                        println!(
+                            "slot {} (parent {}, leader {}): {} transactions in {} entries \
+                             ({} in the ledger), cost {} ({:.1}% of the limit), bank hash {}, {}",
+                            block.slot,
+                            block.parent_slot,
+                            block.leader,
+                            block.transaction_count,
+                            block.entry_count,
+                            recorded_transaction_count,
+                            block.block_cost,
+                            block.packing_efficiency() * 100.0,
+                            block.bank_hash,
+                            divergence,
+                        );
+                    }
+                    let block_count = report.blocks.len().max(1) as f64;
+                    println!(
+                        "{} blocks from {} packets in {} batches, average packing efficiency \
+                         {:.1}%, {} blocks diverged from the trace",
+                        report.blocks.len(),
+                        report.packet_count,
+                        report.packet_batch_count,
+                        report
+                            .blocks
+                            .iter()
+                            .map(|block| block.packing_efficiency())
+                            .sum::<f64>()
+                            / block_count
+                            * 100.0,
+                        diverged_count,
+                    );
+                }
                 ("", _) => {
                     eprintln!("{}", matches.usage());
                     exit(1);
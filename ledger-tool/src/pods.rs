@@ -0,0 +1,440 @@
+//! The `pods` subcommand
+
+use {
+    crate::{ledger_path::canonicalize_ledger_path, ledger_utils::open_blockstore},
+    clap::{value_t, value_t_or_exit, App, AppSettings, Arg, ArgMatches, SubCommand},
+    log::*,
+    solana_clap_utils::input_validators::is_slot,
+    solana_ledger::{blockstore::Blockstore, blockstore_options::AccessType},
+    solana_sdk::clock::Slot,
+    std::{
+        collections::{BTreeMap, HashSet},
+        path::{Path, PathBuf},
+    },
+    svm_geyser::{
+        geyser_plugin::{GeyserPluginRocksDB, PodStoreConfig},
+        pod::{transactions_root, SealReason, SlotCommitment, SlotCommitmentTracker},
+        rocksdb_client::RocksDBConnection,
+        slot_buffer::BufferedTransaction,
+        txn_select::TxnStoreStruct,
+        txn_settle::SvmTxnSelector,
+    },
+};
+
+/// Secondary instance directory of the pod store used by `pods verify`
+const VERIFY_SECONDARY_DIR: &str = "secondary_ledger_tool";
+
+pub trait PodsSubCommand {
+    fn pods_subcommand(self) -> Self;
+}
+
+impl PodsSubCommand for App<'_, '_> {
+    fn pods_subcommand(self) -> Self {
+        let config_arg = Arg::with_name("geyser_plugin_config")
+            .long("geyser-plugin-config")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(true)
+            .help(
+                "Config file of the svm geyser plugin, provides the transaction selector, the \
+                 sealing policy and the pod store location",
+            );
+        let pod_store_arg = Arg::with_name("pod_store")
+            .long("pod-store")
+            .value_name("DIR")
+            .takes_value(true)
+            .help("Pod store to use instead of the one of the plugin config");
+        let to_slot_arg = Arg::with_name("to_slot")
+            .long("to-slot")
+            .validator(is_slot)
+            .value_name("SLOT")
+            .takes_value(true);
+
+        self.subcommand(
+            SubCommand::with_name("pods")
+                .about("Rebuild and verify the pods of the svm geyser plugin")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("rebuild")
+                        .about(
+                            "Append the selected transactions of rooted blocks to the pod store. \
+                             Requires the transaction status columns of the ledger, the \
+                             validator must not be running on the pod store. Transactions \
+                             already in the pod store are skipped, account writes and timeout \
+                             sealing are not reproduced",
+                        )
+                        .arg(&config_arg)
+                        .arg(&pod_store_arg)
+                        .arg(
+                            Arg::with_name("without_account_writes")
+                                .long("without-account-writes")
+                                .takes_value(false)
+                                .help(
+                                    "Rebuild even though the plugin config enables \
+                                     `account_diffs`. The ledger doesn't keep account writes, \
+                                     so the rebuilt records and transactions roots differ from \
+                                     the ones the plugin writes",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("from_slot")
+                                .long("from-slot")
+                                .validator(is_slot)
+                                .value_name("SLOT")
+                                .takes_value(true)
+                                .required(true)
+                                .help("First slot to rebuild"),
+                        )
+                        .arg(
+                            to_slot_arg
+                                .clone()
+                                .help("Last slot to rebuild [default: latest root]"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("verify")
+                        .about(
+                            "Compare the transactions of the pod store with the rooted blocks \
+                             of the ledger",
+                        )
+                        .arg(&config_arg)
+                        .arg(&pod_store_arg)
+                        .arg(
+                            Arg::with_name("from_slot")
+                                .long("from-slot")
+                                .validator(is_slot)
+                                .value_name("SLOT")
+                                .takes_value(true)
+                                .help(
+                                    "First slot to verify, the slots of pruned pods are skipped \
+                                     [default: lowest slot of the ledger]",
+                                ),
+                        )
+                        .arg(to_slot_arg.help(
+                            "Last slot to verify [default: last slot processed into the pod \
+                             store]",
+                        )),
+                ),
+        )
+    }
+}
+
+pub fn pods_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    let ledger_path = canonicalize_ledger_path(ledger_path);
+    let result = match matches.subcommand() {
+        ("rebuild", Some(arg_matches)) => {
+            let (config, pod_store_path) = pod_store_config(arg_matches);
+            let blockstore = open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let from_slot = value_t_or_exit!(arg_matches, "from_slot", Slot);
+            let to_slot =
+                value_t!(arg_matches, "to_slot", Slot).unwrap_or_else(|_| blockstore.max_root());
+            let without_account_writes = arg_matches.is_present("without_account_writes");
+            rebuild_pods(
+                &blockstore,
+                &config,
+                &pod_store_path,
+                from_slot,
+                to_slot,
+                without_account_writes,
+            )
+        }
+        ("verify", Some(arg_matches)) => {
+            let (config, pod_store_path) = pod_store_config(arg_matches);
+            let blockstore = open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let from_slot = value_t!(arg_matches, "from_slot", Slot)
+                .unwrap_or_else(|_| blockstore.lowest_slot());
+            let to_slot = value_t!(arg_matches, "to_slot", Slot).ok();
+            verify_pods(&blockstore, &config, &pod_store_path, from_slot, to_slot)
+        }
+        _ => unreachable!(),
+    };
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn pod_store_config(matches: &ArgMatches<'_>) -> (PodStoreConfig, PathBuf) {
+    let config_file = value_t_or_exit!(matches, "geyser_plugin_config", String);
+    let config = GeyserPluginRocksDB::load_pod_store_config(&config_file).unwrap_or_else(|err| {
+        eprintln!("Unable to read {config_file}: {err}");
+        std::process::exit(1);
+    });
+    let pod_store_path = value_t!(matches, "pod_store", String)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| config.path.clone());
+    (config, pod_store_path)
+}
+
+/// The transactions of a rooted block selected for pods, as the plugin records them
+fn selected_transactions(
+    blockstore: &Blockstore,
+    selector: &SvmTxnSelector,
+    slot: Slot,
+) -> Result<(SlotCommitment, Vec<TxnStoreStruct>), String> {
+    let block = blockstore
+        .get_rooted_block(slot, false)
+        .map_err(|err| format!("Unable to read the block of slot {slot}: {err}"))?;
+    let bank_hash = blockstore
+        .get_bank_hash(slot)
+        .ok_or_else(|| format!("Bank hash of slot {slot} not found"))?;
+    let parent_bank_hash = blockstore
+        .get_bank_hash(block.parent_slot)
+        .ok_or_else(|| format!("Bank hash of slot {} not found", block.parent_slot))?;
+    let commitment = SlotCommitment {
+        slot,
+        blockhash: block.blockhash,
+        bank_hash: bank_hash.to_string(),
+        parent_bank_hash: parent_bank_hash.to_string(),
+        // Not kept in the ledger
+        accounts_delta_hash: None,
+    };
+
+    let mut transactions = vec![];
+    for (index, transaction) in block.transactions.iter().enumerate() {
+        let sanitized =
+            TxnStoreStruct::sanitize_ledger_transaction(transaction).map_err(|err| {
+                format!("Unable to sanitize transaction {index} of slot {slot}: {err}")
+            })?;
+        if selector.is_transaction_selected(&sanitized, &transaction.meta) {
+            transactions.push(TxnStoreStruct::from_sanitized_transaction(
+                &sanitized,
+                &transaction.meta,
+                slot,
+                index,
+            ));
+        }
+    }
+    Ok((commitment, transactions))
+}
+
+fn rebuild_pods(
+    blockstore: &Blockstore,
+    config: &PodStoreConfig,
+    pod_store_path: &Path,
+    from_slot: Slot,
+    to_slot: Slot,
+    without_account_writes: bool,
+) -> Result<(), String> {
+    // Rebuilt records have no account writes, their pods wouldn't match the plugin's
+    if config.account_diffs {
+        if !without_account_writes {
+            return Err(
+                "The plugin config enables `account_diffs`, which the ledger can't reproduce. \
+                 Pass --without-account-writes to rebuild pods without account writes"
+                    .to_string(),
+            );
+        }
+        warn!(
+            "Rebuilding pods without account writes, their records and transactions roots \
+             differ from the ones written by the plugin"
+        );
+    }
+    let client = RocksDBConnection::open(pod_store_path, false)
+        .map_err(|err| format!("Unable to open {}: {err}", pod_store_path.display()))?;
+    client
+        .check_and_create_keys()
+        .and_then(|_| client.migrate_legacy_batches())
+        .map_err(|err| format!("Unable to prepare {}: {err}", pod_store_path.display()))?;
+    if let Some(last_processed_slot) = client
+        .get_last_processed_slot()
+        .map_err(|err| format!("Unable to read the last processed slot: {err}"))?
+    {
+        if from_slot <= last_processed_slot {
+            warn!(
+                "The pod store already processed slots up to {last_processed_slot}, the \
+                 transactions it holds are skipped"
+            );
+        }
+    }
+
+    let mut slot_commitments = SlotCommitmentTracker::default();
+    let mut slot_count = 0;
+    let mut transaction_count = 0;
+    let mut sealed_pod_count = 0;
+    let rooted_slots = blockstore
+        .rooted_slot_iterator(from_slot)
+        .map_err(|err| format!("Unable to read the roots of the ledger: {err}"))?;
+    for slot in rooted_slots.take_while(|slot| *slot <= to_slot) {
+        let (commitment, transactions) =
+            selected_transactions(blockstore, &config.transaction_selector, slot)?;
+        // Same order as the plugin: the bank is frozen before its slot is rooted
+        slot_commitments.insert(commitment);
+        let transactions = transactions
+            .into_iter()
+            .map(|transaction| BufferedTransaction {
+                index: transaction.index as usize,
+                signature: transaction.signature.clone(),
+                record: transaction.to_record(),
+            })
+            .collect::<Vec<_>>();
+        transaction_count += transactions.len();
+
+        // The plugin only saves the slots holding selected transactions
+        let mut sealed_headers = if transactions.is_empty() {
+            vec![]
+        } else {
+            client
+                .save_transaction_batch(
+                    transactions,
+                    slot,
+                    &config.sealing_policy,
+                    &slot_commitments,
+                )
+                .map_err(|err| format!("Unable to save the transactions of slot {slot}: {err}"))?
+        };
+        if config.sealing_policy.seal_on_root {
+            sealed_headers.extend(
+                client
+                    .seal_open_batch(SealReason::Rooted, &slot_commitments)
+                    .map_err(|err| format!("Unable to seal the open pod at slot {slot}: {err}"))?,
+            );
+        }
+        for header in &sealed_headers {
+            slot_commitments.track_pending(header);
+        }
+        sealed_pod_count += sealed_headers.len();
+        slot_count += 1;
+    }
+
+    println!(
+        "Rebuilt {transaction_count} transactions of {slot_count} rooted slots from {from_slot} \
+         to {to_slot} into {}, sealed {sealed_pod_count} pods",
+        pod_store_path.display()
+    );
+    Ok(())
+}
+
+fn verify_pods(
+    blockstore: &Blockstore,
+    config: &PodStoreConfig,
+    pod_store_path: &Path,
+    from_slot: Slot,
+    to_slot: Option<Slot>,
+) -> Result<(), String> {
+    let client = RocksDBConnection::open_secondary(
+        pod_store_path,
+        &pod_store_path.join(VERIFY_SECONDARY_DIR),
+    )
+    .map_err(|err| format!("Unable to open {}: {err}", pod_store_path.display()))?;
+    let to_slot = match to_slot {
+        Some(to_slot) => to_slot,
+        None => client
+            .get_last_processed_slot()
+            .map_err(|err| format!("Unable to read the last processed slot: {err}"))?
+            .ok_or("The pod store has not processed any slot yet")?,
+    };
+    // The transactions of pruned pods are gone from the store
+    let from_slot = match client
+        .get_pruned_slot()
+        .map_err(|err| format!("Unable to read the last pruned slot: {err}"))?
+    {
+        Some(pruned_slot) if pruned_slot >= from_slot => {
+            println!("Skipping slots {from_slot} to {pruned_slot} of the pruned pods");
+            pruned_slot + 1
+        }
+        _ => from_slot,
+    };
+    let in_range = |slot: Slot| (from_slot..=to_slot).contains(&slot);
+
+    let mut expected = BTreeMap::new();
+    let rooted_slots = blockstore
+        .rooted_slot_iterator(from_slot)
+        .map_err(|err| format!("Unable to read the roots of the ledger: {err}"))?;
+    for slot in rooted_slots.take_while(|slot| *slot <= to_slot) {
+        let (_, transactions) =
+            selected_transactions(blockstore, &config.transaction_selector, slot)?;
+        for transaction in transactions {
+            expected.insert(transaction.signature.clone(), transaction);
+        }
+    }
+
+    let mut mismatch_count = 0;
+    let latest_pod_number = client
+        .get_latest_batch_number()
+        .map_err(|err| format!("Unable to read the latest pod: {err}"))?;
+    let pruned_pod_number = client
+        .get_pruned_pod_number()
+        .map_err(|err| format!("Unable to read the last pruned pod: {err}"))?;
+    let mut pods = vec![];
+    for pod_number in pruned_pod_number + 1..=latest_pod_number {
+        let header = client
+            .get_pod_header(pod_number)
+            .map_err(|err| format!("Unable to read the header of pod {pod_number}: {err}"))?
+            .ok_or_else(|| format!("Header of pod {pod_number} not found"))?;
+        if header.last_slot < from_slot || header.first_slot > to_slot {
+            continue;
+        }
+        let records = client
+            .get_pod_records(pod_number)
+            .map_err(|err| format!("Unable to read pod {pod_number}: {err}"))?;
+        if transactions_root(&records).to_string() != header.transactions_root {
+            println!("Pod {pod_number} does not match its transactions root");
+            mismatch_count += 1;
+        }
+        pods.push((pod_number, records));
+    }
+    if let Some(open_pod) = client
+        .get_open_pod_records()
+        .map_err(|err| format!("Unable to read the open pod: {err}"))?
+    {
+        pods.push(open_pod);
+    }
+
+    let mut verified_count = 0;
+    let mut stored_signatures = HashSet::new();
+    for (pod_number, records) in pods {
+        for (index, record) in records.iter().enumerate() {
+            let mut stored = TxnStoreStruct::from_record(record).map_err(|err| {
+                format!("Unable to decode record {index} of pod {pod_number}: {err}")
+            })?;
+            if !in_range(stored.slot()) {
+                continue;
+            }
+            verified_count += 1;
+            let signature = stored.signature.clone();
+            if !stored_signatures.insert(signature.clone()) {
+                println!("Transaction {signature} is stored again in pod {pod_number}");
+                mismatch_count += 1;
+                continue;
+            }
+            // Account writes are not kept in the ledger
+            stored.set_account_writes(vec![]);
+            match expected.remove(&signature) {
+                Some(transaction) if transaction == stored => {}
+                Some(_) => {
+                    println!("Transaction {signature} of pod {pod_number} differs from the ledger");
+                    mismatch_count += 1;
+                }
+                None => {
+                    println!(
+                        "Transaction {signature} of pod {pod_number} is not a selected \
+                         transaction of a rooted block"
+                    );
+                    mismatch_count += 1;
+                }
+            }
+        }
+    }
+    for transaction in expected.values() {
+        println!(
+            "Transaction {} of slot {} is missing from the pods",
+            transaction.signature,
+            transaction.slot()
+        );
+        mismatch_count += 1;
+    }
+
+    println!(
+        "Verified {verified_count} stored transactions of slots {from_slot} to {to_slot}, found \
+         {mismatch_count} differences"
+    );
+    if mismatch_count > 0 {
+        return Err(format!(
+            "{} does not match the ledger",
+            pod_store_path.display()
+        ));
+    }
+    Ok(())
+}
@@ -1831,6 +1831,9 @@ pub struct TransactionStatusBatch {
     pub token_balances: TransactionTokenBalancesSet,
     pub rent_debits: Vec<RentDebits>,
     pub transaction_indexes: Vec<usize>,
+    /// Latest blockhash of the bank when the batch was executed, the blockhash of
+    /// the parent block
+    pub parent_blockhash: Hash,
 }
 
 #[derive(Clone, Debug)]
@@ -1850,6 +1853,8 @@ impl TransactionStatusSender {
         transaction_indexes: Vec<usize>,
     ) {
         let slot = bank.slot();
+        // The bank may register its own blockhash before the batch is processed
+        let parent_blockhash = bank.last_blockhash();
 
         if let Err(e) = self
             .sender
@@ -1867,6 +1872,7 @@ impl TransactionStatusSender {
                 token_balances,
                 rent_debits,
                 transaction_indexes,
+                parent_blockhash,
             }))
         {
             trace!(
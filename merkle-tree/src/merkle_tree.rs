@@ -36,6 +36,18 @@ impl<'a> ProofEntry<'a> {
         assert!(left_sibling.is_none() ^ right_sibling.is_none());
         Self(target, left_sibling, right_sibling)
     }
+
+    pub fn target(&self) -> &'a Hash {
+        self.0
+    }
+
+    pub fn left_sibling(&self) -> Option<&'a Hash> {
+        self.1
+    }
+
+    pub fn right_sibling(&self) -> Option<&'a Hash> {
+        self.2
+    }
 }
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -46,6 +58,10 @@ impl<'a> Proof<'a> {
         self.0.push(entry)
     }
 
+    pub fn entries(&self) -> &[ProofEntry<'a>] {
+        &self.0
+    }
+
     pub fn verify(&self, candidate: Hash) -> bool {
         let result = self.0.iter().try_fold(candidate, |candidate, pe| {
             let lsib = pe.1.unwrap_or(&candidate);
@@ -139,6 +155,11 @@ impl MerkleTree {
         self.nodes.iter().last()
     }
 
+    /// Hash of the leaf at `index`, the candidate verified by its proof
+    pub fn get_leaf(&self, index: usize) -> Option<&Hash> {
+        self.nodes[..self.leaf_count].get(index)
+    }
+
     pub fn find_path(&self, index: usize) -> Option<Proof> {
         if index >= self.leaf_count {
             return None;
@@ -234,7 +255,10 @@ mod tests {
             let hash = hash_leaf!(s);
             let path = mt.find_path(i).unwrap();
             assert!(path.verify(hash));
+            assert_eq!(mt.get_leaf(i), Some(&hash));
+            assert_eq!(path.entries().last().map(ProofEntry::target), mt.get_root());
         }
+        assert_eq!(mt.get_leaf(TEST.len()), None);
     }
 
     #[test]
@@ -0,0 +1,306 @@
+//! Tree of the instructions invoked by a transaction, recorded for simulations
+
+use {
+    serde::{Deserialize, Serialize},
+    solana_sdk::{
+        account::ReadableAccount,
+        hash::{hash, Hash},
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        transaction_context::{IndexOfAccount, TransactionContext},
+    },
+};
+
+/// Accounts with more data are compared by hash instead of being copied at
+/// every invocation level, their changes are not diffed
+pub const MAX_DIFFED_DATA_LEN: usize = 64 * 1024;
+
+/// Bytes of an account's data changed by an instruction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDataDiff {
+    /// Offset of the first changed byte
+    pub offset: usize,
+    /// The changed bytes before the instruction
+    pub before: Vec<u8>,
+    /// The changed bytes after the instruction, of a different length if the
+    /// account was resized
+    pub after: Vec<u8>,
+}
+
+impl AccountDataDiff {
+    /// Returns the smallest range of bytes that differ, `None` if the data is unchanged
+    pub fn new(before: &[u8], after: &[u8]) -> Option<Self> {
+        if before == after {
+            return None;
+        }
+        let prefix_len = before
+            .iter()
+            .zip(after)
+            .take_while(|(before, after)| before == after)
+            .count();
+        let max_suffix_len = before.len().min(after.len()).saturating_sub(prefix_len);
+        let suffix_len = before
+            .iter()
+            .rev()
+            .zip(after.iter().rev())
+            .take(max_suffix_len)
+            .take_while(|(before, after)| before == after)
+            .count();
+        let changed = |data: &[u8]| {
+            data.get(prefix_len..data.len().saturating_sub(suffix_len))
+                .unwrap_or_default()
+                .to_vec()
+        };
+        Some(Self {
+            offset: prefix_len,
+            before: changed(before),
+            after: changed(after),
+        })
+    }
+}
+
+/// State change of an account passed to an instruction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TracedAccount {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+    pub lamports_before: u64,
+    pub lamports_after: u64,
+    pub data_len_before: usize,
+    pub data_len_after: usize,
+    pub data_changed: bool,
+    /// The changed bytes, only for data up to `MAX_DIFFED_DATA_LEN` bytes
+    pub data_diff: Option<AccountDataDiff>,
+}
+
+/// An executed instruction and the instructions it invoked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TracedInstruction {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+    /// Height of the instruction in the invocation stack, top level instructions
+    /// are `solana_sdk::instruction::TRANSACTION_LEVEL_STACK_HEIGHT`
+    pub stack_height: usize,
+    /// Compute units consumed by the instruction, its inner instructions included
+    pub compute_units_consumed: u64,
+    pub result: Result<(), InstructionError>,
+    /// The accounts of the instruction, without duplicates
+    pub accounts: Vec<TracedAccount>,
+    pub inner_instructions: Vec<TracedInstruction>,
+}
+
+enum PendingData {
+    Copy(Vec<u8>),
+    Hash(Hash),
+}
+
+impl PendingData {
+    fn new(data: &[u8]) -> Self {
+        if data.len() > MAX_DIFFED_DATA_LEN {
+            Self::Hash(hash(data))
+        } else {
+            Self::Copy(data.to_vec())
+        }
+    }
+
+    /// Returns whether the data changed to `after`, and the diff if it was copied
+    fn compare(&self, after: &[u8]) -> (bool, Option<AccountDataDiff>) {
+        match self {
+            Self::Copy(before) => {
+                let data_diff = AccountDataDiff::new(before, after);
+                (data_diff.is_some(), data_diff)
+            }
+            Self::Hash(before) => (*before != hash(after), None),
+        }
+    }
+}
+
+struct PendingAccount {
+    index_in_transaction: IndexOfAccount,
+    is_writable: bool,
+    lamports: u64,
+    data_len: usize,
+    data: PendingData,
+}
+
+struct PendingInstruction {
+    program_id: Pubkey,
+    data: Vec<u8>,
+    stack_height: usize,
+    accounts: Vec<PendingAccount>,
+    inner_instructions: Vec<TracedInstruction>,
+}
+
+/// Records the instructions invoked by a transaction, with the state of their
+/// accounts before and after each of them
+#[derive(Default)]
+pub struct ExecutionTrace {
+    stack: Vec<PendingInstruction>,
+    instructions: Vec<TracedInstruction>,
+}
+
+impl ExecutionTrace {
+    /// Records the start of the instruction on top of the invocation stack
+    pub fn start_instruction(&mut self, transaction_context: &TransactionContext) {
+        let Ok(instruction_context) = transaction_context.get_current_instruction_context() else {
+            return;
+        };
+        let program_id = instruction_context
+            .get_last_program_key(transaction_context)
+            .copied()
+            .unwrap_or_default();
+        let mut accounts: Vec<PendingAccount> = Vec::new();
+        for index_in_instruction in 0..instruction_context.get_number_of_instruction_accounts() {
+            let (Ok(index_in_transaction), Ok(is_writable)) = (
+                instruction_context
+                    .get_index_of_instruction_account_in_transaction(index_in_instruction),
+                instruction_context.is_instruction_account_writable(index_in_instruction),
+            ) else {
+                continue;
+            };
+            if accounts
+                .iter()
+                .any(|account| account.index_in_transaction == index_in_transaction)
+            {
+                continue;
+            }
+            let Some(account) = transaction_context
+                .get_account_at_index(index_in_transaction)
+                .ok()
+                .and_then(|account| account.try_borrow().ok().map(|account| account.clone()))
+            else {
+                continue;
+            };
+            // Programs are not modified by the instructions invoking them
+            if account.executable() {
+                continue;
+            }
+            accounts.push(PendingAccount {
+                index_in_transaction,
+                is_writable,
+                lamports: account.lamports(),
+                data_len: account.data().len(),
+                data: PendingData::new(account.data()),
+            });
+        }
+        self.stack.push(PendingInstruction {
+            program_id,
+            data: instruction_context.get_instruction_data().to_vec(),
+            stack_height: transaction_context.get_instruction_context_stack_height(),
+            accounts,
+            inner_instructions: Vec::new(),
+        });
+    }
+
+    /// Records the end of the last started instruction
+    pub fn end_instruction(
+        &mut self,
+        transaction_context: &TransactionContext,
+        compute_units_consumed: u64,
+        result: &Result<(), InstructionError>,
+    ) {
+        let Some(PendingInstruction {
+            program_id,
+            data,
+            stack_height,
+            accounts,
+            inner_instructions,
+        }) = self.stack.pop()
+        else {
+            return;
+        };
+        let accounts = accounts
+            .into_iter()
+            .filter_map(|account| {
+                let pubkey = *transaction_context
+                    .get_key_of_account_at_index(account.index_in_transaction)
+                    .ok()?;
+                let after = transaction_context
+                    .get_account_at_index(account.index_in_transaction)
+                    .ok()?
+                    .try_borrow()
+                    .ok()?;
+                let (data_changed, data_diff) = account.data.compare(after.data());
+                Some(TracedAccount {
+                    pubkey,
+                    is_writable: account.is_writable,
+                    lamports_before: account.lamports,
+                    lamports_after: after.lamports(),
+                    data_len_before: account.data_len,
+                    data_len_after: after.data().len(),
+                    data_changed,
+                    data_diff,
+                })
+            })
+            .collect();
+        let instruction = TracedInstruction {
+            program_id,
+            data,
+            stack_height,
+            compute_units_consumed,
+            result: result.clone(),
+            accounts,
+            inner_instructions,
+        };
+        match self.stack.last_mut() {
+            Some(caller) => caller.inner_instructions.push(instruction),
+            None => self.instructions.push(instruction),
+        }
+    }
+
+    /// Returns the traced top level instructions
+    pub fn into_instructions(self) -> Vec<TracedInstruction> {
+        self.instructions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_data_diff() {
+        assert_eq!(AccountDataDiff::new(&[1, 2, 3], &[1, 2, 3]), None);
+        assert_eq!(
+            AccountDataDiff::new(&[1, 2, 3, 4], &[1, 5, 6, 4]),
+            Some(AccountDataDiff {
+                offset: 1,
+                before: vec![2, 3],
+                after: vec![5, 6],
+            })
+        );
+        assert_eq!(
+            AccountDataDiff::new(&[1, 2], &[1, 2, 0, 0]),
+            Some(AccountDataDiff {
+                offset: 2,
+                before: vec![],
+                after: vec![0, 0],
+            })
+        );
+        assert_eq!(
+            AccountDataDiff::new(&[7, 7, 7], &[7, 7]),
+            Some(AccountDataDiff {
+                offset: 2,
+                before: vec![7],
+                after: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_pending_data() {
+        let data = vec![1, 2, 3];
+        assert_eq!(PendingData::new(&data).compare(&data), (false, None));
+        assert_eq!(
+            PendingData::new(&data).compare(&[1, 2, 4]),
+            (true, AccountDataDiff::new(&data, &[1, 2, 4]))
+        );
+
+        let mut large_data = vec![0; MAX_DIFFED_DATA_LEN + 1];
+        let pending_data = PendingData::new(&large_data);
+        assert!(matches!(pending_data, PendingData::Hash(_)));
+        assert_eq!(pending_data.compare(&large_data), (false, None));
+        large_data[0] = 1;
+        assert_eq!(pending_data.compare(&large_data), (true, None));
+    }
+}
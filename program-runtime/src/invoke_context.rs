@@ -1,6 +1,7 @@
 use {
     crate::{
         compute_budget::ComputeBudget,
+        execution_trace::ExecutionTrace,
         ic_msg,
         loaded_programs::{
             LoadedProgram, LoadedProgramType, LoadedProgramsForTxBatch, ProgramRuntimeEnvironments,
@@ -173,6 +174,7 @@ pub struct InvokeContext<'a> {
     pub lamports_per_signature: u64,
     pub syscall_context: Vec<Option<SyscallContext>>,
     traces: Vec<Vec<[u64; 12]>>,
+    execution_trace: Option<&'a mut ExecutionTrace>,
 }
 
 impl<'a> InvokeContext<'a> {
@@ -203,6 +205,34 @@ impl<'a> InvokeContext<'a> {
             lamports_per_signature,
             syscall_context: Vec::new(),
             traces: Vec::new(),
+            execution_trace: None,
+        }
+    }
+
+    /// Records the invoked instructions into `execution_trace`
+    pub fn set_execution_trace(&mut self, execution_trace: Option<&'a mut ExecutionTrace>) {
+        self.execution_trace = execution_trace;
+    }
+
+    /// Records the start of the instruction on top of the invocation stack, if tracing
+    pub(crate) fn start_instruction_trace(&mut self) {
+        if let Some(execution_trace) = self.execution_trace.as_deref_mut() {
+            execution_trace.start_instruction(self.transaction_context);
+        }
+    }
+
+    /// Records the end of the instruction on top of the invocation stack, if tracing
+    pub(crate) fn end_instruction_trace(
+        &mut self,
+        compute_units_consumed: u64,
+        result: &Result<(), InstructionError>,
+    ) {
+        if let Some(execution_trace) = self.execution_trace.as_deref_mut() {
+            execution_trace.end_instruction(
+                self.transaction_context,
+                compute_units_consumed,
+                result,
+            );
         }
     }
 
@@ -444,10 +474,12 @@ impl<'a> InvokeContext<'a> {
             .get_next_instruction_context()?
             .configure(program_indices, instruction_accounts, instruction_data);
         self.push()?;
-        self.process_executable_chain(compute_units_consumed, timings)
-            // MUST pop if and only if `push` succeeded, independent of `result`.
-            // Thus, the `.and()` instead of an `.and_then()`.
-            .and(self.pop())
+        self.start_instruction_trace();
+        let result = self.process_executable_chain(compute_units_consumed, timings);
+        self.end_instruction_trace(*compute_units_consumed, &result);
+        // MUST pop if and only if `push` succeeded, independent of `result`.
+        // Thus, the `.and()` instead of an `.and_then()`.
+        result.and(self.pop())
     }
 
     /// Calls the instruction's program entrypoint method
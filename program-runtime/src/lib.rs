@@ -12,6 +12,7 @@ extern crate solana_metrics;
 pub use solana_rbpf;
 pub mod compute_budget;
 pub mod compute_budget_processor;
+pub mod execution_trace;
 pub mod invoke_context;
 pub mod loaded_programs;
 pub mod log_collector;
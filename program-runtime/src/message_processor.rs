@@ -1,6 +1,7 @@
 use {
     crate::{
         compute_budget::ComputeBudget,
+        execution_trace::ExecutionTrace,
         invoke_context::InvokeContext,
         loaded_programs::LoadedProgramsForTxBatch,
         log_collector::LogCollector,
@@ -56,6 +57,7 @@ impl MessageProcessor {
         blockhash: Hash,
         lamports_per_signature: u64,
         accumulated_consumed_units: &mut u64,
+        execution_trace: Option<&mut ExecutionTrace>,
     ) -> Result<(), TransactionError> {
         let mut invoke_context = InvokeContext::new(
             transaction_context,
@@ -68,6 +70,7 @@ impl MessageProcessor {
             blockhash,
             lamports_per_signature,
         );
+        invoke_context.set_execution_trace(execution_trace);
 
         debug_assert_eq!(program_indices.len(), message.instructions().len());
         for (instruction_index, ((program_id, instruction), program_indices)) in message
@@ -130,6 +133,8 @@ impl MessageProcessor {
                     })
                     .and_then(|_| {
                         invoke_context.transaction_context.push()?;
+                        invoke_context.start_instruction_trace();
+                        invoke_context.end_instruction_trace(0, &Ok(()));
                         invoke_context.transaction_context.pop()
                     })
             } else {
@@ -303,6 +308,7 @@ mod tests {
             Hash::default(),
             0,
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -352,6 +358,7 @@ mod tests {
             Hash::default(),
             0,
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -391,6 +398,7 @@ mod tests {
             Hash::default(),
             0,
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -520,6 +528,7 @@ mod tests {
             Hash::default(),
             0,
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -553,6 +562,7 @@ mod tests {
             Hash::default(),
             0,
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
 
@@ -583,6 +593,7 @@ mod tests {
             Hash::default(),
             0,
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -611,6 +622,137 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_process_message_execution_trace() {
+        declare_process_instruction!(MockBuiltin, 1, |invoke_context| {
+            let transaction_context = &invoke_context.transaction_context;
+            let instruction_context = transaction_context.get_current_instruction_context()?;
+            instruction_context
+                .try_borrow_instruction_account(transaction_context, 0)?
+                .checked_sub_lamports(1)?;
+            instruction_context
+                .try_borrow_instruction_account(transaction_context, 1)?
+                .checked_add_lamports(1)?;
+            if instruction_context.get_instruction_data() != [1] {
+                return Ok(());
+            }
+            let program_id = *instruction_context.get_last_program_key(transaction_context)?;
+            let account_metas = (0..2)
+                .map(|index_in_instruction| {
+                    let index_in_transaction = instruction_context
+                        .get_index_of_instruction_account_in_transaction(index_in_instruction)?;
+                    Ok(AccountMeta::new(
+                        *transaction_context.get_key_of_account_at_index(index_in_transaction)?,
+                        false,
+                    ))
+                })
+                .collect::<Result<Vec<_>, InstructionError>>()?;
+            invoke_context.native_invoke(
+                Instruction::new_with_bytes(program_id, &[0], account_metas).into(),
+                &[],
+            )
+        });
+
+        let from_pubkey = Pubkey::new_unique();
+        let to_pubkey = Pubkey::new_unique();
+        let mock_program_id = Pubkey::new_unique();
+        let accounts = vec![
+            (
+                from_pubkey,
+                AccountSharedData::new(100, 0, &mock_program_id),
+            ),
+            (to_pubkey, AccountSharedData::new(0, 0, &mock_program_id)),
+            (
+                mock_program_id,
+                create_loadable_account_for_test("mock_program"),
+            ),
+        ];
+        let mut transaction_context = TransactionContext::new(accounts, Rent::default(), 2, 2);
+        let mut programs_loaded_for_tx_batch = LoadedProgramsForTxBatch::default();
+        programs_loaded_for_tx_batch.replenish(
+            mock_program_id,
+            Arc::new(LoadedProgram::new_builtin(0, 0, MockBuiltin::vm)),
+        );
+        let account_keys = vec![from_pubkey, to_pubkey, mock_program_id];
+        let message =
+            SanitizedMessage::Legacy(LegacyMessage::new(Message::new_with_compiled_instructions(
+                1,
+                0,
+                1,
+                account_keys.clone(),
+                Hash::default(),
+                AccountKeys::new(&account_keys, None).compile_instructions(&[
+                    Instruction::new_with_bytes(
+                        mock_program_id,
+                        &[1],
+                        vec![
+                            AccountMeta::new(from_pubkey, true),
+                            AccountMeta::new(to_pubkey, false),
+                            AccountMeta::new_readonly(mock_program_id, false),
+                        ],
+                    ),
+                ]),
+            )));
+        let sysvar_cache = SysvarCache::default();
+        let mut programs_modified_by_tx = LoadedProgramsForTxBatch::default();
+        let mut execution_trace = ExecutionTrace::default();
+        let result = MessageProcessor::process_message(
+            &message,
+            &[vec![2]],
+            &mut transaction_context,
+            None,
+            &programs_loaded_for_tx_batch,
+            &mut programs_modified_by_tx,
+            Arc::new(FeatureSet::all_enabled()),
+            ComputeBudget::default(),
+            &mut ExecuteTimings::default(),
+            &sysvar_cache,
+            Hash::default(),
+            0,
+            &mut 0,
+            Some(&mut execution_trace),
+        );
+        assert!(result.is_ok());
+
+        let instructions = execution_trace.into_instructions();
+        let [outer] = instructions.as_slice() else {
+            panic!("expected one top level instruction: {instructions:?}");
+        };
+        assert_eq!(outer.program_id, mock_program_id);
+        assert_eq!(outer.data, vec![1]);
+        assert_eq!(outer.stack_height, 1);
+        assert_eq!(outer.compute_units_consumed, 2);
+        assert_eq!(outer.result, Ok(()));
+        // The executable program account isn't traced
+        let [from, to] = outer.accounts.as_slice() else {
+            panic!("unexpected accounts: {:?}", outer.accounts);
+        };
+        assert_eq!(
+            (from.pubkey, from.lamports_before, from.lamports_after),
+            (from_pubkey, 100, 98)
+        );
+        assert_eq!(
+            (to.pubkey, to.lamports_before, to.lamports_after),
+            (to_pubkey, 0, 2)
+        );
+
+        let [inner] = outer.inner_instructions.as_slice() else {
+            panic!(
+                "unexpected inner instructions: {:?}",
+                outer.inner_instructions
+            );
+        };
+        assert_eq!(inner.data, vec![0]);
+        assert_eq!(inner.stack_height, 2);
+        assert_eq!(inner.compute_units_consumed, 1);
+        let [from, to] = inner.accounts.as_slice() else {
+            panic!("unexpected accounts: {:?}", inner.accounts);
+        };
+        assert_eq!((from.lamports_before, from.lamports_after), (99, 98));
+        assert_eq!((to.lamports_before, to.lamports_after), (1, 2));
+        assert!(inner.inner_instructions.is_empty());
+    }
+
     #[test]
     fn test_precompile() {
         let mock_program_id = Pubkey::new_unique();
@@ -670,6 +812,7 @@ mod tests {
             Hash::default(),
             0,
             &mut 0,
+            None,
         );
 
         assert_eq!(
@@ -0,0 +1,237 @@
+//! Notify the geyser plugins of a program test of the transactions it commits
+//! and of the slots it roots
+
+use {
+    log::*,
+    solana_accounts_db::transaction_results::{
+        DurableNonceFee, TransactionExecutionDetails, TransactionExecutionResult,
+    },
+    solana_banks_server::banks_server::TransactionCommitNotifier,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, ReplicaBlockInfoV4, ReplicaBlockInfoVersions, ReplicaTransactionInfoV2,
+        ReplicaTransactionInfoVersions, SlotStatus,
+    },
+    solana_runtime::bank::{Bank, TransactionBalancesSet},
+    solana_sdk::{clock::Slot, transaction::SanitizedTransaction},
+    solana_transaction_status::{map_inner_instructions, Reward, TransactionStatusMeta},
+    std::{
+        path::PathBuf,
+        sync::{Mutex, RwLock},
+    },
+};
+
+pub(crate) struct GeyserNotifier {
+    plugins: RwLock<Vec<Box<dyn GeyserPlugin>>>,
+    /// Slot of the working bank and the index of its next transaction
+    next_transaction_index: Mutex<(Slot, usize)>,
+}
+
+impl GeyserNotifier {
+    /// Load each plugin with its config file
+    pub(crate) fn load(plugins: Vec<(Box<dyn GeyserPlugin>, PathBuf)>) -> Self {
+        let plugins = plugins
+            .into_iter()
+            .map(|(mut plugin, config_file)| {
+                let config_file = config_file
+                    .to_str()
+                    .unwrap_or_else(|| panic!("Invalid plugin config file {config_file:?}"));
+                plugin
+                    .on_load(config_file, false)
+                    .unwrap_or_else(|err| panic!("Failed to load plugin {}: {err}", plugin.name()));
+                plugin
+            })
+            .collect();
+        Self {
+            plugins: RwLock::new(plugins),
+            next_transaction_index: Mutex::default(),
+        }
+    }
+
+    /// Notify the status of a frozen bank up to its root
+    pub(crate) fn notify_rooted_bank(&self, bank: &Bank) {
+        let slot = bank.slot();
+        let parent = bank.parent();
+        let parent_blockhash = parent
+            .as_ref()
+            .map(|parent| parent.last_blockhash())
+            .unwrap_or_default()
+            .to_string();
+        let parent_bank_hash = parent
+            .as_ref()
+            .map(|parent| parent.hash())
+            .unwrap_or_default()
+            .to_string();
+        let blockhash = bank.last_blockhash().to_string();
+        let bank_hash = bank.hash().to_string();
+        let accounts_delta_hash = bank
+            .rc
+            .accounts
+            .accounts_db
+            .get_accounts_delta_hash(slot)
+            .map(|accounts_delta_hash| accounts_delta_hash.0.to_string());
+        let rewards: Vec<_> = bank
+            .rewards
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(pubkey, reward)| Reward {
+                pubkey: pubkey.to_string(),
+                lamports: reward.lamports,
+                post_balance: reward.post_balance,
+                reward_type: Some(reward.reward_type),
+                commission: reward.commission,
+            })
+            .collect();
+        let block_info = ReplicaBlockInfoV4 {
+            parent_slot: bank.parent_slot(),
+            parent_blockhash: &parent_blockhash,
+            slot,
+            blockhash: &blockhash,
+            rewards: &rewards,
+            block_time: Some(bank.clock().unix_timestamp),
+            block_height: Some(bank.block_height()),
+            executed_transaction_count: bank.executed_transaction_count(),
+            // Transactions aren't recorded into entries
+            entry_count: 0,
+            bank_hash: &bank_hash,
+            parent_bank_hash: &parent_bank_hash,
+            accounts_delta_hash: accounts_delta_hash.as_deref(),
+        };
+
+        let parent = Some(bank.parent_slot());
+        for plugin in self.plugins.read().unwrap().iter() {
+            let results = [
+                plugin.update_slot_status(slot, parent, SlotStatus::Processed),
+                plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_4(&block_info)),
+                plugin.update_slot_status(slot, parent, SlotStatus::Confirmed),
+                plugin.update_slot_status(slot, parent, SlotStatus::Rooted),
+            ];
+            for err in results.into_iter().filter_map(Result::err) {
+                error!(
+                    "Failed to notify slot {slot} to plugin {}: {err}",
+                    plugin.name()
+                );
+            }
+        }
+    }
+
+    /// Unload the plugins, they aren't notified anymore
+    pub(crate) fn unload(&self) {
+        for mut plugin in self.plugins.write().unwrap().drain(..) {
+            info!("Unloading plugin {}", plugin.name());
+            plugin.on_unload();
+        }
+    }
+
+    fn next_transaction_index(&self, slot: Slot) -> usize {
+        let mut next_transaction_index = self.next_transaction_index.lock().unwrap();
+        if next_transaction_index.0 != slot {
+            *next_transaction_index = (slot, 0);
+        }
+        let index = next_transaction_index.1;
+        next_transaction_index.1 += 1;
+        index
+    }
+}
+
+impl Drop for GeyserNotifier {
+    fn drop(&mut self) {
+        self.unload();
+    }
+}
+
+fn transaction_status_meta(
+    bank: &Bank,
+    transaction: &SanitizedTransaction,
+    details: &TransactionExecutionDetails,
+    pre_balances: Vec<u64>,
+    post_balances: Vec<u64>,
+) -> TransactionStatusMeta {
+    let lamports_per_signature = match details.durable_nonce_fee {
+        Some(DurableNonceFee::Valid(lamports_per_signature)) => Some(lamports_per_signature),
+        Some(DurableNonceFee::Invalid) => None,
+        None => {
+            bank.get_lamports_per_signature_for_blockhash(transaction.message().recent_blockhash())
+        }
+    }
+    .expect("lamports_per_signature must be available");
+    TransactionStatusMeta {
+        status: details.status.clone(),
+        fee: bank.get_fee_for_message_with_lamports_per_signature(
+            transaction.message(),
+            lamports_per_signature,
+        ),
+        pre_balances,
+        post_balances,
+        inner_instructions: details
+            .inner_instructions
+            .clone()
+            .map(|inner_instructions| map_inner_instructions(inner_instructions).collect()),
+        log_messages: details.log_messages.clone(),
+        pre_token_balances: None,
+        post_token_balances: None,
+        rewards: None,
+        loaded_addresses: transaction.get_loaded_addresses(),
+        return_data: details.return_data.clone(),
+        compute_units_consumed: Some(details.executed_units),
+    }
+}
+
+impl TransactionCommitNotifier for GeyserNotifier {
+    fn notify_committed_transactions(
+        &self,
+        bank: &Bank,
+        transactions: &[SanitizedTransaction],
+        execution_results: &[TransactionExecutionResult],
+        balances: &TransactionBalancesSet,
+    ) {
+        let plugins = self.plugins.read().unwrap();
+        if !plugins
+            .iter()
+            .any(|plugin| plugin.transaction_notifications_enabled())
+        {
+            return;
+        }
+
+        let slot = bank.slot();
+        for (((transaction, execution_result), pre_balances), post_balances) in transactions
+            .iter()
+            .zip(execution_results)
+            .zip(&balances.pre_balances)
+            .zip(&balances.post_balances)
+        {
+            let Some(details) = execution_result.details() else {
+                continue;
+            };
+            let transaction_status_meta = transaction_status_meta(
+                bank,
+                transaction,
+                details,
+                pre_balances.clone(),
+                post_balances.clone(),
+            );
+            let transaction_info = ReplicaTransactionInfoV2 {
+                signature: transaction.signature(),
+                is_vote: transaction.is_simple_vote_transaction(),
+                transaction,
+                transaction_status_meta: &transaction_status_meta,
+                index: self.next_transaction_index(slot),
+            };
+            for plugin in plugins
+                .iter()
+                .filter(|plugin| plugin.transaction_notifications_enabled())
+            {
+                if let Err(err) = plugin.notify_transaction(
+                    ReplicaTransactionInfoVersions::V0_0_2(&transaction_info),
+                    slot,
+                ) {
+                    error!(
+                        "Failed to notify transaction {} to plugin {}: {err}",
+                        transaction.signature(),
+                        plugin.name()
+                    );
+                }
+            }
+        }
+    }
+}
@@ -4,6 +4,7 @@
 // Export tokio for test clients
 pub use tokio;
 use {
+    crate::geyser::GeyserNotifier,
     async_trait::async_trait,
     base64::{prelude::BASE64_STANDARD, Engine},
     chrono_humanize::{Accuracy, HumanTime, Tense},
@@ -13,8 +14,11 @@ use {
         epoch_accounts_hash::EpochAccountsHash,
     },
     solana_banks_client::start_client,
-    solana_banks_server::banks_server::start_local_server,
+    solana_banks_server::banks_server::{
+        start_local_server_with_notifier, TransactionCommitNotifierArc,
+    },
     solana_bpf_loader_program::serialization::serialize_parameters,
+    solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
     solana_program_runtime::{
         compute_budget::ComputeBudget, ic_msg, invoke_context::BuiltinFunctionWithContext,
         loaded_programs::LoadedProgram, stable_log, timings::ExecuteTimings,
@@ -76,6 +80,7 @@ pub use {
     solana_sdk::transaction_context::IndexOfAccount,
 };
 
+mod geyser;
 pub mod programs;
 
 /// Errors from the program test environment
@@ -468,6 +473,7 @@ pub struct ProgramTest {
     prefer_bpf: bool,
     deactivate_feature_set: HashSet<Pubkey>,
     transaction_account_lock_limit: Option<usize>,
+    geyser_plugins: Vec<(Box<dyn GeyserPlugin>, PathBuf)>,
 }
 
 impl Default for ProgramTest {
@@ -500,6 +506,7 @@ impl Default for ProgramTest {
             prefer_bpf,
             deactivate_feature_set: HashSet::default(),
             transaction_account_lock_limit: None,
+            geyser_plugins: vec![],
         }
     }
 }
@@ -739,6 +746,26 @@ impl ProgramTest {
         self.deactivate_feature_set.insert(feature_id);
     }
 
+    /// Load a geyser plugin with `config_file` when the test starts. The plugin
+    /// is notified of the committed transactions, and of the slots rooted by
+    /// [`ProgramTestContext::warp_to_slot`]. Account updates aren't notified.
+    pub fn add_geyser_plugin(
+        &mut self,
+        plugin: Box<dyn GeyserPlugin>,
+        config_file: impl Into<PathBuf>,
+    ) {
+        self.geyser_plugins.push((plugin, config_file.into()));
+    }
+
+    fn load_geyser_plugins(&mut self) -> Option<Arc<GeyserNotifier>> {
+        if self.geyser_plugins.is_empty() {
+            return None;
+        }
+        Some(Arc::new(GeyserNotifier::load(std::mem::take(
+            &mut self.geyser_plugins,
+        ))))
+    }
+
     fn setup_bank(
         &mut self,
     ) -> (
@@ -877,12 +904,14 @@ impl ProgramTest {
 
     pub async fn start(mut self) -> (BanksClient, Keypair, Hash) {
         let (bank_forks, block_commitment_cache, last_blockhash, gci) = self.setup_bank();
+        let geyser_notifier = self.load_geyser_plugins();
         let target_tick_duration = gci.genesis_config.poh_config.target_tick_duration;
         let target_slot_duration = target_tick_duration * gci.genesis_config.ticks_per_slot as u32;
-        let transport = start_local_server(
+        let transport = start_local_server_with_notifier(
             bank_forks.clone(),
             block_commitment_cache.clone(),
             target_tick_duration,
+            geyser_notifier.map(|notifier| notifier as TransactionCommitNotifierArc),
         )
         .await;
         let banks_client = start_client(transport)
@@ -912,11 +941,15 @@ impl ProgramTest {
     /// with SOL for sending transactions
     pub async fn start_with_context(mut self) -> ProgramTestContext {
         let (bank_forks, block_commitment_cache, last_blockhash, gci) = self.setup_bank();
+        let geyser_notifier = self.load_geyser_plugins();
         let target_tick_duration = gci.genesis_config.poh_config.target_tick_duration;
-        let transport = start_local_server(
+        let transport = start_local_server_with_notifier(
             bank_forks.clone(),
             block_commitment_cache.clone(),
             target_tick_duration,
+            geyser_notifier
+                .clone()
+                .map(|notifier| notifier as TransactionCommitNotifierArc),
         )
         .await;
         let banks_client = start_client(transport)
@@ -929,6 +962,7 @@ impl ProgramTest {
             banks_client,
             last_blockhash,
             gci,
+            geyser_notifier,
         )
     }
 }
@@ -1018,6 +1052,7 @@ pub struct ProgramTestContext {
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     _bank_task: DroppableTask<()>,
+    geyser_notifier: Option<Arc<GeyserNotifier>>,
 }
 
 impl ProgramTestContext {
@@ -1027,6 +1062,7 @@ impl ProgramTestContext {
         banks_client: BanksClient,
         last_blockhash: Hash,
         genesis_config_info: GenesisConfigInfo,
+        geyser_notifier: Option<Arc<GeyserNotifier>>,
     ) -> Self {
         // Run a simulated PohService to provide the client with new blockhashes.  New blockhashes
         // are required when sending multiple otherwise identical transactions in series from a
@@ -1064,6 +1100,7 @@ impl ProgramTestContext {
             bank_forks,
             block_commitment_cache,
             _bank_task: bank_task,
+            geyser_notifier,
         }
     }
 
@@ -1138,11 +1175,11 @@ impl ProgramTestContext {
         let pre_warp_slot = warp_slot - 1;
         let warp_bank = if pre_warp_slot == working_slot {
             bank.freeze();
-            bank
+            bank.clone()
         } else {
             bank_forks
                 .insert(Bank::warp_from_parent(
-                    bank,
+                    bank.clone(),
                     &Pubkey::default(),
                     pre_warp_slot,
                     // some warping tests cannot use the append vecs because of the sequence of adding roots and flushing
@@ -1155,6 +1192,12 @@ impl ProgramTestContext {
         let abs_request_sender = AbsRequestSender::new(snapshot_request_sender);
 
         bank_forks.set_root(pre_warp_slot, &abs_request_sender, Some(pre_warp_slot));
+        if let Some(geyser_notifier) = &self.geyser_notifier {
+            geyser_notifier.notify_rooted_bank(&bank);
+            if warp_bank.slot() != working_slot {
+                geyser_notifier.notify_rooted_bank(&warp_bank);
+            }
+        }
 
         // The call to `set_root()` above will send an EAH request.  Need to intercept and handle
         // all EpochAccountsHash requests so future rooted banks do not hang in Bank::freeze()
@@ -1224,7 +1267,10 @@ impl ProgramTestContext {
 
         // warp_bank is frozen so go forward to get unfrozen bank at warp_slot
         let warp_slot = pre_warp_slot + 1;
-        let mut warp_bank = Bank::new_from_parent(bank, &Pubkey::default(), warp_slot);
+        let mut warp_bank = Bank::new_from_parent(bank.clone(), &Pubkey::default(), warp_slot);
+        if let Some(geyser_notifier) = &self.geyser_notifier {
+            geyser_notifier.notify_rooted_bank(&bank);
+        }
 
         warp_bank.force_reward_interval_end_for_tests();
         bank_forks.insert(warp_bank);
@@ -1243,6 +1289,13 @@ impl ProgramTestContext {
         Ok(())
     }
 
+    /// Unload the geyser plugins, letting them flush what they were notified of
+    pub fn unload_geyser_plugins(&mut self) {
+        if let Some(geyser_notifier) = self.geyser_notifier.take() {
+            geyser_notifier.unload();
+        }
+    }
+
     /// Get a new latest blockhash, similar in spirit to RpcClient::get_latest_blockhash()
     pub async fn get_new_latest_blockhash(&mut self) -> io::Result<Hash> {
         let blockhash = self
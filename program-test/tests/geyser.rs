@@ -0,0 +1,66 @@
+use {
+    serde_json::json,
+    solana_program_test::ProgramTest,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, system_instruction, transaction::Transaction},
+    svm_geyser::{
+        geyser_plugin::{GeyserPluginRocksDB, POD_STORE_DIR},
+        rocksdb_client::RocksDBConnection,
+    },
+    tempfile::TempDir,
+};
+
+#[tokio::test]
+async fn pods_written_from_committed_transactions() {
+    let pod_dir = TempDir::new().unwrap();
+    let config_file = pod_dir.path().join("svm_geyser.json");
+    let config = json!({
+        "path": pod_dir.path(),
+        "transaction_selector": { "mentions": ["*"] },
+        "pod_sealing": { "seal_on_root": true },
+    });
+    std::fs::write(&config_file, config.to_string()).unwrap();
+
+    let mut program_test = ProgramTest::default();
+    program_test.add_geyser_plugin(Box::new(GeyserPluginRocksDB::new()), &config_file);
+    let mut context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer,
+            &Pubkey::new_unique(),
+            1_000_000,
+        )],
+        Some(&payer),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let signature = transaction.signatures[0];
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    // Pods are sealed once the slot of the transfer is rooted
+    context.warp_to_slot(5).unwrap();
+    context.unload_geyser_plugins();
+
+    let secondary_dir = TempDir::new().unwrap();
+    let pod_store = RocksDBConnection::open_secondary(
+        &pod_dir.path().join(POD_STORE_DIR),
+        secondary_dir.path(),
+    )
+    .unwrap();
+    let location = pod_store
+        .get_transaction_location(&signature.to_string())
+        .unwrap()
+        .unwrap();
+    assert!(pod_store.is_pod_sealed(location.pod_number).unwrap());
+    let header = pod_store
+        .get_pod_header(location.pod_number)
+        .unwrap()
+        .unwrap();
+    assert_eq!(header.transaction_count, 1);
+    assert!(header.is_complete());
+}
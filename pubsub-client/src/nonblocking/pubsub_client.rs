@@ -179,14 +179,14 @@ use {
     solana_rpc_client_api::{
         config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
-            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcPodSubscribeConfig, RpcProgramAccountsConfig, RpcSignatureSubscribeConfig,
+            RpcTransactionLogsConfig, RpcTransactionLogsFilter,
         },
         error_object::RpcErrorObject,
         filter::maybe_map_filters,
         response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
+            RpcPodUpdate, RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
@@ -396,6 +396,22 @@ impl PubsubClient {
         self.subscribe("block", json!([filter, config])).await
     }
 
+    /// Subscribe to pod events.
+    ///
+    /// Receives messages of type [`RpcPodUpdate`] when a pod written by the
+    /// svm geyser plugin is sealed and its last slot reached the requested
+    /// commitment. Pods are received in order, once each.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `podSubscribe` RPC method.
+    pub async fn pod_subscribe(
+        &self,
+        config: Option<RpcPodSubscribeConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<RpcPodUpdate>> {
+        self.subscribe("pod", json!([config])).await
+    }
+
     /// Subscribe to transaction log events.
     ///
     /// Receives messages of type [`RpcLogsResponse`] when a transaction is committed.
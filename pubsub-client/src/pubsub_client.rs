@@ -100,13 +100,13 @@ use {
     solana_rpc_client_api::{
         config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
-            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcPodSubscribeConfig, RpcProgramAccountsConfig, RpcSignatureSubscribeConfig,
+            RpcTransactionLogsConfig, RpcTransactionLogsFilter,
         },
         filter,
         response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
+            RpcPodUpdate, RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
@@ -303,6 +303,12 @@ pub type BlockSubscription = (
     Receiver<RpcResponse<RpcBlockUpdate>>,
 );
 
+pub type PubsubPodClientSubscription = PubsubClientSubscription<RpcResponse<RpcPodUpdate>>;
+pub type PodSubscription = (
+    PubsubPodClientSubscription,
+    Receiver<RpcResponse<RpcPodUpdate>>,
+);
+
 pub type PubsubProgramClientSubscription = PubsubClientSubscription<RpcResponse<RpcKeyedAccount>>;
 pub type ProgramSubscription = (
     PubsubProgramClientSubscription,
@@ -462,6 +468,53 @@ impl PubsubClient {
         Ok((result, receiver))
     }
 
+    /// Subscribe to pod events.
+    ///
+    /// Receives messages of type [`RpcPodUpdate`] when a pod written by the
+    /// svm geyser plugin is sealed and its last slot reached the requested
+    /// commitment. Pods are received in order, once each.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `podSubscribe` RPC method.
+    pub fn pod_subscribe(
+        url: &str,
+        config: Option<RpcPodSubscribeConfig>,
+    ) -> Result<PodSubscription, PubsubClientError> {
+        let url = Url::parse(url)?;
+        let socket = connect_with_retry(url)?;
+        let (sender, receiver) = unbounded();
+
+        let socket = Arc::new(RwLock::new(socket));
+        let socket_clone = socket.clone();
+        let exit = Arc::new(AtomicBool::new(false));
+        let exit_clone = exit.clone();
+        let body = json!({
+            "jsonrpc":"2.0",
+            "id":1,
+            "method":"podSubscribe",
+            "params":[config]
+        })
+        .to_string();
+
+        let subscription_id = PubsubPodClientSubscription::send_subscribe(&socket_clone, body)?;
+
+        let t_cleanup = std::thread::spawn(move || {
+            Self::cleanup_with_sender(exit_clone, &socket_clone, sender)
+        });
+
+        let result = PubsubClientSubscription {
+            message_type: PhantomData,
+            operation: "pod",
+            socket,
+            subscription_id,
+            t_cleanup: Some(t_cleanup),
+            exit,
+        };
+
+        Ok((result, receiver))
+    }
+
     /// Subscribe to transaction log events.
     ///
     /// Receives messages of type [`RpcLogsResponse`] when a transaction is committed.
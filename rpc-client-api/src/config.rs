@@ -1,11 +1,12 @@
 use {
     crate::filter::RpcFilterType,
-    solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
+    solana_account_decoder::{UiAccountData, UiAccountEncoding, UiDataSliceConfig},
     solana_sdk::{
-        clock::{Epoch, Slot},
+        clock::{Epoch, Slot, UnixTimestamp},
         commitment_config::{CommitmentConfig, CommitmentLevel},
     },
     solana_transaction_status::{TransactionDetails, UiTransactionEncoding},
+    std::collections::HashMap,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -46,6 +47,53 @@ pub struct RpcSimulateTransactionConfig {
     pub min_context_slot: Option<Slot>,
     #[serde(default)]
     pub inner_instructions: bool,
+    /// Accounts to replace before the simulation, keyed by address
+    pub account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+    /// Clock sysvar fields to replace before the simulation
+    pub clock_override: Option<RpcClockOverride>,
+    /// Return the tree of invoked instructions with their account changes
+    #[serde(default)]
+    pub trace: bool,
+}
+
+/// Replacement of an account for a simulation; unset fields keep the current
+/// value of the account, or the default value if it does not exist
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountOverride {
+    pub lamports: Option<u64>,
+    pub owner: Option<String>,
+    pub data: Option<UiAccountData>,
+    pub executable: Option<bool>,
+}
+
+/// Replacement of the clock sysvar for a simulation; unset fields keep the
+/// bank's value
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcClockOverride {
+    pub slot: Option<Slot>,
+    pub epoch_start_timestamp: Option<UnixTimestamp>,
+    pub epoch: Option<Epoch>,
+    pub leader_schedule_epoch: Option<Epoch>,
+    pub unix_timestamp: Option<UnixTimestamp>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleConfig {
+    #[serde(default)]
+    pub sig_verify: bool,
+    #[serde(default)]
+    pub replace_recent_blockhash: bool,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Accounts to return after each transaction of the bundle
+    pub accounts: Option<RpcSimulateTransactionAccountsConfig>,
+    pub min_context_slot: Option<Slot>,
+    #[serde(default)]
+    pub inner_instructions: bool,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -214,6 +262,17 @@ pub struct RpcBlockSubscribeConfig {
     pub max_supported_transaction_version: Option<u8>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodSubscribeConfig {
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Include the transactions and the state diff of the pod, not only its header
+    pub include_body: Option<bool>,
+    pub max_supported_transaction_version: Option<u8>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSignaturesForAddressConfig {
@@ -256,6 +315,13 @@ pub trait EncodingConfig {
     fn new_with_encoding(encoding: &Option<UiTransactionEncoding>) -> Self;
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodConfig {
+    pub encoding: Option<UiTransactionEncoding>,
+    pub max_supported_transaction_version: Option<u8>,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcBlockConfig {
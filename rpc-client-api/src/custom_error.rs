@@ -24,6 +24,7 @@ pub const JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH: i64 = -32013
 pub const JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET: i64 = -32014;
 pub const JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION: i64 = -32015;
 pub const JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
+pub const JSON_RPC_SERVER_ERROR_POD_STORE_NOT_AVAILABLE: i64 = -32017;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -45,6 +46,8 @@ pub enum RpcCustomError {
     PodNotAvailable { pod_number: u64, latest_pod_number: u64 },
     #[error("LatestPodNotAvailable")]
     LatestPodNotAvailable { latest_pod_error: String },
+    #[error("PodStoreNotAvailable")]
+    PodStoreNotAvailable { pod_store_error: String },
     #[error("NodeUnhealthy")]
     NodeUnhealthy { num_slots_behind: Option<Slot> },
     #[error("TransactionPrecompileVerificationFailure")]
@@ -135,6 +138,11 @@ impl From<RpcCustomError> for Error {
                 message: format!("{latest_pod_error}"),
                 data: None,
             },
+            RpcCustomError::PodStoreNotAvailable { pod_store_error } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_POD_STORE_NOT_AVAILABLE),
+                message: format!("Pod store not available: {pod_store_error}"),
+                data: None,
+            },
             RpcCustomError::NodeUnhealthy { num_slots_behind } => Self {
                 code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY),
                 message: if let Some(num_slots_behind) = num_slots_behind {
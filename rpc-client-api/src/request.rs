@@ -67,17 +67,22 @@ pub enum RpcRequest {
     GetInflationReward,
     GetLargestAccounts,
     GetLatestBlockhash,
+    GetLatestPod,
     GetLeaderSchedule,
     GetMaxRetransmitSlot,
     GetMaxShredInsertSlot,
     GetMinimumBalanceForRentExemption,
     GetMultipleAccounts,
+    GetPod,
+    GetPodHealth,
+    GetPodTransactionProof,
     GetProgramAccounts,
     #[deprecated(
         since = "1.9.0",
         note = "Please use RpcRequest::GetLatestBlockhash instead"
     )]
     GetRecentBlockhash,
+    GetRecentAccountContention,
     GetRecentPerformanceSamples,
     GetRecentPrioritizationFees,
     GetHighestSnapshotSlot,
@@ -86,6 +91,7 @@ pub enum RpcRequest {
         note = "Please use RpcRequest::GetHighestSnapshotSlot instead"
     )]
     GetSnapshotSlot,
+    GetSignaturePod,
     GetSignaturesForAddress,
     GetSignatureStatuses,
     GetSlot,
@@ -116,6 +122,7 @@ pub enum RpcRequest {
     RegisterNode,
     RequestAirdrop,
     SendTransaction,
+    SimulateBundle,
     SimulateTransaction,
     SignVote,
 }
@@ -155,17 +162,23 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetInflationReward => "getInflationReward",
             RpcRequest::GetLargestAccounts => "getLargestAccounts",
             RpcRequest::GetLatestBlockhash => "getLatestBlockhash",
+            RpcRequest::GetLatestPod => "getLatestPod",
             RpcRequest::GetLeaderSchedule => "getLeaderSchedule",
             RpcRequest::GetMaxRetransmitSlot => "getMaxRetransmitSlot",
             RpcRequest::GetMaxShredInsertSlot => "getMaxShredInsertSlot",
             RpcRequest::GetMinimumBalanceForRentExemption => "getMinimumBalanceForRentExemption",
             RpcRequest::GetMultipleAccounts => "getMultipleAccounts",
+            RpcRequest::GetPod => "getPod",
+            RpcRequest::GetPodHealth => "getPodHealth",
+            RpcRequest::GetPodTransactionProof => "getPodTransactionProof",
             RpcRequest::GetProgramAccounts => "getProgramAccounts",
             RpcRequest::GetRecentBlockhash => "getRecentBlockhash",
+            RpcRequest::GetRecentAccountContention => "getRecentAccountContention",
             RpcRequest::GetRecentPerformanceSamples => "getRecentPerformanceSamples",
             RpcRequest::GetRecentPrioritizationFees => "getRecentPrioritizationFees",
             RpcRequest::GetHighestSnapshotSlot => "getHighestSnapshotSlot",
             RpcRequest::GetSnapshotSlot => "getSnapshotSlot",
+            RpcRequest::GetSignaturePod => "getSignaturePod",
             RpcRequest::GetSignaturesForAddress => "getSignaturesForAddress",
             RpcRequest::GetSignatureStatuses => "getSignatureStatuses",
             RpcRequest::GetSlot => "getSlot",
@@ -192,6 +205,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::RegisterNode => "registerNode",
             RpcRequest::RequestAirdrop => "requestAirdrop",
             RpcRequest::SendTransaction => "sendTransaction",
+            RpcRequest::SimulateBundle => "simulateBundle",
             RpcRequest::SimulateTransaction => "simulateTransaction",
             RpcRequest::SignVote => "signVote",
         };
@@ -209,6 +223,7 @@ pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 pub const NUM_LARGEST_ACCOUNTS: usize = 20;
 pub const MAX_GET_PROGRAM_ACCOUNT_FILTERS: usize = 4;
 pub const MAX_GET_SLOT_LEADERS: usize = 5000;
+pub const MAX_SIMULATE_BUNDLE_TRANSACTIONS: usize = 16;
 
 // Limit the length of the `epoch_credits` array for each validator in a `get_vote_accounts`
 // response
@@ -7,11 +7,13 @@ use {
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         hash::Hash,
         inflation::Inflation,
+        instruction::InstructionError,
         transaction::{Result, TransactionError},
     },
     solana_transaction_status::{
-        ConfirmedTransactionStatusWithSignature, TransactionConfirmationStatus, UiConfirmedBlock,
-        UiInnerInstructions, UiTransactionReturnData,
+        ConfirmedTransactionStatusWithSignature, EncodedConfirmedTransactionWithStatusMeta,
+        TransactionConfirmationStatus, UiConfirmedBlock, UiInnerInstructions,
+        UiTransactionReturnData,
     },
     std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr},
     thiserror::Error,
@@ -424,6 +426,59 @@ pub struct RpcSimulateTransactionResult {
     pub units_consumed: Option<u64>,
     pub return_data: Option<UiTransactionReturnData>,
     pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+    /// The invoked instructions with their account changes, if requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<RpcTracedInstruction>>,
+}
+
+/// An instruction invoked by a simulated transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTracedInstruction {
+    pub program_id: String,
+    /// Base64 encoded instruction data
+    pub data: String,
+    pub stack_height: usize,
+    /// Compute units consumed by the instruction, its inner instructions included
+    pub compute_units_consumed: u64,
+    pub err: Option<InstructionError>,
+    pub accounts: Vec<RpcTracedAccount>,
+    pub inner_instructions: Vec<RpcTracedInstruction>,
+}
+
+/// State change of an account passed to a traced instruction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTracedAccount {
+    pub pubkey: String,
+    pub is_writable: bool,
+    pub lamports_before: u64,
+    pub lamports_after: u64,
+    pub data_len_before: usize,
+    pub data_len_after: usize,
+    pub data_changed: bool,
+    /// Only set for accounts small enough to be diffed
+    pub data_diff: Option<RpcAccountDataDiff>,
+}
+
+/// Bytes of an account's data changed by a traced instruction, base64 encoded
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountDataDiff {
+    pub offset: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleResult {
+    /// Error of the transaction that failed the bundle, if any
+    pub err: Option<TransactionError>,
+    /// Index of the transaction that failed the bundle, if any
+    pub failed_transaction_index: Option<usize>,
+    /// Results of the simulated transactions, up to the one that failed the bundle
+    pub transaction_results: Vec<RpcSimulateTransactionResult>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -555,6 +610,166 @@ pub struct RpcPrioritizationFee {
     pub prioritization_fee: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountContention {
+    pub slot: Slot,
+    pub accounts: Vec<RpcContendedAccount>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContendedAccount {
+    pub pubkey: String,
+    /// Transactions retried because the account was locked by another transaction
+    pub account_in_use_retries: u64,
+    /// Transactions retried because the account had reached its block cost limit
+    pub account_cost_limit_retries: u64,
+    /// Compute units of the retried transactions
+    pub blocked_compute_units: u64,
+}
+
+/// Why a pod stopped accepting transactions
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcPodSealReason {
+    MaxTransactions,
+    MaxBytes,
+    MaxSlots,
+    Timeout,
+    Rooted,
+}
+
+/// Bank state before and after a slot covered by a pod
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodSlotState {
+    pub slot: Slot,
+    pub blockhash: String,
+    pub bank_hash: String,
+    pub parent_bank_hash: String,
+    pub accounts_delta_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodHeader {
+    pub pod_number: u64,
+    pub sealed: bool,
+    pub seal_reason: RpcPodSealReason,
+    pub parent_pod_number: Option<u64>,
+    pub transaction_count: u64,
+    pub first_slot: Slot,
+    pub last_slot: Slot,
+    pub transactions_root: String,
+    /// The `parent_bank_hash` of the first slot is the state before the pod
+    pub first_slot_state: Option<RpcPodSlotState>,
+    /// The `bank_hash` of the last slot is the state after the pod
+    pub last_slot_state: Option<RpcPodSlotState>,
+}
+
+/// Final state of an account written by the transactions of a pod
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodAccountWrite {
+    pub pubkey: String,
+    pub owner: String,
+    pub lamports: u64,
+    pub data_hash: String,
+    /// Position of the write among the writes of its transaction
+    pub write_version: u64,
+    pub txn_signature: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPod {
+    pub pod_header: RpcPodHeader,
+    pub pod_data: Vec<EncodedConfirmedTransactionWithStatusMeta>,
+    pub state_diff: Vec<RpcPodAccountWrite>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, Error, Eq, PartialEq)]
+pub enum RpcPodUpdateError {
+    #[error("pod store error")]
+    PodStoreError,
+
+    #[error("unsupported transaction version ({0})")]
+    UnsupportedTransactionVersion(u8),
+}
+
+/// Pushed to `podSubscribe` subscribers once a pod is sealed
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodUpdate {
+    pub pod_header: RpcPodHeader,
+    /// Only set if the subscription asked for the pod body
+    pub pod_data: Option<Vec<EncodedConfirmedTransactionWithStatusMeta>>,
+    pub state_diff: Option<Vec<RpcPodAccountWrite>>,
+    pub err: Option<RpcPodUpdateError>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcLatestPod {
+    pub latest_pod: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcPodHealthStatus {
+    Ok,
+    /// The pods lag the latest root by more than the health check slot distance
+    Behind,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodHealth {
+    pub status: RpcPodHealthStatus,
+    pub latest_rooted_slot: Slot,
+    /// Latest slot whose transactions are in the pods, `None` until the plugin
+    /// processed a rooted slot
+    pub last_pod_slot: Option<Slot>,
+    pub slot_lag: u64,
+    pub latest_pod: u64,
+}
+
+/// Pod a transaction was stored in
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSignaturePod {
+    pub pod_number: u64,
+    /// Position of the transaction in the pod
+    pub index: u64,
+    pub slot: Slot,
+    pub sealed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodProofEntry {
+    pub target: String,
+    pub left_sibling: Option<String>,
+    pub right_sibling: Option<String>,
+}
+
+/// Merkle path from a transaction record to the transactions root of its pod,
+/// the entries are ordered from the leaf to the root
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPodTransactionProof {
+    pub pod_number: u64,
+    pub index: u64,
+    pub slot: Slot,
+    pub transactions_root: String,
+    /// Leaf hash of the transaction record
+    pub leaf: String,
+    /// The transaction record as stored in the pod, base64 encoded
+    pub record: String,
+    pub proof: Vec<RpcPodProofEntry>,
+}
+
 #[cfg(test)]
 pub mod tests {
 
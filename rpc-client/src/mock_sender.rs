@@ -11,11 +11,14 @@ use {
         config::RpcBlockProductionConfig,
         request::RpcRequest,
         response::{
-            Response, RpcAccountBalance, RpcBlockProduction, RpcBlockProductionRange, RpcBlockhash,
-            RpcConfirmedTransactionStatusWithSignature, RpcContactInfo, RpcFees, RpcIdentity,
-            RpcInflationGovernor, RpcInflationRate, RpcInflationReward, RpcKeyedAccount,
-            RpcPerfSample, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult,
-            RpcSnapshotSlotInfo, RpcStakeActivation, RpcSupply, RpcVersionInfo, RpcVoteAccountInfo,
+            Response, RpcAccountBalance, RpcAccountContention, RpcBlockProduction,
+            RpcBlockProductionRange, RpcBlockhash, RpcConfirmedTransactionStatusWithSignature,
+            RpcContactInfo, RpcContendedAccount, RpcFees, RpcIdentity, RpcInflationGovernor,
+            RpcInflationRate, RpcInflationReward, RpcKeyedAccount, RpcLatestPod, RpcPerfSample,
+            RpcPod, RpcPodHeader, RpcPodHealth, RpcPodHealthStatus, RpcPodSealReason,
+            RpcPodTransactionProof, RpcPrioritizationFee, RpcResponseContext, RpcSignaturePod,
+            RpcSimulateBundleResult, RpcSimulateTransactionResult, RpcSnapshotSlotInfo,
+            RpcStakeActivation, RpcSupply, RpcVersionInfo, RpcVoteAccountInfo,
             RpcVoteAccountStatus, StakeActivationState,
         },
     },
@@ -248,6 +251,48 @@ impl RpcSender for MockSender {
                 incremental: Some(110),
             }),
             "getBlockHeight" => Value::Number(Number::from(1234)),
+            "getPod" => {
+                let pod_number = params[0].as_u64().unwrap_or_default();
+                serde_json::to_value(RpcPod {
+                    pod_header: RpcPodHeader {
+                        pod_number,
+                        sealed: true,
+                        seal_reason: RpcPodSealReason::MaxTransactions,
+                        parent_pod_number: pod_number.checked_sub(1).filter(|parent| *parent > 0),
+                        transaction_count: 0,
+                        first_slot: 2,
+                        last_slot: 2,
+                        transactions_root: "11111111111111111111111111111111".to_string(),
+                        first_slot_state: None,
+                        last_slot_state: None,
+                    },
+                    pod_data: vec![],
+                    state_diff: vec![],
+                })?
+            }
+            "getLatestPod" => serde_json::to_value(RpcLatestPod { latest_pod: 1 })?,
+            "getSignaturePod" => serde_json::to_value(Some(RpcSignaturePod {
+                pod_number: 1,
+                index: 0,
+                slot: 1,
+                sealed: true,
+            }))?,
+            "getPodTransactionProof" => serde_json::to_value(Some(RpcPodTransactionProof {
+                pod_number: 1,
+                index: 0,
+                slot: 1,
+                transactions_root: "11111111111111111111111111111111".to_string(),
+                leaf: "11111111111111111111111111111111".to_string(),
+                record: String::new(),
+                proof: vec![],
+            }))?,
+            "getPodHealth" => serde_json::to_value(RpcPodHealth {
+                status: RpcPodHealthStatus::Ok,
+                latest_rooted_slot: 2,
+                last_pod_slot: Some(1),
+                slot_lag: 1,
+                latest_pod: 1,
+            })?,
             "getSlotLeaders" => json!([PUBKEY]),
             "getBlockProduction" => {
                 if params.is_null() {
@@ -342,6 +387,14 @@ impl RpcSender for MockSender {
                 };
                 Value::String(signature)
             }
+            "simulateBundle" => serde_json::to_value(Response {
+                context: RpcResponseContext { slot: 1, api_version: None },
+                value: RpcSimulateBundleResult {
+                    err: None,
+                    failed_transaction_index: None,
+                    transaction_results: vec![],
+                },
+            })?,
             "simulateTransaction" => serde_json::to_value(Response {
                 context: RpcResponseContext { slot: 1, api_version: None },
                 value: RpcSimulateTransactionResult {
@@ -351,6 +404,7 @@ impl RpcSender for MockSender {
                     units_consumed: None,
                     return_data: None,
                     inner_instructions: None,
+                    trace: None,
                 },
             })?,
             "getMinimumBalanceForRentExemption" => json![20],
@@ -428,6 +482,15 @@ impl RpcSender for MockSender {
                 slot: 123_456_789,
                 prioritization_fee: 10_000,
             }])?,
+            "getRecentAccountContention" => serde_json::to_value(vec![RpcAccountContention {
+                slot: 123_456_789,
+                accounts: vec![RpcContendedAccount {
+                    pubkey: PUBKEY.to_string(),
+                    account_in_use_retries: 12,
+                    account_cost_limit_retries: 3,
+                    blocked_compute_units: 1_400_000,
+                }],
+            }])?,
             "getIdentity" => serde_json::to_value(RpcIdentity {
                 identity: PUBKEY.to_string(),
             })?,
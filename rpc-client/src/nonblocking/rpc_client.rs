@@ -1412,6 +1412,75 @@ impl RpcClient {
         .await
     }
 
+    /// Simulates an ordered bundle of transactions, each transaction seeing
+    /// the account changes of the previous ones.
+    ///
+    /// The bundle fails as a whole at its first failing transaction, whose
+    /// index is reported in the [`failed_transaction_index`] field of the
+    /// returned [`RpcSimulateBundleResult`]. Nothing is committed either way.
+    ///
+    /// [`failed_transaction_index`]: solana_rpc_client_api::response::RpcSimulateBundleResult::failed_transaction_index
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `simulateBundle` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{
+    /// #     client_error::Error,
+    /// #     config::RpcSimulateBundleConfig,
+    /// # };
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// #     signature::Signer,
+    /// #     signer::keypair::Keypair,
+    /// #     system_transaction,
+    /// # };
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let alice = Keypair::new();
+    /// #     let bob = Keypair::new();
+    /// let latest_blockhash = rpc_client.get_latest_blockhash().await?;
+    /// let fund_bob = system_transaction::transfer(&alice, &bob.pubkey(), 100, latest_blockhash);
+    /// let pay_alice = system_transaction::transfer(&bob, &alice.pubkey(), 50, latest_blockhash);
+    /// let result = rpc_client
+    ///     .simulate_bundle_with_config(&[fund_bob, pay_alice], RpcSimulateBundleConfig::default())
+    ///     .await?;
+    /// assert!(result.value.err.is_none());
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn simulate_bundle_with_config(
+        &self,
+        transactions: &[impl SerializableTransaction],
+        config: RpcSimulateBundleConfig,
+    ) -> RpcResult<RpcSimulateBundleResult> {
+        let encoding = if let Some(encoding) = config.encoding {
+            encoding
+        } else {
+            self.default_cluster_transaction_encoding().await?
+        };
+        let commitment = config.commitment.unwrap_or_default();
+        let commitment = self.maybe_map_commitment(commitment).await?;
+        let config = RpcSimulateBundleConfig {
+            encoding: Some(encoding),
+            commitment: Some(commitment),
+            ..config
+        };
+        let serialized_encoded = transactions
+            .iter()
+            .map(|transaction| serialize_and_encode(transaction, encoding))
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.send(
+            RpcRequest::SimulateBundle,
+            json!([serialized_encoded, config]),
+        )
+        .await
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -3305,6 +3374,176 @@ impl RpcClient {
             .map_err(|err| err.into_with_request(request))?
     }
 
+    /// Returns a sealed pod along with the state diff of its transactions.
+    ///
+    /// Transactions are encoded as JSON and only legacy transactions are
+    /// supported, use [`get_pod_with_config`] to change either.
+    ///
+    /// [`get_pod_with_config`]: RpcClient::get_pod_with_config
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPod` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// let latest_pod = rpc_client.get_latest_pod().await?;
+    /// let pod = rpc_client.get_pod(latest_pod).await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_pod(&self, pod_number: u64) -> ClientResult<RpcPod> {
+        self.get_pod_with_config(pod_number, RpcPodConfig::default())
+            .await
+    }
+
+    /// Returns a sealed pod along with the state diff of its transactions.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPod` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{
+    /// #     config::RpcPodConfig,
+    /// #     client_error::Error,
+    /// # };
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_transaction_status::UiTransactionEncoding;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// let config = RpcPodConfig {
+    ///     encoding: Some(UiTransactionEncoding::Base64),
+    ///     max_supported_transaction_version: Some(0),
+    /// };
+    /// let pod = rpc_client.get_pod_with_config(1, config).await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_pod_with_config(
+        &self,
+        pod_number: u64,
+        config: RpcPodConfig,
+    ) -> ClientResult<RpcPod> {
+        self.send(RpcRequest::GetPod, json!([pod_number, config]))
+            .await
+    }
+
+    /// Returns the number of the most recently sealed pod.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getLatestPod` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// let latest_pod = rpc_client.get_latest_pod().await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_latest_pod(&self) -> ClientResult<u64> {
+        let latest_pod: RpcLatestPod = self.send(RpcRequest::GetLatestPod, Value::Null).await?;
+        Ok(latest_pod.latest_pod)
+    }
+
+    /// Returns how far the pods lag the latest root of the node.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPodHealth` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// let pod_health = rpc_client.get_pod_health().await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_pod_health(&self) -> ClientResult<RpcPodHealth> {
+        self.send(RpcRequest::GetPodHealth, Value::Null).await
+    }
+
+    /// Returns the pod a transaction was stored in, `None` if the transaction
+    /// is not in the pods.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getSignaturePod` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::Signature;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let signature = Signature::default();
+    /// let signature_pod = rpc_client.get_signature_pod(&signature).await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_signature_pod(
+        &self,
+        signature: &Signature,
+    ) -> ClientResult<Option<RpcSignaturePod>> {
+        self.send(RpcRequest::GetSignaturePod, json!([signature.to_string()]))
+            .await
+    }
+
+    /// Returns the merkle path proving that a transaction is part of a sealed
+    /// pod, `None` if the transaction is not in the pods.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPodTransactionProof` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::Signature;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let signature = Signature::default();
+    /// let proof = rpc_client.get_pod_transaction_proof(&signature).await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_pod_transaction_proof(
+        &self,
+        signature: &Signature,
+    ) -> ClientResult<Option<RpcPodTransactionProof>> {
+        self.send(
+            RpcRequest::GetPodTransactionProof,
+            json!([signature.to_string()]),
+        )
+        .await
+    }
+
     /// Returns information about the current epoch.
     ///
     /// This method uses the configured default [commitment level][cl].
@@ -3588,6 +3827,40 @@ impl RpcClient {
             .await
     }
 
+    /// Returns the most contended writable accounts of recent leader slots, in reverse slot
+    /// order.
+    ///
+    /// Accounts are ranked by the number of transactions the banking stage had to retry
+    /// because the account was locked or had reached its block cost limit. `limit` caps the
+    /// number of accounts returned per slot.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getRecentAccountContention` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// let limit = 5;
+    /// let account_contention = rpc_client.get_recent_account_contention(
+    ///     Some(limit),
+    /// ).await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_recent_account_contention(
+        &self,
+        limit: Option<usize>,
+    ) -> ClientResult<Vec<RpcAccountContention>> {
+        self.send(RpcRequest::GetRecentAccountContention, json!([limit]))
+            .await
+    }
+
     /// Returns the identity pubkey for the current node.
     ///
     /// # RPC Reference
@@ -3,6 +3,7 @@ mod cluster_tpu_info;
 pub mod max_slots;
 pub mod optimistically_confirmed_bank_tracker;
 pub mod parsed_token_accounts;
+pub mod pod_store;
 pub mod rpc;
 mod rpc_cache;
 pub mod rpc_completed_slots_service;
@@ -0,0 +1,468 @@
+//! Read-only access to the pods written by the svm geyser plugin.
+//!
+//! The plugin owns the primary RocksDB instance, each RPC service keeps a
+//! single secondary instance open and catches it up with the primary at most
+//! once every `CATCH_UP_INTERVAL_MS`.
+
+pub use svm_geyser::geyser_plugin::POD_STORE_DIR;
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    solana_ledger::blockstore::Blockstore,
+    solana_rpc_client_api::{
+        config::RpcPodConfig,
+        custom_error::RpcCustomError,
+        response::{
+            RpcPod, RpcPodAccountWrite, RpcPodHeader, RpcPodHealth, RpcPodHealthStatus,
+            RpcPodProofEntry, RpcPodSealReason, RpcPodSlotState, RpcPodTransactionProof,
+            RpcSignaturePod,
+        },
+    },
+    solana_sdk::{clock::Slot, signature::Signature, timing::timestamp},
+    solana_transaction_status::UiTransactionEncoding,
+    std::{
+        fmt::Display,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, RwLock,
+        },
+    },
+    svm_geyser::{
+        pod::{PodHeader, SealReason, SlotCommitment, TransactionProof},
+        rocksdb_client::RocksDBConnection,
+        txn_select::{account_state_diff, RocksDBAccountWrite, TxnStoreStruct},
+    },
+};
+
+/// Secondary instance directories inside the pod store, one per service
+pub const RPC_SECONDARY_DIR: &str = "secondary";
+pub const PUBSUB_SECONDARY_DIR: &str = "secondary_pubsub";
+const CATCH_UP_INTERVAL_MS: u64 = 200;
+
+pub(crate) fn pod_store_error(err: impl Display) -> RpcCustomError {
+    RpcCustomError::PodStoreNotAvailable {
+        pod_store_error: err.to_string(),
+    }
+}
+
+fn rpc_pod_slot_state(commitment: SlotCommitment) -> RpcPodSlotState {
+    RpcPodSlotState {
+        slot: commitment.slot,
+        blockhash: commitment.blockhash,
+        bank_hash: commitment.bank_hash,
+        parent_bank_hash: commitment.parent_bank_hash,
+        accounts_delta_hash: commitment.accounts_delta_hash,
+    }
+}
+
+pub(crate) fn rpc_pod_header(header: PodHeader) -> RpcPodHeader {
+    RpcPodHeader {
+        pod_number: header.pod_number,
+        sealed: header.sealed,
+        seal_reason: match header.seal_reason {
+            SealReason::MaxTransactions => RpcPodSealReason::MaxTransactions,
+            SealReason::MaxBytes => RpcPodSealReason::MaxBytes,
+            SealReason::MaxSlots => RpcPodSealReason::MaxSlots,
+            SealReason::Timeout => RpcPodSealReason::Timeout,
+            SealReason::Rooted => RpcPodSealReason::Rooted,
+        },
+        parent_pod_number: header.parent_pod_number,
+        transaction_count: header.transaction_count,
+        first_slot: header.first_slot,
+        last_slot: header.last_slot,
+        transactions_root: header.transactions_root,
+        first_slot_state: header.first_slot_state.map(rpc_pod_slot_state),
+        last_slot_state: header.last_slot_state.map(rpc_pod_slot_state),
+    }
+}
+
+fn rpc_pod_account_write(account_write: RocksDBAccountWrite) -> RpcPodAccountWrite {
+    RpcPodAccountWrite {
+        pubkey: account_write.pubkey,
+        owner: account_write.owner,
+        lamports: account_write.lamports,
+        data_hash: account_write.data_hash,
+        write_version: account_write.write_version,
+        txn_signature: account_write.txn_signature,
+    }
+}
+
+pub struct PodStore {
+    path: PathBuf,
+    secondary_path: PathBuf,
+    connection: RwLock<Option<Arc<RocksDBConnection>>>,
+    last_catch_up_ms: AtomicU64,
+}
+
+impl PodStore {
+    pub fn new(ledger_path: &Path) -> Self {
+        Self::new_with_secondary_dir(ledger_path, RPC_SECONDARY_DIR)
+    }
+
+    /// Used by the services running next to the JSON RPC service, which keeps `RPC_SECONDARY_DIR`
+    pub fn new_with_secondary_dir(ledger_path: &Path, secondary_dir: &str) -> Self {
+        let path = ledger_path.join(POD_STORE_DIR);
+        Self {
+            secondary_path: path.join(secondary_dir),
+            path,
+            connection: RwLock::default(),
+            last_catch_up_ms: AtomicU64::default(),
+        }
+    }
+
+    /// Returns the secondary handle, opening it if the plugin created the
+    /// store since the last attempt.
+    pub fn connection(&self) -> Result<Arc<RocksDBConnection>, RpcCustomError> {
+        if let Some(connection) = self.connection.read().unwrap().as_ref() {
+            self.maybe_catch_up(connection)?;
+            return Ok(Arc::clone(connection));
+        }
+
+        let mut w_connection = self.connection.write().unwrap();
+        if let Some(connection) = w_connection.as_ref() {
+            return Ok(Arc::clone(connection));
+        }
+        let connection = Arc::new(
+            RocksDBConnection::open_secondary(&self.path, &self.secondary_path).map_err(|err| {
+                pod_store_error(format!("failed to open {}: {err}", self.path.display()))
+            })?,
+        );
+        self.last_catch_up_ms.store(timestamp(), Ordering::Relaxed);
+        *w_connection = Some(Arc::clone(&connection));
+        Ok(connection)
+    }
+
+    /// Returns the number of the latest sealed pod
+    pub fn get_latest_sealed_pod_number(&self) -> Result<u64, RpcCustomError> {
+        self.connection()?
+            .get_latest_batch_number()
+            .map_err(pod_store_error)
+    }
+
+    /// Compare the slot the pods reached with the latest root, the pods are
+    /// behind once they lag it by more than `max_slot_lag`
+    pub fn get_pod_health(
+        &self,
+        latest_rooted_slot: Slot,
+        max_slot_lag: u64,
+    ) -> Result<RpcPodHealth, RpcCustomError> {
+        let connection = self.connection()?;
+        let last_pod_slot = connection
+            .get_last_processed_slot()
+            .map_err(pod_store_error)?;
+        let latest_pod = connection
+            .get_latest_batch_number()
+            .map_err(pod_store_error)?;
+        let slot_lag = latest_rooted_slot.saturating_sub(last_pod_slot.unwrap_or_default());
+        Ok(RpcPodHealth {
+            status: if slot_lag > max_slot_lag {
+                RpcPodHealthStatus::Behind
+            } else {
+                RpcPodHealthStatus::Ok
+            },
+            latest_rooted_slot,
+            last_pod_slot,
+            slot_lag,
+            latest_pod,
+        })
+    }
+
+    /// Returns the header of a pod, or `None` while the pod is missing or still open
+    pub fn get_sealed_pod_header(
+        &self,
+        pod_number: u64,
+    ) -> Result<Option<PodHeader>, RpcCustomError> {
+        Ok(self
+            .connection()?
+            .get_pod_header(pod_number)
+            .map_err(pod_store_error)?
+            .filter(|pod_header| pod_header.sealed))
+    }
+
+    /// Returns the pod a transaction was stored in, or `None` if the transaction
+    /// wasn't selected or its pod was pruned
+    pub fn get_signature_pod(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<RpcSignaturePod>, RpcCustomError> {
+        let connection = self.connection()?;
+        let Some(location) = connection
+            .get_transaction_location(&signature.to_string())
+            .map_err(pod_store_error)?
+        else {
+            return Ok(None);
+        };
+        let latest_pod_number = connection
+            .get_latest_batch_number()
+            .map_err(pod_store_error)?;
+        Ok(Some(RpcSignaturePod {
+            pod_number: location.pod_number,
+            index: location.index,
+            slot: location.slot,
+            sealed: location.pod_number <= latest_pod_number,
+        }))
+    }
+
+    /// Returns the merkle path of a transaction to the transactions root of its
+    /// pod, which is only available once the pod is sealed
+    pub fn get_pod_transaction_proof(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<RpcPodTransactionProof>, RpcCustomError> {
+        let connection = self.connection()?;
+        let Some(location) = connection
+            .get_transaction_location(&signature.to_string())
+            .map_err(pod_store_error)?
+        else {
+            return Ok(None);
+        };
+        let pod_number = location.pod_number;
+        let pod_header = match self.get_sealed_pod_header(pod_number)? {
+            Some(pod_header) => pod_header,
+            None => {
+                return Err(RpcCustomError::PodNotAvailable {
+                    pod_number,
+                    latest_pod_number: self.get_latest_sealed_pod_number()?,
+                })
+            }
+        };
+        let records = connection
+            .get_pod_records(pod_number)
+            .map_err(pod_store_error)?;
+        let proof = TransactionProof::new(&records, location.index as usize).ok_or_else(|| {
+            pod_store_error(format!(
+                "transaction {signature} not found at index {} of pod {pod_number}",
+                location.index
+            ))
+        })?;
+        Ok(Some(RpcPodTransactionProof {
+            pod_number,
+            index: location.index,
+            slot: location.slot,
+            transactions_root: pod_header.transactions_root,
+            leaf: proof.leaf.to_string(),
+            record: BASE64_STANDARD.encode(&records[location.index as usize]),
+            proof: proof
+                .entries
+                .into_iter()
+                .map(|entry| RpcPodProofEntry {
+                    target: entry.target.to_string(),
+                    left_sibling: entry.left_sibling.map(|hash| hash.to_string()),
+                    right_sibling: entry.right_sibling.map(|hash| hash.to_string()),
+                })
+                .collect(),
+        }))
+    }
+
+    /// Decode a sealed pod, its transactions are encoded as in `getTransaction`
+    pub fn get_pod(
+        &self,
+        pod_number: u64,
+        blockstore: &Blockstore,
+        config: RpcPodConfig,
+    ) -> Result<RpcPod, RpcCustomError> {
+        let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Json);
+        let pod_header = match self.get_sealed_pod_header(pod_number)? {
+            Some(pod_header) => pod_header,
+            None => {
+                return Err(RpcCustomError::PodNotAvailable {
+                    pod_number,
+                    latest_pod_number: self.get_latest_sealed_pod_number()?,
+                })
+            }
+        };
+
+        let txn_store_structs = self
+            .connection()?
+            .get_pod_records(pod_number)
+            .map_err(pod_store_error)?
+            .iter()
+            .map(|record| TxnStoreStruct::from_record(record))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                pod_store_error(format!(
+                    "failed to decode the records of pod {pod_number}: {err}"
+                ))
+            })?;
+        let mut transactions = Vec::with_capacity(txn_store_structs.len());
+        for txn_store in &txn_store_structs {
+            let block_time = blockstore.get_block_time(txn_store.slot()).ok().flatten();
+            let confirmed_transaction =
+                txn_store
+                    .to_confirmed_transaction(block_time)
+                    .map_err(|err| {
+                        pod_store_error(format!(
+                            "failed to decode transaction {} of pod {pod_number}: {err}",
+                            txn_store.signature
+                        ))
+                    })?;
+            transactions.push(
+                confirmed_transaction
+                    .encode(encoding, config.max_supported_transaction_version)
+                    .map_err(RpcCustomError::from)?,
+            );
+        }
+        Ok(RpcPod {
+            pod_header: rpc_pod_header(pod_header),
+            pod_data: transactions,
+            state_diff: account_state_diff(&txn_store_structs)
+                .into_iter()
+                .map(rpc_pod_account_write)
+                .collect(),
+        })
+    }
+
+    fn maybe_catch_up(&self, connection: &RocksDBConnection) -> Result<(), RpcCustomError> {
+        let now = timestamp();
+        let last_catch_up_ms = self.last_catch_up_ms.load(Ordering::Relaxed);
+        if now.saturating_sub(last_catch_up_ms) < CATCH_UP_INTERVAL_MS {
+            return Ok(());
+        }
+        // Only one request catches up, the others read the current state
+        if self
+            .last_catch_up_ms
+            .compare_exchange(last_catch_up_ms, now, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            connection
+                .try_catch_up_with_primary()
+                .map_err(pod_store_error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_ledger::get_tmp_ledger_path_auto_delete,
+        svm_geyser::{
+            pod::{hash_leaf, PodSealingPolicy, SlotCommitmentTracker},
+            slot_buffer::BufferedTransaction,
+        },
+    };
+
+    #[test]
+    fn test_pod_store_catches_up_with_primary() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let pod_store = PodStore::new(ledger_path.path());
+        assert!(matches!(
+            pod_store.connection(),
+            Err(RpcCustomError::PodStoreNotAvailable { .. })
+        ));
+
+        let primary =
+            RocksDBConnection::open(&ledger_path.path().join(POD_STORE_DIR), false).unwrap();
+        primary.check_and_create_keys().unwrap();
+        let secondary = pod_store.connection().unwrap();
+        assert_eq!(secondary.get_latest_batch_number().unwrap(), 0);
+
+        primary.db.put("batch_count", "3").unwrap();
+        primary.db.flush().unwrap();
+        pod_store.last_catch_up_ms.store(0, Ordering::Relaxed);
+        let secondary = pod_store.connection().unwrap();
+        assert_eq!(secondary.get_latest_batch_number().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_get_pod_health() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let pod_store = PodStore::new(ledger_path.path());
+        let primary =
+            RocksDBConnection::open(&ledger_path.path().join(POD_STORE_DIR), false).unwrap();
+        primary.check_and_create_keys().unwrap();
+
+        let health = pod_store.get_pod_health(20, 10).unwrap();
+        assert_eq!(health.status, RpcPodHealthStatus::Behind);
+        assert_eq!(health.last_pod_slot, None);
+        assert_eq!(health.slot_lag, 20);
+
+        primary.advance_last_processed_slot(15).unwrap();
+        primary.db.flush().unwrap();
+        pod_store.last_catch_up_ms.store(0, Ordering::Relaxed);
+        let health = pod_store.get_pod_health(20, 10).unwrap();
+        assert_eq!(health.status, RpcPodHealthStatus::Ok);
+        assert_eq!(health.last_pod_slot, Some(15));
+        assert_eq!(health.slot_lag, 5);
+        assert_eq!(health.latest_pod, 0);
+    }
+
+    #[test]
+    fn test_get_pod_transaction_proof() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let pod_store = PodStore::new(ledger_path.path());
+        let primary =
+            RocksDBConnection::open(&ledger_path.path().join(POD_STORE_DIR), false).unwrap();
+        primary.check_and_create_keys().unwrap();
+        let signatures: Vec<_> = (0..3).map(|_| Signature::new_unique()).collect();
+        let transactions = signatures
+            .iter()
+            .enumerate()
+            .map(|(index, signature)| BufferedTransaction {
+                index,
+                signature: signature.to_string(),
+                record: format!("txn-{index}").into_bytes(),
+            })
+            .collect();
+        let policy = PodSealingPolicy {
+            max_transactions: Some(2),
+            ..PodSealingPolicy::default()
+        };
+        primary
+            .save_transaction_batch(transactions, 4, &policy, &SlotCommitmentTracker::default())
+            .unwrap();
+        primary.db.flush().unwrap();
+
+        assert_eq!(
+            pod_store.get_signature_pod(&signatures[1]).unwrap(),
+            Some(RpcSignaturePod {
+                pod_number: 1,
+                index: 1,
+                slot: 4,
+                sealed: true,
+            })
+        );
+        assert!(
+            !pod_store
+                .get_signature_pod(&signatures[2])
+                .unwrap()
+                .unwrap()
+                .sealed
+        );
+        assert_eq!(
+            pod_store
+                .get_signature_pod(&Signature::new_unique())
+                .unwrap(),
+            None
+        );
+
+        let records = primary.get_pod_records(1).unwrap();
+        let expected = TransactionProof::new(&records, 1).unwrap();
+        let proof = pod_store
+            .get_pod_transaction_proof(&signatures[1])
+            .unwrap()
+            .unwrap();
+        assert_eq!(proof.pod_number, 1);
+        assert_eq!(
+            proof.transactions_root,
+            primary
+                .get_pod_header(1)
+                .unwrap()
+                .unwrap()
+                .transactions_root
+        );
+        assert_eq!(proof.leaf, expected.leaf.to_string());
+        let record = BASE64_STANDARD.decode(&proof.record).unwrap();
+        assert_eq!(record, records[1]);
+        assert_eq!(hash_leaf(&record).to_string(), proof.leaf);
+        assert_eq!(proof.proof.len(), expected.entries.len());
+        assert_eq!(proof.proof.last().unwrap().target, proof.transactions_root);
+
+        // The pod of the last transaction is still open
+        assert!(matches!(
+            pod_store.get_pod_transaction_proof(&signatures[2]),
+            Err(RpcCustomError::PodNotAvailable { pod_number: 2, .. })
+        ));
+    }
+}
@@ -2,7 +2,8 @@
 use {
     crate::{
         max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        parsed_token_accounts::*, pod_store::PodStore, rpc_cache::LargestAccountsCache,
+        rpc_health::*,
     },
     base64::{prelude::BASE64_STANDARD, Engine},
     bincode::{config::Options, serialize},
@@ -14,6 +15,7 @@ use {
         UiAccount, UiAccountEncoding, UiDataSliceConfig, MAX_BASE58_BYTES,
     },
     solana_accounts_db::{
+        account_overrides::AccountOverrides,
         accounts::AccountAddressFilter,
         accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig},
         inline_spl_token::{SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
@@ -32,6 +34,7 @@ use {
     },
     solana_metrics::inc_new_counter_info,
     solana_perf::packet::PACKET_DATA_SIZE,
+    solana_program_runtime::execution_trace::TracedInstruction,
     solana_rpc_client_api::{
         config::*,
         custom_error::RpcCustomError,
@@ -42,12 +45,17 @@ use {
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
             MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
             MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, MAX_SIMULATE_BUNDLE_TRANSACTIONS,
+            NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
     solana_runtime::{
-        bank::{Bank, TransactionSimulationResult},
+        account_contention_cache::AccountContentionCache,
+        bank::{
+            Bank, BundleSimulationResult, BundleTransactionSimulationResult,
+            TransactionSimulationResult,
+        },
         bank_forks::BankForks,
         commitment::{BlockCommitmentArray, BlockCommitmentCache, CommitmentSlots},
         installed_scheduler_pool::BankWithScheduler,
@@ -57,7 +65,7 @@ use {
         snapshot_utils,
     },
     solana_sdk::{
-        account::{AccountSharedData, ReadableAccount},
+        account::{to_account, AccountSharedData, ReadableAccount, WritableAccount},
         account_utils::StateMut,
         clock::{Slot, UnixTimestamp, MAX_RECENT_BLOCKHASHES},
         commitment_config::{CommitmentConfig, CommitmentLevel},
@@ -73,10 +81,10 @@ use {
         stake::state::{StakeActivationStatus, StakeStateV2},
         stake_history::StakeHistory,
         system_instruction,
-        sysvar::stake_history,
+        sysvar::{self, stake_history},
         transaction::{
             self, AddressLoader, MessageHash, SanitizedTransaction, TransactionError,
-            VersionedTransaction, MAX_TX_ACCOUNT_LOCKS,
+            TransactionVerificationMode, VersionedTransaction, MAX_TX_ACCOUNT_LOCKS,
         },
     },
     solana_send_transaction_service::{
@@ -113,17 +121,13 @@ use {
     },
 };
 
-use svm_geyser::{
-    rocksdb_client::RocksDBConnection,
-    txn_select::TxnStoreStruct,
-};
-
 pub mod account_resolver;
 
 type RpcCustomResult<T> = std::result::Result<T, RpcCustomError>;
 
 pub const MAX_REQUEST_BODY_SIZE: usize = 50 * (1 << 10); // 50kB
 pub const PERFORMANCE_SAMPLES_LIMIT: usize = 720;
+pub const ACCOUNT_CONTENTION_LIMIT: usize = 20;
 
 fn new_response<T>(bank: &Bank, value: T) -> RpcResponse<T> {
     RpcResponse {
@@ -215,21 +219,11 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    account_contention_cache: Arc<AccountContentionCache>,
+    pod_store: Arc<PodStore>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Pod {
-    pub pod_data: Vec<TxnStoreStruct>,
-}
-
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct LatestPod {
-    pub latest_pod: u64,
-}
-
 impl JsonRpcRequestProcessor {
     fn get_bank_with_config(&self, config: RpcContextConfig) -> Result<Arc<Bank>> {
         let RpcContextConfig {
@@ -334,8 +328,10 @@ impl JsonRpcRequestProcessor {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        account_contention_cache: Arc<AccountContentionCache>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (sender, receiver) = unbounded();
+        let pod_store = Arc::new(PodStore::new(blockstore.ledger_path()));
         (
             Self {
                 config,
@@ -356,6 +352,8 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
+                account_contention_cache,
+                pod_store,
             },
             receiver,
         )
@@ -371,6 +369,7 @@ impl JsonRpcRequestProcessor {
         let bank_forks = BankForks::new_rw_arc(bank);
         let bank = bank_forks.read().unwrap().root_bank();
         let blockstore = Arc::new(Blockstore::open(&get_tmp_ledger_path!()).unwrap());
+        let pod_store = Arc::new(PodStore::new(blockstore.ledger_path()));
         let exit = Arc::new(AtomicBool::new(false));
         let cluster_info = Arc::new({
             let keypair = Arc::new(Keypair::new());
@@ -430,6 +429,8 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            account_contention_cache: Arc::new(AccountContentionCache::default()),
+            pod_store,
         }
     }
 
@@ -1270,51 +1271,42 @@ impl JsonRpcRequestProcessor {
     pub async fn get_pod(
         &self,
         pod_number: u64,
-    ) -> Result<Option<Pod>> {
-        let ledger_path = self.blockstore.ledger_path().join("rocksdb_seq");
-        let seq_tore = RocksDBConnection::open(&ledger_path, true).unwrap();
-        // let batch_store = &self.seqstore;
-        match seq_tore.get_batch_data(pod_number) {
-            Ok(pod_data) => {
-                if pod_data.len() == 25 {
-                    let mut txn_store_structs: Vec<TxnStoreStruct> = Vec::new();
-                    for data_str in pod_data {
-                        let txn_store: TxnStoreStruct = serde_json::from_str(&data_str).unwrap();
-                        txn_store_structs.push(txn_store);
-                    }
-                    Ok(Some(Pod {
-                        pod_data: txn_store_structs,
-                    }))
-                } else {
-                    let mut latest_pod_number = seq_tore.get_latest_batch_number().unwrap();
-                    return Err(RpcCustomError::PodNotAvailable { pod_number, latest_pod_number }.into());
-                }
-            }
+        config: Option<RpcPodConfig>,
+    ) -> Result<Option<RpcPod>> {
+        Ok(Some(self.pod_store.get_pod(
+            pod_number,
+            &self.blockstore,
+            config.unwrap_or_default(),
+        )?))
+    }
+
+    pub async fn get_latest_pod_index(&self) -> Result<Option<RpcLatestPod>> {
+        let pod_store = self.pod_store.connection()?;
+        match pod_store.get_latest_batch_number() {
+            Ok(latest_pod) => Ok(Some(RpcLatestPod { latest_pod })),
             Err(e) => {
-                let mut latest_pod_number = seq_tore.get_latest_batch_number().unwrap();
-                return Err(RpcCustomError::PodNotAvailable { pod_number, latest_pod_number }.into());
+                let latest_pod_error = e.to_string();
+                Err(RpcCustomError::LatestPodNotAvailable { latest_pod_error }.into())
             }
         }
     }
 
-    pub async fn get_latest_pod_index(
+    pub async fn get_pod_health(&self) -> Result<RpcPodHealth> {
+        let latest_rooted_slot = self.bank_forks.read().unwrap().root();
+        Ok(self
+            .pod_store
+            .get_pod_health(latest_rooted_slot, self.config.health_check_slot_distance)?)
+    }
+
+    pub async fn get_signature_pod(&self, signature: Signature) -> Result<Option<RpcSignaturePod>> {
+        Ok(self.pod_store.get_signature_pod(&signature)?)
+    }
+
+    pub async fn get_pod_transaction_proof(
         &self,
-    ) -> Result<Option<LatestPod>> {
-        let ledger_path = self.blockstore.ledger_path().join("rocksdb_seq");
-        let seq_tore = RocksDBConnection::open(&ledger_path, true).unwrap();
-        match seq_tore.get_latest_batch_number() {
-            Ok(latest_pod_number) => {
-                Ok(Some(
-                    LatestPod {
-                        latest_pod: latest_pod_number
-                    }
-                ))
-            }
-            Err(e) => {
-                let latest_pod_error = e.to_string();
-                return Err(RpcCustomError::LatestPodNotAvailable { latest_pod_error }.into());
-            }
-        }
+        signature: Signature,
+    ) -> Result<Option<RpcPodTransactionProof>> {
+        Ok(self.pod_store.get_pod_transaction_proof(&signature)?)
     }
 
     pub async fn get_blocks_with_limit(
@@ -2248,6 +2240,25 @@ impl JsonRpcRequestProcessor {
             })
             .collect())
     }
+
+    fn get_recent_account_contention(&self, limit: usize) -> Vec<RpcAccountContention> {
+        self.account_contention_cache
+            .get_recent_contention(limit)
+            .into_iter()
+            .map(|(slot, accounts)| RpcAccountContention {
+                slot,
+                accounts: accounts
+                    .into_iter()
+                    .map(|(pubkey, contention)| RpcContendedAccount {
+                        pubkey: pubkey.to_string(),
+                        account_in_use_retries: contention.account_in_use_retries,
+                        account_cost_limit_retries: contention.account_cost_limit_retries,
+                        blocked_compute_units: contention.blocked_compute_units,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
 }
 
 fn optimize_filters(filters: &mut [RpcFilterType]) {
@@ -2277,6 +2288,70 @@ fn verify_transaction(
     Ok(())
 }
 
+/// Builds the accounts overriding the bank's for a simulation
+fn get_simulation_account_overrides(
+    bank: &Bank,
+    account_overrides: HashMap<String, RpcAccountOverride>,
+    clock_override: Option<RpcClockOverride>,
+) -> Result<AccountOverrides> {
+    if account_overrides.len() > MAX_MULTIPLE_ACCOUNTS {
+        return Err(Error::invalid_params(format!(
+            "Too many account overrides provided; max {MAX_MULTIPLE_ACCOUNTS}"
+        )));
+    }
+
+    let mut overrides = AccountOverrides::default();
+    for (address, account_override) in account_overrides {
+        let pubkey = verify_pubkey(&address)?;
+        let RpcAccountOverride {
+            lamports,
+            owner,
+            data,
+            executable,
+        } = account_override;
+        let mut account = bank.get_account(&pubkey).unwrap_or_default();
+        if let Some(lamports) = lamports {
+            account.set_lamports(lamports);
+        }
+        if let Some(owner) = owner {
+            account.set_owner(verify_pubkey(&owner)?);
+        }
+        if let Some(data) = data {
+            let data = data.decode().ok_or_else(|| {
+                Error::invalid_params(format!("Invalid data for account override {address}"))
+            })?;
+            account.set_data_from_slice(&data);
+        }
+        if let Some(executable) = executable {
+            account.set_executable(executable);
+        }
+        overrides.set_account(&pubkey, Some(account));
+    }
+
+    if let Some(RpcClockOverride {
+        slot,
+        epoch_start_timestamp,
+        epoch,
+        leader_schedule_epoch,
+        unix_timestamp,
+    }) = clock_override
+    {
+        let mut clock = bank.clock();
+        clock.slot = slot.unwrap_or(clock.slot);
+        clock.epoch_start_timestamp = epoch_start_timestamp.unwrap_or(clock.epoch_start_timestamp);
+        clock.epoch = epoch.unwrap_or(clock.epoch);
+        clock.leader_schedule_epoch = leader_schedule_epoch.unwrap_or(clock.leader_schedule_epoch);
+        clock.unix_timestamp = unix_timestamp.unwrap_or(clock.unix_timestamp);
+        let mut clock_account = bank
+            .get_account(&sysvar::clock::id())
+            .ok_or_else(Error::internal_error)?;
+        to_account(&clock, &mut clock_account).ok_or_else(Error::internal_error)?;
+        overrides.set_account(&sysvar::clock::id(), Some(clock_account));
+    }
+
+    Ok(overrides)
+}
+
 fn verify_filter(input: &RpcFilterType) -> Result<()> {
     input
         .verify()
@@ -3375,6 +3450,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateBundle")]
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3390,14 +3473,32 @@ pub mod rpc_full {
         fn get_pod(
             &self,
             meta: Self::Metadata,
-            pod_number: Slot,
-        ) -> BoxFuture<Result<Option<Pod>>>;
+            pod_number: u64,
+            config: Option<RpcPodConfig>,
+        ) -> BoxFuture<Result<Option<RpcPod>>>;
 
         #[rpc(meta, name = "getLatestPod")]
         fn get_latest_pod_index(
             &self,
             meta: Self::Metadata,
-        ) -> BoxFuture<Result<Option<LatestPod>>>;
+        ) -> BoxFuture<Result<Option<RpcLatestPod>>>;
+
+        #[rpc(meta, name = "getPodHealth")]
+        fn get_pod_health(&self, meta: Self::Metadata) -> BoxFuture<Result<RpcPodHealth>>;
+
+        #[rpc(meta, name = "getSignaturePod")]
+        fn get_signature_pod(
+            &self,
+            meta: Self::Metadata,
+            signature_str: String,
+        ) -> BoxFuture<Result<Option<RpcSignaturePod>>>;
+
+        #[rpc(meta, name = "getPodTransactionProof")]
+        fn get_pod_transaction_proof(
+            &self,
+            meta: Self::Metadata,
+            signature_str: String,
+        ) -> BoxFuture<Result<Option<RpcPodTransactionProof>>>;
 
         #[rpc(meta, name = "getBlockTime")]
         fn get_block_time(
@@ -3479,6 +3580,13 @@ pub mod rpc_full {
             meta: Self::Metadata,
             pubkey_strs: Option<Vec<String>>,
         ) -> Result<Vec<RpcPrioritizationFee>>;
+
+        #[rpc(meta, name = "getRecentAccountContention")]
+        fn get_recent_account_contention(
+            &self,
+            meta: Self::Metadata,
+            limit: Option<usize>,
+        ) -> Result<Vec<RpcAccountContention>>;
     }
 
     pub struct FullImpl;
@@ -3740,7 +3848,8 @@ pub mod rpc_full {
                     units_consumed,
                     return_data,
                     inner_instructions: _, // Always `None` due to `enable_cpi_recording = false`
-                } = preflight_bank.simulate_transaction(&transaction, false)
+                    execution_trace: _,    // Always `None` due to `enable_execution_trace = false`
+                } = preflight_bank.simulate_transaction(&transaction, false, false, None)
                 {
                     match err {
                         TransactionError::BlockhashNotFound => {
@@ -3759,6 +3868,7 @@ pub mod rpc_full {
                             units_consumed: Some(units_consumed),
                             return_data: return_data.map(|return_data| return_data.into()),
                             inner_instructions: None,
+                            trace: None,
                         },
                     }
                     .into());
@@ -3790,6 +3900,9 @@ pub mod rpc_full {
                 accounts: config_accounts,
                 min_context_slot,
                 inner_instructions: enable_cpi_recording,
+                account_overrides,
+                clock_override,
+                trace: enable_execution_trace,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -3819,6 +3932,15 @@ pub mod rpc_full {
             if sig_verify {
                 verify_transaction(&transaction, &bank.feature_set)?;
             }
+            let account_overrides = if account_overrides.is_some() || clock_override.is_some() {
+                Some(get_simulation_account_overrides(
+                    bank,
+                    account_overrides.unwrap_or_default(),
+                    clock_override,
+                )?)
+            } else {
+                None
+            };
 
             let TransactionSimulationResult {
                 result,
@@ -3827,7 +3949,13 @@ pub mod rpc_full {
                 units_consumed,
                 return_data,
                 inner_instructions,
-            } = bank.simulate_transaction(&transaction, enable_cpi_recording);
+                execution_trace,
+            } = bank.simulate_transaction(
+                &transaction,
+                enable_cpi_recording,
+                enable_execution_trace,
+                account_overrides.as_ref(),
+            );
 
             let account_keys = transaction.message().account_keys();
             let number_of_accounts = account_keys.len();
@@ -3852,7 +3980,16 @@ pub mod rpc_full {
                 if result.is_err() {
                     Some(vec![None; config_accounts.addresses.len()])
                 } else {
+                    // Requested accounts that the transaction does not load keep their overrides
                     let mut post_simulation_accounts_map = HashMap::new();
+                    if let Some(account_overrides) = &account_overrides {
+                        for address_str in &config_accounts.addresses {
+                            let pubkey = verify_pubkey(address_str)?;
+                            if let Some(account) = account_overrides.get(&pubkey) {
+                                post_simulation_accounts_map.insert(pubkey, account.clone());
+                            }
+                        }
+                    }
                     for (pubkey, data) in post_simulation_accounts {
                         post_simulation_accounts_map.insert(pubkey, data);
                     }
@@ -3893,6 +4030,181 @@ pub mod rpc_full {
                     units_consumed: Some(units_consumed),
                     return_data: return_data.map(|return_data| return_data.into()),
                     inner_instructions,
+                    trace: execution_trace.map(|instructions| {
+                        instructions
+                            .into_iter()
+                            .map(rpc_traced_instruction_from_traced_instruction)
+                            .collect()
+                    }),
+                },
+            ))
+        }
+
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>> {
+            debug!("simulate_bundle rpc request received");
+            let RpcSimulateBundleConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                min_context_slot,
+                inner_instructions: enable_cpi_recording,
+            } = config.unwrap_or_default();
+            if data.is_empty() || data.len() > MAX_SIMULATE_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Bundles must have between 1 and {MAX_SIMULATE_BUNDLE_TRANSACTIONS} transactions"
+                )));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+            if replace_recent_blockhash && sig_verify {
+                return Err(Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                ));
+            }
+
+            let (accounts_encoding, addresses) = match config_accounts {
+                Some(config_accounts) => {
+                    let accounts_encoding = config_accounts
+                        .encoding
+                        .unwrap_or(UiAccountEncoding::Base64);
+                    if accounts_encoding == UiAccountEncoding::Binary
+                        || accounts_encoding == UiAccountEncoding::Base58
+                    {
+                        return Err(Error::invalid_params("base58 encoding not supported"));
+                    }
+                    if config_accounts.addresses.len() > MAX_MULTIPLE_ACCOUNTS {
+                        return Err(Error::invalid_params(format!(
+                            "Too many accounts provided; max {MAX_MULTIPLE_ACCOUNTS}"
+                        )));
+                    }
+                    let addresses = config_accounts
+                        .addresses
+                        .iter()
+                        .map(|address| verify_pubkey(address))
+                        .collect::<Result<Vec<_>>>()?;
+                    (Some(accounts_encoding), addresses)
+                }
+                None => (None, vec![]),
+            };
+
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            let transactions = data
+                .into_iter()
+                .map(|data| {
+                    let (_, mut unsanitized_tx) =
+                        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                    if replace_recent_blockhash {
+                        unsanitized_tx
+                            .message
+                            .set_recent_blockhash(bank.last_blockhash());
+                    }
+                    Ok(unsanitized_tx)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let verification_mode = if sig_verify {
+                TransactionVerificationMode::FullVerification
+            } else {
+                TransactionVerificationMode::HashOnly
+            };
+
+            let BundleSimulationResult {
+                result,
+                transaction_results,
+            } = bank.simulate_bundle(
+                transactions,
+                verification_mode,
+                &addresses,
+                enable_cpi_recording,
+            );
+
+            let transaction_results = transaction_results
+                .into_iter()
+                .map(|transaction_result| {
+                    let BundleTransactionSimulationResult {
+                        transaction,
+                        simulation:
+                            TransactionSimulationResult {
+                                result,
+                                logs,
+                                units_consumed,
+                                return_data,
+                                inner_instructions,
+                                ..
+                            },
+                        post_accounts,
+                    } = transaction_result;
+
+                    let accounts = match accounts_encoding {
+                        Some(_) if result.is_err() => Some(vec![None; post_accounts.len()]),
+                        Some(accounts_encoding) => {
+                            let post_accounts_map = post_accounts
+                                .iter()
+                                .filter_map(|(pubkey, account)| {
+                                    account.clone().map(|account| (*pubkey, account))
+                                })
+                                .collect::<HashMap<_, _>>();
+                            Some(
+                                post_accounts
+                                    .iter()
+                                    .map(|(pubkey, account)| match account {
+                                        Some(_) => get_encoded_account(
+                                            bank,
+                                            pubkey,
+                                            accounts_encoding,
+                                            None,
+                                            Some(&post_accounts_map),
+                                        ),
+                                        None => Ok(None),
+                                    })
+                                    .collect::<Result<Vec<_>>>()?,
+                            )
+                        }
+                        None => None,
+                    };
+
+                    let account_keys = transaction.message().account_keys();
+                    let inner_instructions = inner_instructions.map(|info| {
+                        map_inner_instructions(info)
+                            .map(|converted| UiInnerInstructions::parse(converted, &account_keys))
+                            .collect()
+                    });
+
+                    Ok(RpcSimulateTransactionResult {
+                        err: result.err(),
+                        logs: Some(logs),
+                        accounts,
+                        units_consumed: Some(units_consumed),
+                        return_data: return_data.map(|return_data| return_data.into()),
+                        inner_instructions,
+                        trace: None,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let (failed_transaction_index, err) = match result {
+                Ok(()) => (None, None),
+                Err((index, err)) => (Some(index), Some(err)),
+            };
+            Ok(new_response(
+                bank,
+                RpcSimulateBundleResult {
+                    err,
+                    failed_transaction_index,
+                    transaction_results,
                 },
             ))
         }
@@ -3915,19 +4227,57 @@ pub mod rpc_full {
         fn get_pod(
             &self,
             meta: Self::Metadata,
-            pod_number: Slot,
-        ) -> BoxFuture<Result<Option<Pod>>> {
+            pod_number: u64,
+            config: Option<RpcPodConfig>,
+        ) -> BoxFuture<Result<Option<RpcPod>>> {
             debug!("get_pod rpc request received: {:?}", pod_number);
-            Box::pin(async move { meta.get_pod(pod_number).await })
+            Box::pin(async move { meta.get_pod(pod_number, config).await })
         }
 
         fn get_latest_pod_index(
             &self, meta: Self::Metadata,
-        ) -> BoxFuture<Result<Option<LatestPod>>> {
+        ) -> BoxFuture<Result<Option<RpcLatestPod>>> {
             debug!("get_latest_pod_index rpc request received");
             Box::pin(async move { meta.get_latest_pod_index().await })
         }
 
+        fn get_pod_health(&self, meta: Self::Metadata) -> BoxFuture<Result<RpcPodHealth>> {
+            debug!("get_pod_health rpc request received");
+            Box::pin(async move { meta.get_pod_health().await })
+        }
+
+        fn get_signature_pod(
+            &self,
+            meta: Self::Metadata,
+            signature_str: String,
+        ) -> BoxFuture<Result<Option<RpcSignaturePod>>> {
+            debug!(
+                "get_signature_pod rpc request received: {:?}",
+                signature_str
+            );
+            let signature = verify_signature(&signature_str);
+            if let Err(err) = signature {
+                return Box::pin(future::err(err));
+            }
+            Box::pin(async move { meta.get_signature_pod(signature.unwrap()).await })
+        }
+
+        fn get_pod_transaction_proof(
+            &self,
+            meta: Self::Metadata,
+            signature_str: String,
+        ) -> BoxFuture<Result<Option<RpcPodTransactionProof>>> {
+            debug!(
+                "get_pod_transaction_proof rpc request received: {:?}",
+                signature_str
+            );
+            let signature = verify_signature(&signature_str);
+            if let Err(err) = signature {
+                return Box::pin(future::err(err));
+            }
+            Box::pin(async move { meta.get_pod_transaction_proof(signature.unwrap()).await })
+        }
+
         fn get_blocks(
             &self,
             meta: Self::Metadata,
@@ -4123,6 +4473,63 @@ pub mod rpc_full {
                 .collect::<Result<Vec<_>>>()?;
             meta.get_recent_prioritization_fees(pubkeys)
         }
+
+        fn get_recent_account_contention(
+            &self,
+            meta: Self::Metadata,
+            limit: Option<usize>,
+        ) -> Result<Vec<RpcAccountContention>> {
+            debug!("get_recent_account_contention rpc request received");
+            let limit = limit.unwrap_or(ACCOUNT_CONTENTION_LIMIT);
+            if limit > ACCOUNT_CONTENTION_LIMIT {
+                return Err(Error::invalid_params(format!(
+                    "Invalid limit; max {ACCOUNT_CONTENTION_LIMIT}"
+                )));
+            }
+            Ok(meta.get_recent_account_contention(limit))
+        }
+    }
+}
+
+fn rpc_traced_instruction_from_traced_instruction(
+    instruction: TracedInstruction,
+) -> RpcTracedInstruction {
+    let TracedInstruction {
+        program_id,
+        data,
+        stack_height,
+        compute_units_consumed,
+        result,
+        accounts,
+        inner_instructions,
+    } = instruction;
+    RpcTracedInstruction {
+        program_id: program_id.to_string(),
+        data: BASE64_STANDARD.encode(data),
+        stack_height,
+        compute_units_consumed,
+        err: result.err(),
+        accounts: accounts
+            .into_iter()
+            .map(|account| RpcTracedAccount {
+                pubkey: account.pubkey.to_string(),
+                is_writable: account.is_writable,
+                lamports_before: account.lamports_before,
+                lamports_after: account.lamports_after,
+                data_len_before: account.data_len_before,
+                data_len_after: account.data_len_after,
+                data_changed: account.data_changed,
+                data_diff: account.data_diff.map(|data_diff| RpcAccountDataDiff {
+                    offset: data_diff.offset,
+                    before: BASE64_STANDARD.encode(data_diff.before),
+                    after: BASE64_STANDARD.encode(data_diff.after),
+                }),
+            })
+            .collect(),
+        inner_instructions: inner_instructions
+            .into_iter()
+            .map(rpc_traced_instruction_from_traced_instruction)
+            .collect(),
     }
 }
 
@@ -4783,8 +5190,11 @@ pub mod tests {
             filter::{Memcmp, MemcmpEncodedBytes},
         },
         solana_runtime::{
-            accounts_background_service::AbsRequestSender, bank::BankTestConfig,
-            commitment::BlockCommitment, non_circulating_supply::non_circulating_accounts,
+            account_contention_cache::{AccountContention, AccountContentionMap},
+            accounts_background_service::AbsRequestSender,
+            bank::BankTestConfig,
+            commitment::BlockCommitment,
+            non_circulating_supply::non_circulating_accounts,
         },
         solana_sdk::{
             account::{Account, WritableAccount},
@@ -4945,6 +5355,7 @@ pub mod tests {
                 max_complete_transaction_status_slot.clone(),
                 max_complete_rewards_slot,
                 Arc::new(PrioritizationFeeCache::default()),
+                Arc::new(AccountContentionCache::default()),
             )
             .0;
 
@@ -6259,6 +6670,210 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_simulate_transaction_with_account_overrides() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        // Alice only holds lamports in the overrides
+        let alice = Keypair::new();
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let carol_pubkey = solana_sdk::pubkey::new_rand();
+        let tx =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+        let tx_serialized_encoded = BASE64_STANDARD.encode(serialize(&tx).unwrap());
+        let simulate = |config: Value| {
+            let request = create_test_request(
+                "simulateTransaction",
+                Some(json!([tx_serialized_encoded, config])),
+            );
+            rpc.handle_request_sync(request)
+        };
+
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(simulate(json!({"encoding": "base64"})));
+        assert_eq!(result.value.err, Some(TransactionError::AccountNotFound));
+
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(simulate(json!({
+                "encoding": "base64",
+                "accounts": {
+                    "addresses": [bob_pubkey.to_string(), carol_pubkey.to_string()],
+                },
+                "accountOverrides": {
+                    alice.pubkey().to_string(): {"lamports": 2 * rent_exempt_amount},
+                    carol_pubkey.to_string(): {
+                        "lamports": rent_exempt_amount,
+                        "data": [BASE64_STANDARD.encode([1u8, 2, 3]), "base64"],
+                    },
+                },
+                "clockOverride": {"unixTimestamp": 42},
+            })));
+        assert_eq!(result.value.err, None);
+        let accounts = result.value.accounts.unwrap();
+        assert_eq!(accounts[0].as_ref().unwrap().lamports, rent_exempt_amount);
+        let carol_account: AccountSharedData = accounts[1].as_ref().unwrap().decode().unwrap();
+        assert_eq!(carol_account.data(), &[1, 2, 3]);
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+
+        // Overridden data must be binary encoded
+        let (code, _) = parse_failure_response(simulate(json!({
+            "encoding": "base64",
+            "accountOverrides": {
+                carol_pubkey.to_string(): {"data": ["010203", "binary"]},
+            },
+        })));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_with_trace() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let tx = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &bob_pubkey,
+            rent_exempt_amount,
+            recent_blockhash,
+        );
+        let tx_serialized_encoded = BASE64_STANDARD.encode(serialize(&tx).unwrap());
+        let simulate = |config: Value| {
+            let request = create_test_request(
+                "simulateTransaction",
+                Some(json!([tx_serialized_encoded, config])),
+            );
+            rpc.handle_request_sync(request)
+        };
+
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(simulate(json!({"encoding": "base64"})));
+        assert_eq!(result.value.trace, None);
+
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(simulate(json!({"encoding": "base64", "trace": true})));
+        assert_eq!(result.value.err, None);
+        let trace = result.value.trace.unwrap();
+        assert_eq!(trace.len(), 1);
+        let instruction = &trace[0];
+        assert_eq!(instruction.program_id, system_program::id().to_string());
+        assert_eq!(
+            instruction.data,
+            BASE64_STANDARD.encode(&tx.message.instructions[0].data)
+        );
+        assert_eq!(instruction.stack_height, 1);
+        assert_eq!(instruction.compute_units_consumed, 150);
+        assert_eq!(instruction.err, None);
+        assert!(instruction.inner_instructions.is_empty());
+
+        let mint_account = &instruction.accounts[0];
+        assert_eq!(mint_account.pubkey, rpc.mint_keypair.pubkey().to_string());
+        assert!(mint_account.is_writable);
+        assert_eq!(
+            mint_account.lamports_before - mint_account.lamports_after,
+            rent_exempt_amount
+        );
+        let bob_account = &instruction.accounts[1];
+        assert_eq!(bob_account.pubkey, bob_pubkey.to_string());
+        assert_eq!(bob_account.lamports_before, 0);
+        assert_eq!(bob_account.lamports_after, rent_exempt_amount);
+        assert!(!bob_account.data_changed);
+        assert_eq!(bob_account.data_diff, None);
+    }
+
+    #[test]
+    fn test_rpc_simulate_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        // Bob is paid by Alice with the lamports she receives earlier in the bundle
+        let alice = Keypair::new();
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let fund_alice = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &alice.pubkey(),
+            3 * rent_exempt_amount,
+            recent_blockhash,
+        );
+        let pay_bob =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+        let overspend = system_transaction::transfer(
+            &alice,
+            &bob_pubkey,
+            4 * rent_exempt_amount,
+            recent_blockhash,
+        );
+        let encode = |tx: &Transaction| BASE64_STANDARD.encode(serialize(tx).unwrap());
+        let config = json!({
+            "sigVerify": true,
+            "encoding": "base64",
+            "accounts": {
+                "encoding": "base64",
+                "addresses": [bob_pubkey.to_string()],
+            },
+        });
+
+        let request = create_test_request(
+            "simulateBundle",
+            Some(json!([[encode(&fund_alice), encode(&pay_bob)], config])),
+        );
+        let result: RpcResponse<RpcSimulateBundleResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let RpcSimulateBundleResult {
+            err,
+            failed_transaction_index,
+            transaction_results,
+        } = result.value;
+        assert_eq!(err, None);
+        assert_eq!(failed_transaction_index, None);
+        assert_eq!(transaction_results.len(), 2);
+        assert_eq!(transaction_results[0].accounts, Some(vec![None]));
+        let bob_account = transaction_results[1].accounts.as_ref().unwrap()[0]
+            .as_ref()
+            .unwrap();
+        assert_eq!(bob_account.lamports, rent_exempt_amount);
+        assert!(transaction_results
+            .iter()
+            .all(|result| result.err.is_none() && result.units_consumed == Some(150)));
+        assert_eq!(bank.get_balance(&bob_pubkey), 0);
+
+        // The first failing transaction fails the bundle
+        let request = create_test_request(
+            "simulateBundle",
+            Some(json!([
+                [encode(&fund_alice), encode(&overspend), encode(&pay_bob)],
+                config
+            ])),
+        );
+        let result: RpcResponse<RpcSimulateBundleResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.failed_transaction_index, Some(1));
+        assert!(matches!(
+            result.value.err,
+            Some(TransactionError::InstructionError(0, _))
+        ));
+        assert_eq!(result.value.transaction_results.len(), 2);
+        assert_eq!(
+            result.value.transaction_results[1].accounts,
+            Some(vec![None])
+        );
+
+        // Empty bundles are rejected
+        let request = create_test_request("simulateBundle", Some(json!([[], config])));
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+    }
+
     #[test]
     fn test_rpc_simulate_transaction_with_parsing_token_accounts() {
         let rpc = RpcHandler::start();
@@ -6945,6 +7560,7 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            Arc::new(AccountContentionCache::default()),
         );
         SendTransactionService::new::<NullTpuInfo>(
             tpu_address,
@@ -7219,6 +7835,7 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            Arc::new(AccountContentionCache::default()),
         );
         SendTransactionService::new::<NullTpuInfo>(
             tpu_address,
@@ -8851,6 +9468,7 @@ pub mod tests {
             max_complete_transaction_status_slot,
             max_complete_rewards_slot,
             Arc::new(PrioritizationFeeCache::default()),
+            Arc::new(AccountContentionCache::default()),
         );
 
         let mut io = MetaIoHandler::default();
@@ -9322,4 +9940,70 @@ pub mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_rpc_get_recent_account_contention() {
+        let rpc = RpcHandler::start();
+        let hot_account = Pubkey::new_unique();
+        let warm_account = Pubkey::new_unique();
+        let mut contention = AccountContentionMap::default();
+        contention.add(
+            hot_account,
+            &AccountContention {
+                account_in_use_retries: 3,
+                account_cost_limit_retries: 1,
+                blocked_compute_units: 4_000,
+            },
+        );
+        contention.add(
+            warm_account,
+            &AccountContention {
+                account_in_use_retries: 1,
+                account_cost_limit_retries: 0,
+                blocked_compute_units: 1_000,
+            },
+        );
+        rpc.meta.account_contention_cache.record(1, &contention);
+        rpc.meta.account_contention_cache.record(2, &contention);
+
+        let request = create_test_request("getRecentAccountContention", Some(json!([1])));
+        let response: Vec<RpcAccountContention> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let hot_contended_account = RpcContendedAccount {
+            pubkey: hot_account.to_string(),
+            account_in_use_retries: 3,
+            account_cost_limit_retries: 1,
+            blocked_compute_units: 4_000,
+        };
+        assert_eq!(
+            response,
+            vec![
+                RpcAccountContention {
+                    slot: 2,
+                    accounts: vec![hot_contended_account.clone()],
+                },
+                RpcAccountContention {
+                    slot: 1,
+                    accounts: vec![hot_contended_account],
+                },
+            ]
+        );
+
+        let request = create_test_request("getRecentAccountContention", None);
+        let response: Vec<RpcAccountContention> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(response.len(), 2);
+        assert_eq!(response[0].accounts.len(), 2);
+
+        let request = create_test_request(
+            "getRecentAccountContention",
+            Some(json!([ACCOUNT_CONTENTION_LIMIT + 1])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            format!("Invalid limit; max {ACCOUNT_CONTENTION_LIMIT}"),
+        );
+        assert_eq!(response, expected);
+    }
 }
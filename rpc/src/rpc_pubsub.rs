@@ -7,9 +7,9 @@ use {
         rpc_pubsub_service::PubSubConfig,
         rpc_subscription_tracker::{
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
-            LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
-            SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionParams,
-            SubscriptionToken,
+            LogsSubscriptionKind, LogsSubscriptionParams, PodSubscriptionParams,
+            ProgramSubscriptionParams, SignatureSubscriptionParams, SubscriptionControl,
+            SubscriptionId, SubscriptionParams, SubscriptionToken,
         },
     },
     dashmap::DashMap,
@@ -20,12 +20,12 @@ use {
     solana_rpc_client_api::{
         config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
-            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcPodSubscribeConfig, RpcProgramAccountsConfig, RpcSignatureSubscribeConfig,
+            RpcTransactionLogsConfig, RpcTransactionLogsFilter,
         },
         response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
+            RpcPodUpdate, RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
@@ -214,6 +214,23 @@ pub trait RpcSolPubSub {
         id: PubSubSubscriptionId,
     ) -> Result<bool>;
 
+    // Get notification when a pod is sealed
+    #[pubsub(subscription = "podNotification", subscribe, name = "podSubscribe")]
+    fn pod_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcPodUpdate>>,
+        config: Option<RpcPodSubscribeConfig>,
+    );
+
+    // Unsubscribe from pod notification subscription.
+    #[pubsub(subscription = "podNotification", unsubscribe, name = "podUnsubscribe")]
+    fn pod_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: PubSubSubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when vote is encountered
     #[pubsub(subscription = "voteNotification", subscribe, name = "voteSubscribe")]
     fn vote_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<RpcVote>);
@@ -334,6 +351,14 @@ mod internal {
         #[rpc(name = "blockUnsubscribe")]
         fn block_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
 
+        // Get notification when a pod is sealed
+        #[rpc(name = "podSubscribe")]
+        fn pod_subscribe(&self, config: Option<RpcPodSubscribeConfig>) -> Result<SubscriptionId>;
+
+        // Unsubscribe from pod notification subscription.
+        #[rpc(name = "podUnsubscribe")]
+        fn pod_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
+
         // Get notification when vote is encountered
         #[rpc(name = "voteSubscribe")]
         fn vote_subscribe(&self) -> Result<SubscriptionId>;
@@ -568,6 +593,21 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
+    fn pod_subscribe(&self, config: Option<RpcPodSubscribeConfig>) -> Result<SubscriptionId> {
+        let config = config.unwrap_or_default();
+        let params = PodSubscriptionParams {
+            commitment: config.commitment.unwrap_or_default(),
+            encoding: config.encoding.unwrap_or(UiTransactionEncoding::Base64),
+            include_body: config.include_body.unwrap_or_default(),
+            max_supported_transaction_version: config.max_supported_transaction_version,
+        };
+        self.subscribe(SubscriptionParams::Pod(params))
+    }
+
+    fn pod_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        self.unsubscribe(id)
+    }
+
     fn vote_subscribe(&self) -> Result<SubscriptionId> {
         if !self.config.enable_vote_subscription {
             return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
@@ -132,6 +132,7 @@ struct SentNotificationStats {
     num_root: AtomicUsize,
     num_vote: AtomicUsize,
     num_block: AtomicUsize,
+    num_pod: AtomicUsize,
     total_creation_to_queue_time_us: AtomicU64,
     last_report: AtomicInterval,
 }
@@ -186,6 +187,11 @@ impl SentNotificationStats {
                     self.num_block.swap(0, Ordering::Relaxed) as i64,
                     i64
                 ),
+                (
+                    "num_pod",
+                    self.num_pod.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
                 (
                     "total_creation_to_queue_time_us",
                     self.total_creation_to_queue_time_us
@@ -235,6 +241,9 @@ fn increment_sent_notification_stats(
         SubscriptionParams::Block(_) => {
             stats.num_block.fetch_add(1, Ordering::Relaxed);
         }
+        SubscriptionParams::Pod(_) => {
+            stats.num_pod.fetch_add(1, Ordering::Relaxed);
+        }
     }
     stats.total_creation_to_queue_time_us.fetch_add(
         notification.created_at.elapsed().as_micros() as u64,
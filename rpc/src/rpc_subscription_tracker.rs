@@ -44,6 +44,7 @@ pub enum SubscriptionParams {
     Account(AccountSubscriptionParams),
     Block(BlockSubscriptionParams),
     Logs(LogsSubscriptionParams),
+    Pod(PodSubscriptionParams),
     Program(ProgramSubscriptionParams),
     Signature(SignatureSubscriptionParams),
     Slot,
@@ -62,6 +63,7 @@ impl SubscriptionParams {
             SubscriptionParams::Slot => "slotNotification",
             SubscriptionParams::SlotsUpdates => "slotsUpdatesNotification",
             SubscriptionParams::Block(_) => "blockNotification",
+            SubscriptionParams::Pod(_) => "podNotification",
             SubscriptionParams::Root => "rootNotification",
             SubscriptionParams::Vote => "voteNotification",
         }
@@ -74,6 +76,7 @@ impl SubscriptionParams {
             SubscriptionParams::Program(params) => Some(params.commitment),
             SubscriptionParams::Signature(params) => Some(params.commitment),
             SubscriptionParams::Block(params) => Some(params.commitment),
+            SubscriptionParams::Pod(params) => Some(params.commitment),
             SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
             | SubscriptionParams::Root
@@ -86,6 +89,7 @@ impl SubscriptionParams {
             SubscriptionParams::Account(params) => &params.commitment,
             SubscriptionParams::Block(params) => &params.commitment,
             SubscriptionParams::Logs(params) => &params.commitment,
+            SubscriptionParams::Pod(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
             SubscriptionParams::Root
@@ -101,6 +105,7 @@ impl SubscriptionParams {
             SubscriptionParams::Account(params) => &params.commitment,
             SubscriptionParams::Block(params) => &params.commitment,
             SubscriptionParams::Logs(params) => &params.commitment,
+            SubscriptionParams::Pod(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
             SubscriptionParams::Root
@@ -159,6 +164,14 @@ pub enum LogsSubscriptionKind {
     Single(Pubkey),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PodSubscriptionParams {
+    pub commitment: CommitmentConfig,
+    pub encoding: UiTransactionEncoding,
+    pub include_body: bool,
+    pub max_supported_transaction_version: Option<u8>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ProgramSubscriptionParams {
     pub pubkey: Pubkey,
@@ -320,6 +333,9 @@ pub struct SubscriptionInfo {
     params: SubscriptionParams,
     method: &'static str,
     pub last_notified_slot: RwLock<Slot>,
+    /// Number of the last pod notified to a pod subscription, `None` until the pod
+    /// store could be read
+    pub last_notified_pod: RwLock<Option<u64>>,
     commitment: Option<CommitmentConfig>,
 }
 
@@ -446,9 +462,11 @@ impl SubscriptionsTracker {
         params: SubscriptionParams,
         id: SubscriptionId,
         last_notified_slot: impl FnOnce() -> Slot,
+        last_notified_pod: impl FnOnce() -> Option<u64>,
     ) {
         let info = Arc::new(SubscriptionInfo {
             last_notified_slot: RwLock::new(last_notified_slot()),
+            last_notified_pod: RwLock::new(last_notified_pod()),
             id,
             commitment: params.commitment(),
             method: params.method(),
@@ -703,7 +721,7 @@ mod tests {
         let bank_forks = BankForks::new_rw_arc(bank);
         let mut tracker = SubscriptionsTracker::new(bank_forks);
 
-        tracker.subscribe(SubscriptionParams::Slot, 0.into(), || 0);
+        tracker.subscribe(SubscriptionParams::Slot, 0.into(), || 0, || None);
         let info = tracker
             .node_progress_watchers
             .get(&SubscriptionParams::Slot)
@@ -713,6 +731,7 @@ mod tests {
         assert_eq!(info.method, SubscriptionParams::Slot.method());
         assert_eq!(info.id, SubscriptionId::from(0));
         assert_eq!(*info.last_notified_slot.read().unwrap(), 0);
+        assert_eq!(*info.last_notified_pod.read().unwrap(), None);
 
         let account_params = SubscriptionParams::Account(AccountSubscriptionParams {
             pubkey: Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap(),
@@ -720,7 +739,7 @@ mod tests {
             encoding: UiAccountEncoding::Base64Zstd,
             data_slice: None,
         });
-        tracker.subscribe(account_params.clone(), 1.into(), || 42);
+        tracker.subscribe(account_params.clone(), 1.into(), || 42, || None);
 
         let info = tracker
             .commitment_watchers
@@ -749,7 +768,7 @@ mod tests {
         let bank_forks = BankForks::new_rw_arc(bank);
         let mut tracker = SubscriptionsTracker::new(bank_forks);
 
-        tracker.subscribe(SubscriptionParams::Slot, 0.into(), || 0);
+        tracker.subscribe(SubscriptionParams::Slot, 0.into(), || 0, || None);
         assert_eq!(counts(&tracker), (0, 0, 0, 1));
         tracker.unsubscribe(SubscriptionParams::Slot, 0.into());
         assert_eq!(counts(&tracker), (0, 0, 0, 0));
@@ -760,7 +779,7 @@ mod tests {
             encoding: UiAccountEncoding::Base64Zstd,
             data_slice: None,
         });
-        tracker.subscribe(account_params.clone(), 1.into(), || 0);
+        tracker.subscribe(account_params.clone(), 1.into(), || 0, || None);
         assert_eq!(counts(&tracker), (0, 1, 0, 0));
         tracker.unsubscribe(account_params, 1.into());
         assert_eq!(counts(&tracker), (0, 0, 0, 0));
@@ -771,7 +790,7 @@ mod tests {
             encoding: UiAccountEncoding::Base64Zstd,
             data_slice: None,
         });
-        tracker.subscribe(account_params2.clone(), 2.into(), || 0);
+        tracker.subscribe(account_params2.clone(), 2.into(), || 0, || None);
         assert_eq!(counts(&tracker), (0, 0, 1, 0));
         tracker.unsubscribe(account_params2, 2.into());
         assert_eq!(counts(&tracker), (0, 0, 0, 0));
@@ -781,7 +800,7 @@ mod tests {
             commitment: CommitmentConfig::processed(),
             enable_received_notification: false,
         });
-        tracker.subscribe(signature_params.clone(), 3.into(), || 0);
+        tracker.subscribe(signature_params.clone(), 3.into(), || 0, || None);
         assert_eq!(counts(&tracker), (1, 1, 0, 0));
         tracker.unsubscribe(signature_params, 3.into());
         assert_eq!(counts(&tracker), (0, 0, 0, 0));
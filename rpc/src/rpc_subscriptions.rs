@@ -4,12 +4,13 @@ use {
     crate::{
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
         parsed_token_accounts::{get_parsed_token_account, get_parsed_token_accounts},
+        pod_store::{rpc_pod_header, PodStore, PUBSUB_SECONDARY_DIR},
         rpc_pubsub_service::PubSubConfig,
         rpc_subscription_tracker::{
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
-            LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
-            SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionInfo,
-            SubscriptionParams, SubscriptionsTracker,
+            LogsSubscriptionKind, LogsSubscriptionParams, PodSubscriptionParams,
+            ProgramSubscriptionParams, SignatureSubscriptionParams, SubscriptionControl,
+            SubscriptionId, SubscriptionInfo, SubscriptionParams, SubscriptionsTracker,
         },
     },
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
@@ -20,10 +21,15 @@ use {
     solana_ledger::{blockstore::Blockstore, get_tmp_ledger_path},
     solana_measure::measure::Measure,
     solana_rayon_threadlimit::get_thread_count,
-    solana_rpc_client_api::response::{
-        ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse, RpcBlockUpdate,
-        RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
-        RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
+    solana_rpc_client_api::{
+        config::RpcPodConfig,
+        custom_error::RpcCustomError,
+        response::{
+            ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse,
+            RpcBlockUpdate, RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcPodUpdate,
+            RpcPodUpdateError, RpcResponseContext, RpcSignatureResult, RpcVote, SlotInfo,
+            SlotUpdate,
+        },
     },
     solana_runtime::{
         bank::{Bank, TransactionLogInfo},
@@ -54,6 +60,7 @@ use {
         thread::{Builder, JoinHandle},
         time::{Duration, Instant},
     },
+    svm_geyser::pod::PodHeader,
     tokio::sync::broadcast,
 };
 
@@ -367,6 +374,110 @@ fn filter_block_result_txs(
     }))
 }
 
+fn filter_pod_result(
+    pod_header: PodHeader,
+    params: &PodSubscriptionParams,
+    pod_store: &PodStore,
+    blockstore: &Blockstore,
+) -> RpcPodUpdate {
+    if !params.include_body {
+        return RpcPodUpdate {
+            pod_header: rpc_pod_header(pod_header),
+            pod_data: None,
+            state_diff: None,
+            err: None,
+        };
+    }
+
+    let config = RpcPodConfig {
+        encoding: Some(params.encoding),
+        max_supported_transaction_version: params.max_supported_transaction_version,
+    };
+    match pod_store.get_pod(pod_header.pod_number, blockstore, config) {
+        Ok(pod) => RpcPodUpdate {
+            pod_header: pod.pod_header,
+            pod_data: Some(pod.pod_data),
+            state_diff: Some(pod.state_diff),
+            err: None,
+        },
+        Err(err) => {
+            let err = match err {
+                RpcCustomError::UnsupportedTransactionVersion(version) => {
+                    RpcPodUpdateError::UnsupportedTransactionVersion(version)
+                }
+                err => {
+                    error!("get_pod error: {:?}", err);
+                    RpcPodUpdateError::PodStoreError
+                }
+            };
+            RpcPodUpdate {
+                pod_header: rpc_pod_header(pod_header),
+                pod_data: None,
+                state_diff: None,
+                err: Some(err),
+            }
+        }
+    }
+}
+
+/// Notify the pods sealed since the last notification of the subscription, in order,
+/// until a pod covers a slot which has not reached the subscription commitment yet
+/// or still misses the bank state of its slots.
+/// Returns the number of notified pods.
+fn notify_sealed_pods(
+    params: &PodSubscriptionParams,
+    subscription: &SubscriptionInfo,
+    pod_store: &PodStore,
+    blockstore: &Blockstore,
+    slot: Slot,
+    notifier: &RpcNotifier,
+) -> usize {
+    let mut w_last_notified_pod = subscription.last_notified_pod.write().unwrap();
+    let latest_pod_number = match pod_store.get_latest_sealed_pod_number() {
+        Ok(latest_pod_number) => latest_pod_number,
+        Err(err) => {
+            debug!("pod store not available: {:?}", err);
+            return 0;
+        }
+    };
+    let last_notified_pod = match *w_last_notified_pod {
+        Some(last_notified_pod) => last_notified_pod,
+        // Only the pods sealed after the first successful read are notified
+        None => {
+            *w_last_notified_pod = Some(latest_pod_number);
+            return 0;
+        }
+    };
+
+    let mut num_notified = 0;
+    for pod_number in last_notified_pod + 1..=latest_pod_number {
+        let pod_header = match pod_store.get_sealed_pod_header(pod_number) {
+            Ok(Some(pod_header)) => pod_header,
+            Ok(None) => break,
+            Err(err) => {
+                // Retried on the next notification trigger
+                error!("get_sealed_pod_header error: {:?}", err);
+                break;
+            }
+        };
+        if pod_header.last_slot > slot || !pod_header.is_complete() {
+            break;
+        }
+
+        notifier.notify(
+            RpcResponse::from(RpcNotificationResponse {
+                context: RpcNotificationContext { slot },
+                value: filter_pod_result(pod_header, params, pod_store, blockstore),
+            }),
+            subscription,
+            false,
+        );
+        *w_last_notified_pod = Some(pod_number);
+        num_notified += 1;
+    }
+    num_notified
+}
+
 fn filter_account_result(
     result: Option<(AccountSharedData, Slot)>,
     params: &AccountSubscriptionParams,
@@ -475,6 +586,14 @@ fn initial_last_notified_slot(
     }
 }
 
+/// Only the pods sealed after the subscription are notified
+fn initial_last_notified_pod(params: &SubscriptionParams, pod_store: &PodStore) -> Option<u64> {
+    match params {
+        SubscriptionParams::Pod(_) => pod_store.get_latest_sealed_pod_number().ok(),
+        _ => None,
+    }
+}
+
 #[derive(Default)]
 struct PubsubNotificationStats {
     since: Option<Instant>,
@@ -621,6 +740,8 @@ impl RpcSubscriptions {
         let (notification_sender, notification_receiver) = crossbeam_channel::unbounded();
 
         let subscriptions = SubscriptionsTracker::new(bank_forks.clone());
+        let pod_store =
+            PodStore::new_with_secondary_dir(blockstore.ledger_path(), PUBSUB_SECONDARY_DIR);
 
         let (broadcast_sender, _) = broadcast::channel(config.queue_capacity_items);
 
@@ -654,6 +775,7 @@ impl RpcSubscriptions {
                                 max_complete_transaction_status_slot,
                                 max_complete_rewards_slot,
                                 blockstore,
+                                pod_store,
                                 notifier,
                                 notification_receiver,
                                 subscriptions,
@@ -775,6 +897,7 @@ impl RpcSubscriptions {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
         blockstore: Arc<Blockstore>,
+        pod_store: PodStore,
         notifier: RpcNotifier,
         notification_receiver: Receiver<TimestampedNotificationEntry>,
         mut subscriptions: SubscriptionsTracker,
@@ -793,15 +916,20 @@ impl RpcSubscriptions {
                     let TimestampedNotificationEntry { entry, queued_at } = notification_entry;
                     match entry {
                         NotificationEntry::Subscribed(params, id) => {
-                            subscriptions.subscribe(params.clone(), id, || {
-                                initial_last_notified_slot(
-                                    &params,
-                                    &bank_forks,
-                                    &block_commitment_cache,
-                                    &optimistically_confirmed_bank,
-                                )
-                                .unwrap_or(0)
-                            });
+                            subscriptions.subscribe(
+                                params.clone(),
+                                id,
+                                || {
+                                    initial_last_notified_slot(
+                                        &params,
+                                        &bank_forks,
+                                        &block_commitment_cache,
+                                        &optimistically_confirmed_bank,
+                                    )
+                                    .unwrap_or(0)
+                                },
+                                || initial_last_notified_pod(&params, &pod_store),
+                            );
                         }
                         NotificationEntry::Unsubscribed(params, id) => {
                             subscriptions.unsubscribe(params, id);
@@ -863,6 +991,7 @@ impl RpcSubscriptions {
                                 subscriptions.commitment_watchers(),
                                 &bank_forks,
                                 &blockstore,
+                                &pod_store,
                                 &commitment_slots,
                                 &notifier,
                                 SOURCE,
@@ -880,6 +1009,7 @@ impl RpcSubscriptions {
                                 subscriptions.gossip_watchers(),
                                 &bank_forks,
                                 &blockstore,
+                                &pod_store,
                                 &commitment_slots,
                                 &notifier,
                                 SOURCE,
@@ -935,6 +1065,7 @@ impl RpcSubscriptions {
         subscriptions: &HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
         bank_forks: &Arc<RwLock<BankForks>>,
         blockstore: &Blockstore,
+        pod_store: &PodStore,
         commitment_slots: &CommitmentSlots,
         notifier: &RpcNotifier,
         source: &'static str,
@@ -950,6 +1081,9 @@ impl RpcSubscriptions {
         let num_logs_found = AtomicUsize::new(0);
         let num_logs_notified = AtomicUsize::new(0);
 
+        let num_pods_found = AtomicUsize::new(0);
+        let num_pods_notified = AtomicUsize::new(0);
+
         let num_programs_found = AtomicUsize::new(0);
         let num_programs_notified = AtomicUsize::new(0);
 
@@ -1096,6 +1230,20 @@ impl RpcSubscriptions {
                         }
                     }
                 }
+                SubscriptionParams::Pod(params) => {
+                    num_pods_found.fetch_add(1, Ordering::Relaxed);
+                    if let Some(slot) = slot {
+                        let notified = notify_sealed_pods(
+                            params,
+                            subscription,
+                            pod_store,
+                            blockstore,
+                            slot,
+                            notifier,
+                        );
+                        num_pods_notified.fetch_add(notified, Ordering::Relaxed);
+                    }
+                }
                 SubscriptionParams::Program(params) => {
                     num_programs_found.fetch_add(1, Ordering::Relaxed);
                     if let Some(slot) = slot {
@@ -1146,6 +1294,7 @@ impl RpcSubscriptions {
 
         let total_notified = num_accounts_notified.load(Ordering::Relaxed)
             + num_logs_notified.load(Ordering::Relaxed)
+            + num_pods_notified.load(Ordering::Relaxed)
             + num_programs_notified.load(Ordering::Relaxed)
             + num_signatures_notified.load(Ordering::Relaxed);
         let total_ms = total_time.as_ms();
@@ -1186,6 +1335,16 @@ impl RpcSubscriptions {
                     num_logs_notified.load(Ordering::Relaxed),
                     i64
                 ),
+                (
+                    "num_pod_subscriptions",
+                    num_pods_found.load(Ordering::Relaxed),
+                    i64
+                ),
+                (
+                    "num_pods_notified",
+                    num_pods_notified.load(Ordering::Relaxed),
+                    i64
+                ),
                 (
                     "num_program_subscriptions",
                     num_programs_found.load(Ordering::Relaxed),
@@ -1254,6 +1413,7 @@ pub(crate) mod tests {
             optimistically_confirmed_bank_tracker::{
                 BankNotification, OptimisticallyConfirmedBank, OptimisticallyConfirmedBankTracker,
             },
+            pod_store::POD_STORE_DIR,
             rpc::{create_test_transaction_entries, populate_blockstore_for_tests},
             rpc_pubsub::RpcSolPubSubInternal,
             rpc_pubsub_service,
@@ -1262,8 +1422,8 @@ pub(crate) mod tests {
         solana_ledger::get_tmp_ledger_path_auto_delete,
         solana_rpc_client_api::config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
-            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcPodSubscribeConfig, RpcProgramAccountsConfig, RpcSignatureSubscribeConfig,
+            RpcTransactionLogsConfig, RpcTransactionLogsFilter,
         },
         solana_runtime::{
             commitment::BlockCommitment,
@@ -1272,6 +1432,7 @@ pub(crate) mod tests {
         },
         solana_sdk::{
             commitment_config::CommitmentConfig,
+            hash::Hash,
             message::Message,
             signature::{Keypair, Signer},
             stake, system_instruction, system_program, system_transaction,
@@ -1282,6 +1443,11 @@ pub(crate) mod tests {
             collections::HashSet,
             sync::atomic::{AtomicU64, Ordering::Relaxed},
         },
+        svm_geyser::{
+            pod::{PodSealingPolicy, SlotCommitment, SlotCommitmentTracker},
+            rocksdb_client::RocksDBConnection,
+            slot_buffer::BufferedTransaction,
+        },
     };
 
     struct AccountResult {
@@ -1580,6 +1746,143 @@ pub(crate) mod tests {
             .assert_unsubscribed(&SubscriptionParams::Block(params));
     }
 
+    #[test]
+    #[serial]
+    fn test_check_pod_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let primary =
+            RocksDBConnection::open(&ledger_path.path().join(POD_STORE_DIR), false).unwrap();
+        primary.check_and_create_keys().unwrap();
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests_with_blockstore(
+            exit,
+            Arc::new(AtomicU64::default()),
+            Arc::new(AtomicU64::default()),
+            blockstore,
+            bank_forks,
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+        let config = RpcPodSubscribeConfig {
+            commitment: Some(CommitmentConfig::processed()),
+            ..RpcPodSubscribeConfig::default()
+        };
+        let params = PodSubscriptionParams {
+            commitment: CommitmentConfig::processed(),
+            encoding: UiTransactionEncoding::Base64,
+            include_body: false,
+            max_supported_transaction_version: None,
+        };
+        let sub_id = rpc.pod_subscribe(Some(config)).unwrap();
+        subscriptions
+            .control
+            .assert_subscribed(&SubscriptionParams::Pod(params.clone()));
+
+        // Nothing is sealed yet
+        let commitment_slots = |slot| CommitmentSlots {
+            slot,
+            root: 0,
+            highest_confirmed_slot: 0,
+            highest_super_majority_root: 0,
+        };
+        subscriptions.notify_subscribers(commitment_slots(0));
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+
+        // Seal pods 1 and 2 at slot 1
+        let policy = PodSealingPolicy {
+            max_transactions: Some(1),
+            ..PodSealingPolicy::default()
+        };
+        let slot_commitment = |slot| SlotCommitment {
+            slot,
+            blockhash: Hash::new_unique().to_string(),
+            bank_hash: Hash::new_unique().to_string(),
+            parent_bank_hash: Hash::new_unique().to_string(),
+            accounts_delta_hash: None,
+        };
+        let mut commitments = SlotCommitmentTracker::default();
+        commitments.insert(slot_commitment(1));
+        let seal_pods = |indexes: Vec<usize>, slot, commitments: &SlotCommitmentTracker| {
+            primary
+                .save_transaction_batch(
+                    indexes
+                        .into_iter()
+                        .map(|index| BufferedTransaction {
+                            index,
+                            signature: format!("signature-{index}"),
+                            record: format!("txn-{index}").into_bytes(),
+                        })
+                        .collect(),
+                    slot,
+                    &policy,
+                    commitments,
+                )
+                .unwrap()
+        };
+        let sealed_headers = seal_pods(vec![1, 2], 1, &commitments);
+        assert_eq!(sealed_headers.len(), 2);
+        primary.db.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(300));
+
+        // Slot 1 hasn't reached the subscription commitment yet
+        subscriptions.notify_subscribers(commitment_slots(0));
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+
+        subscriptions.notify_subscribers(commitment_slots(1));
+        for header in sealed_headers {
+            let actual_resp = receiver.recv();
+            let actual_resp = serde_json::from_str::<serde_json::Value>(&actual_resp).unwrap();
+            let expected_resp = json!({
+               "jsonrpc": "2.0",
+               "method": "podNotification",
+               "params": {
+                   "result": {
+                       "context": { "slot": 1 },
+                       "value": RpcPodUpdate {
+                           pod_header: rpc_pod_header(header),
+                           pod_data: None,
+                           state_diff: None,
+                           err: None,
+                       },
+                   },
+                   "subscription": 0,
+               }
+            });
+            assert_eq!(expected_resp, actual_resp);
+        }
+
+        // Pods are notified once, and only once the bank state of their slots is known
+        let mut sealed_headers = seal_pods(vec![3], 2, &commitments);
+        primary.db.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(300));
+        subscriptions.notify_subscribers(commitment_slots(2));
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+
+        let mut header = sealed_headers.pop().unwrap();
+        assert!(header.set_slot_commitment(&slot_commitment(2)));
+        primary.save_pod_header(&header).unwrap();
+        primary.db.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(300));
+        subscriptions.notify_subscribers(commitment_slots(2));
+        let actual_resp = serde_json::from_str::<serde_json::Value>(&receiver.recv()).unwrap();
+        assert_eq!(
+            actual_resp["params"]["result"]["value"]["podHeader"]["podNumber"],
+            json!(3)
+        );
+
+        rpc.pod_unsubscribe(sub_id).unwrap();
+        subscriptions
+            .control
+            .assert_unsubscribed(&SubscriptionParams::Pod(params));
+    }
+
     #[test]
     #[serial]
     fn test_check_confirmed_block_subscribe_with_mentions() {
@@ -1,10 +1,53 @@
 use {
-    solana_sdk::{clock::Slot, signature::Signature, transaction::SanitizedTransaction},
+    solana_cost_model::cost_model::CostModel,
+    solana_program_runtime::compute_budget_processor::process_compute_budget_instructions,
+    solana_sdk::{
+        clock::Slot, feature_set::FeatureSet, fee::FeeBudgetLimits, hash::Hash,
+        signature::Signature, transaction::SanitizedTransaction,
+    },
     solana_transaction_status::TransactionStatusMeta,
     std::sync::Arc,
 };
 
+/// Compute budget and fees of an executed transaction, beyond its status meta
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionComputeInfo {
+    /// The compute unit limit requested by the transaction, or the default limit
+    /// of its instructions
+    pub compute_unit_limit: u32,
+    /// The compute unit price requested by the transaction, in micro-lamports
+    pub compute_unit_price: u64,
+    /// The prioritization fee paid on top of the signature fees, in lamports
+    pub prioritization_fee: u64,
+    /// The cost of the transaction in the cost model, in compute units
+    pub cost_units: u64,
+    /// Size of the account data loaded for the transaction, programs included
+    pub loaded_accounts_data_size: usize,
+}
+
+impl TransactionComputeInfo {
+    pub fn new(
+        transaction: &SanitizedTransaction,
+        feature_set: &FeatureSet,
+        loaded_accounts_data_size: usize,
+    ) -> Self {
+        // An executed transaction has valid compute budget instructions
+        let compute_budget_limits =
+            process_compute_budget_instructions(transaction.message().program_instructions_iter())
+                .unwrap_or_default();
+        Self {
+            compute_unit_limit: compute_budget_limits.compute_unit_limit,
+            compute_unit_price: compute_budget_limits.compute_unit_price,
+            prioritization_fee: FeeBudgetLimits::from(compute_budget_limits).prioritization_fee,
+            cost_units: CostModel::calculate_cost(transaction, feature_set).sum(),
+            loaded_accounts_data_size,
+        }
+    }
+}
+
 pub trait TransactionNotifier {
+    /// `parent_blockhash` is the latest blockhash of the bank when the transaction was
+    /// executed, the blockhash of the parent block
     fn notify_transaction(
         &self,
         slot: Slot,
@@ -12,6 +55,8 @@ pub trait TransactionNotifier {
         signature: &Signature,
         transaction_status_meta: &TransactionStatusMeta,
         transaction: &SanitizedTransaction,
+        compute_info: &TransactionComputeInfo,
+        parent_blockhash: &Hash,
     );
 }
 
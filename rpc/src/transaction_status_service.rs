@@ -1,5 +1,5 @@
 use {
-    crate::transaction_notifier_interface::TransactionNotifierArc,
+    crate::transaction_notifier_interface::{TransactionComputeInfo, TransactionNotifierArc},
     crossbeam_channel::{Receiver, RecvTimeoutError},
     itertools::izip,
     solana_accounts_db::transaction_results::{DurableNonceFee, TransactionExecutionDetails},
@@ -73,6 +73,7 @@ impl TransactionStatusService {
                 token_balances,
                 rent_debits,
                 transaction_indexes,
+                parent_blockhash,
             }) => {
                 let slot = bank.slot();
                 for (
@@ -102,6 +103,7 @@ impl TransactionStatusService {
                             durable_nonce_fee,
                             return_data,
                             executed_units,
+                            loaded_accounts_data_size,
                             ..
                         } = details;
                         let lamports_per_signature = match durable_nonce_fee {
@@ -155,12 +157,19 @@ impl TransactionStatusService {
                         };
 
                         if let Some(transaction_notifier) = transaction_notifier.as_ref() {
+                            let compute_info = TransactionComputeInfo::new(
+                                &transaction,
+                                &bank.feature_set,
+                                loaded_accounts_data_size,
+                            );
                             transaction_notifier.notify_transaction(
                                 slot,
                                 transaction_index,
                                 transaction.signature(),
                                 &transaction_status_meta,
                                 &transaction,
+                                &compute_info,
+                                &parent_blockhash,
                             );
                         }
 
@@ -258,6 +267,8 @@ pub(crate) mod tests {
     struct TestNotification {
         _meta: TransactionStatusMeta,
         transaction: SanitizedTransaction,
+        compute_info: TransactionComputeInfo,
+        parent_blockhash: Hash,
     }
 
     struct TestTransactionNotifier {
@@ -280,6 +291,8 @@ pub(crate) mod tests {
             signature: &Signature,
             transaction_status_meta: &TransactionStatusMeta,
             transaction: &SanitizedTransaction,
+            compute_info: &TransactionComputeInfo,
+            parent_blockhash: &Hash,
         ) {
             self.notifications.insert(
                 TestNotifierKey {
@@ -290,6 +303,8 @@ pub(crate) mod tests {
                 TestNotification {
                     _meta: transaction_status_meta.clone(),
                     transaction: transaction.clone(),
+                    compute_info: compute_info.clone(),
+                    parent_blockhash: *parent_blockhash,
                 },
             );
         }
@@ -375,6 +390,8 @@ pub(crate) mod tests {
             return_data: None,
             executed_units: 0,
             accounts_data_len_delta: 0,
+            loaded_accounts_data_size: 42,
+            execution_trace: None,
         });
 
         let balances = TransactionBalancesSet {
@@ -406,6 +423,8 @@ pub(crate) mod tests {
         };
 
         let slot = bank.slot();
+        // Not the latest blockhash of the bank, the notification must use the batch one
+        let parent_blockhash = Hash::new_unique();
         let signature = *transaction.signature();
         let transaction_index: usize = bank.transaction_count().try_into().unwrap();
         let transaction_status_batch = TransactionStatusBatch {
@@ -416,6 +435,7 @@ pub(crate) mod tests {
             token_balances,
             rent_debits: vec![rent_debits],
             transaction_indexes: vec![transaction_index],
+            parent_blockhash,
         };
 
         let test_notifier = Arc::new(TestTransactionNotifier::new());
@@ -451,5 +471,11 @@ pub(crate) mod tests {
             expected_transaction.signature(),
             result.transaction.signature()
         );
+        assert_eq!(result.compute_info.compute_unit_limit, 200_000);
+        assert_eq!(result.compute_info.compute_unit_price, 0);
+        assert_eq!(result.compute_info.prioritization_fee, 0);
+        assert!(result.compute_info.cost_units > 0);
+        assert_eq!(result.compute_info.loaded_accounts_data_size, 42);
+        assert_eq!(result.parent_blockhash, parent_blockhash);
     }
 }
@@ -0,0 +1,228 @@
+//! Write-lock contention of accounts seen by the banking stage in recent leader slots
+use {
+    solana_sdk::{clock::Slot, pubkey::Pubkey, saturating_add_assign},
+    std::{
+        cmp::Reverse,
+        collections::{BTreeMap, HashMap},
+        sync::RwLock,
+    },
+};
+
+/// The maximum number of leader slots to keep in `AccountContentionCache`
+const MAX_NUM_RECENT_SLOTS: usize = 150;
+/// The maximum number of accounts to keep per slot in `AccountContentionCache`
+const MAX_NUM_ACCOUNTS_PER_SLOT: usize = 128;
+
+/// Transactions that had to be retried because of an account
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AccountContention {
+    /// Retries because the account was locked by another transaction
+    pub account_in_use_retries: u64,
+    /// Retries because the account had reached its cost limit for the block
+    pub account_cost_limit_retries: u64,
+    /// Compute units of the retried transactions
+    pub blocked_compute_units: u64,
+}
+
+impl AccountContention {
+    pub fn retries(&self) -> u64 {
+        self.account_in_use_retries
+            .saturating_add(self.account_cost_limit_retries)
+    }
+
+    pub fn accumulate(&mut self, other: &AccountContention) {
+        saturating_add_assign!(self.account_in_use_retries, other.account_in_use_retries);
+        saturating_add_assign!(
+            self.account_cost_limit_retries,
+            other.account_cost_limit_retries
+        );
+        saturating_add_assign!(self.blocked_compute_units, other.blocked_compute_units);
+    }
+}
+
+/// Contention of the accounts written by retried transactions
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountContentionMap(HashMap<Pubkey, AccountContention>);
+
+impl AccountContentionMap {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountContention> {
+        self.0.get(pubkey)
+    }
+
+    pub fn add(&mut self, pubkey: Pubkey, contention: &AccountContention) {
+        self.0.entry(pubkey).or_default().accumulate(contention);
+    }
+
+    pub fn accumulate(&mut self, other: &AccountContentionMap) {
+        for (pubkey, contention) in &other.0 {
+            self.add(*pubkey, contention);
+        }
+    }
+
+    /// Returns the `limit` most contended accounts, by retries then blocked compute units
+    pub fn most_contended(&self, limit: usize) -> Vec<(Pubkey, AccountContention)> {
+        let mut accounts: Vec<_> = self
+            .0
+            .iter()
+            .map(|(pubkey, contention)| (*pubkey, *contention))
+            .collect();
+        accounts.sort_unstable_by_key(|(pubkey, contention)| {
+            (
+                Reverse(contention.retries()),
+                Reverse(contention.blocked_compute_units),
+                *pubkey,
+            )
+        });
+        accounts.truncate(limit);
+        accounts
+    }
+
+    /// Drops the accounts beyond the `limit` most contended ones
+    pub fn retain_most_contended(&mut self, limit: usize) {
+        if self.0.len() > limit {
+            self.0 = self.most_contended(limit).into_iter().collect();
+        }
+    }
+}
+
+/// Stores the account contention of the most recent leader slots
+#[derive(Debug)]
+pub struct AccountContentionCache {
+    slots: RwLock<BTreeMap<Slot, AccountContentionMap>>,
+    max_num_slots: usize,
+    max_num_accounts_per_slot: usize,
+}
+
+impl Default for AccountContentionCache {
+    fn default() -> Self {
+        Self::new(MAX_NUM_RECENT_SLOTS, MAX_NUM_ACCOUNTS_PER_SLOT)
+    }
+}
+
+impl AccountContentionCache {
+    pub fn new(max_num_slots: usize, max_num_accounts_per_slot: usize) -> Self {
+        Self {
+            slots: RwLock::default(),
+            max_num_slots,
+            max_num_accounts_per_slot,
+        }
+    }
+
+    /// Adds the contention seen while producing `slot`, keeping the most contended accounts
+    /// of the slot and dropping the oldest slots beyond the capacity of the cache
+    pub fn record(&self, slot: Slot, contention: &AccountContentionMap) {
+        if contention.is_empty() {
+            return;
+        }
+        let mut slots = self.slots.write().unwrap();
+        let slot_contention = slots.entry(slot).or_default();
+        slot_contention.accumulate(contention);
+        slot_contention.retain_most_contended(self.max_num_accounts_per_slot);
+        while slots.len() > self.max_num_slots {
+            slots.pop_first();
+        }
+    }
+
+    /// Returns the `limit` most contended accounts of every cached slot, newest slot first
+    pub fn get_recent_contention(
+        &self,
+        limit: usize,
+    ) -> Vec<(Slot, Vec<(Pubkey, AccountContention)>)> {
+        self.slots
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|(slot, contention)| (*slot, contention.most_contended(limit)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_contention_cache() {
+        let hot_account = Pubkey::new_unique();
+        let warm_account = Pubkey::new_unique();
+        let account_in_use = AccountContention {
+            account_in_use_retries: 1,
+            account_cost_limit_retries: 0,
+            blocked_compute_units: 100,
+        };
+        let account_cost_limit = AccountContention {
+            account_in_use_retries: 0,
+            account_cost_limit_retries: 1,
+            blocked_compute_units: 200,
+        };
+
+        let mut contention = AccountContentionMap::default();
+        contention.add(hot_account, &account_in_use);
+        contention.add(hot_account, &account_cost_limit);
+        contention.add(warm_account, &account_cost_limit);
+
+        let cache = AccountContentionCache::new(2, 2);
+        cache.record(1, &contention);
+        cache.record(2, &AccountContentionMap::default());
+        cache.record(3, &contention);
+        cache.record(3, &contention);
+        assert_eq!(
+            cache.get_recent_contention(1),
+            vec![
+                (
+                    3,
+                    vec![(
+                        hot_account,
+                        AccountContention {
+                            account_in_use_retries: 2,
+                            account_cost_limit_retries: 2,
+                            blocked_compute_units: 600,
+                        }
+                    )]
+                ),
+                (
+                    1,
+                    vec![(
+                        hot_account,
+                        AccountContention {
+                            account_in_use_retries: 1,
+                            account_cost_limit_retries: 1,
+                            blocked_compute_units: 300,
+                        }
+                    )]
+                ),
+            ]
+        );
+
+        // The oldest slot is dropped beyond the capacity of the cache
+        cache.record(4, &contention);
+        let recent_contention = cache.get_recent_contention(2);
+        assert_eq!(
+            recent_contention
+                .iter()
+                .map(|(slot, _)| *slot)
+                .collect::<Vec<_>>(),
+            vec![4, 3]
+        );
+        assert_eq!(
+            recent_contention[0].1,
+            vec![
+                (hot_account, contention.0[&hot_account]),
+                (warm_account, account_cost_limit)
+            ]
+        );
+
+        // Only the most contended accounts of a slot are kept
+        let cache = AccountContentionCache::new(2, 1);
+        cache.record(1, &contention);
+        assert_eq!(
+            cache.get_recent_contention(2),
+            vec![(1, vec![(hot_account, contention.0[&hot_account])])]
+        );
+    }
+}
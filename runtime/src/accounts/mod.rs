@@ -367,6 +367,7 @@ fn load_transaction_accounts(
         program_indices,
         rent: tx_rent,
         rent_debits,
+        loaded_accounts_data_size: accumulated_accounts_data_size,
     })
 }
 
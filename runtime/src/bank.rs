@@ -42,7 +42,7 @@ use solana_sdk::recent_blockhashes_account;
 pub use solana_sdk::reward_type::RewardType;
 use {
     crate::{
-        bank::metrics::*,
+        bank::{address_lookup_table::BundleAddressLoader, metrics::*},
         bank_forks::BankForks,
         builtins::{BuiltinPrototype, BUILTINS},
         epoch_rewards_hasher::hash_rewards_into_partitions,
@@ -112,6 +112,7 @@ use {
     solana_program_runtime::{
         compute_budget::ComputeBudget,
         compute_budget_processor::process_compute_budget_instructions,
+        execution_trace::{ExecutionTrace, TracedInstruction},
         invoke_context::BuiltinFunctionWithContext,
         loaded_programs::{
             LoadProgramMetrics, LoadedProgram, LoadedProgramMatchCriteria, LoadedProgramType,
@@ -170,8 +171,9 @@ use {
         sysvar::{self, last_restart_slot::LastRestartSlot, Sysvar, SysvarId},
         timing::years_as_slots,
         transaction::{
-            self, MessageHash, Result, SanitizedTransaction, Transaction, TransactionError,
-            TransactionVerificationMode, VersionedTransaction, MAX_TX_ACCOUNT_LOCKS,
+            self, AddressLoader, MessageHash, Result, SanitizedTransaction, Transaction,
+            TransactionError, TransactionVerificationMode, VersionedTransaction,
+            MAX_TX_ACCOUNT_LOCKS,
         },
         transaction_context::{
             ExecutionRecord, TransactionAccount, TransactionContext, TransactionReturnData,
@@ -338,7 +340,26 @@ pub struct TransactionSimulationResult {
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
     pub inner_instructions: Option<Vec<InnerInstructions>>,
+    /// The invoked instructions with their account changes, if requested
+    pub execution_trace: Option<Vec<TracedInstruction>>,
 }
+
+pub struct BundleTransactionSimulationResult {
+    /// The simulated transaction, with its address table lookups resolved
+    pub transaction: SanitizedTransaction,
+    pub simulation: TransactionSimulationResult,
+    /// States of the requested addresses after the transaction, `None` if the account
+    /// does not exist
+    pub post_accounts: Vec<(Pubkey, Option<AccountSharedData>)>,
+}
+
+pub struct BundleSimulationResult {
+    /// Index and error of the transaction that failed the bundle, if any
+    pub result: std::result::Result<(), (usize, TransactionError)>,
+    /// Simulations of the executed transactions, up to the one that failed the bundle
+    pub transaction_results: Vec<BundleTransactionSimulationResult>,
+}
+
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
     pub post_balances: TransactionBalances,
@@ -4235,6 +4256,12 @@ impl Bank {
         TransactionBatch::new(lock_results, self, Cow::Borrowed(transactions))
     }
 
+    /// Returns the accounts of `transaction` that are locked by other transactions, the
+    /// reason for an `AccountInUse` lock result
+    pub fn get_conflicting_account_locks(&self, transaction: &SanitizedTransaction) -> Vec<Pubkey> {
+        self.rc.accounts.get_conflicting_locks(transaction)
+    }
+
     /// Prepare a transaction batch from a single transaction without locking accounts
     pub fn prepare_unlocked_batch_from_single_tx<'a>(
         &'a self,
@@ -4258,22 +4285,157 @@ impl Bank {
         &self,
         transaction: &SanitizedTransaction,
         enable_cpi_recording: bool,
+        enable_execution_trace: bool,
+        account_overrides: Option<&AccountOverrides>,
     ) -> TransactionSimulationResult {
         assert!(self.is_frozen(), "simulation bank must be frozen");
 
-        self.simulate_transaction_unchecked(transaction, enable_cpi_recording)
+        self.simulate_transaction_unchecked(
+            transaction,
+            enable_cpi_recording,
+            enable_execution_trace,
+            account_overrides,
+        )
     }
 
     /// Run transactions against a bank without committing the results; does not check if the bank
     /// is frozen, enabling use in single-Bank test frameworks
+    ///
+    /// `account_overrides` replace the bank's accounts for the simulation. Overriding a program or
+    /// its program data account runs the overriding bytecode, and overriding the clock sysvar
+    /// changes the clock seen by the programs.
+    ///
+    /// `enable_execution_trace` records the tree of invoked instructions along with the
+    /// lamports and data changes of their accounts.
     pub fn simulate_transaction_unchecked(
         &self,
         transaction: &SanitizedTransaction,
         enable_cpi_recording: bool,
+        enable_execution_trace: bool,
+        account_overrides: Option<&AccountOverrides>,
     ) -> TransactionSimulationResult {
         let account_keys = transaction.message().account_keys();
-        let number_of_accounts = account_keys.len();
-        let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+        let account_overrides =
+            self.get_account_overrides_for_simulation(&account_keys, account_overrides);
+        self.simulate_transaction_with_overrides(
+            transaction,
+            enable_cpi_recording,
+            enable_execution_trace,
+            &account_overrides,
+        )
+    }
+
+    /// Simulate an ordered bundle of transactions against a frozen bank, each
+    /// transaction seeing the accounts written by the previous ones. Nothing is
+    /// committed; the first transaction that fails to sanitize or execute fails the
+    /// whole bundle and the remaining ones are not simulated. Lookup tables written by
+    /// the bundle are resolved as of the next slot, as a following block would see them.
+    pub fn simulate_bundle(
+        &self,
+        transactions: Vec<VersionedTransaction>,
+        verification_mode: TransactionVerificationMode,
+        account_addresses: &[Pubkey],
+        enable_cpi_recording: bool,
+    ) -> BundleSimulationResult {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        let mut bundle_accounts = HashMap::<Pubkey, AccountSharedData>::new();
+        let mut signatures = HashSet::new();
+        let mut transaction_results = Vec::with_capacity(transactions.len());
+        for (index, transaction) in transactions.into_iter().enumerate() {
+            let transaction = match self
+                .verify_transaction_with_loader(
+                    transaction,
+                    verification_mode,
+                    BundleAddressLoader {
+                        bank: self,
+                        bundle_accounts: &bundle_accounts,
+                    },
+                )
+                .and_then(|transaction| {
+                    signatures
+                        .insert(*transaction.signature())
+                        .then_some(transaction)
+                        .ok_or(TransactionError::AlreadyProcessed)
+                }) {
+                Ok(transaction) => transaction,
+                Err(err) => {
+                    return BundleSimulationResult {
+                        result: Err((index, err)),
+                        transaction_results,
+                    }
+                }
+            };
+
+            let message = transaction.message();
+            let account_keys = message.account_keys();
+            let mut account_overrides =
+                self.get_account_overrides_for_simulation(&account_keys, None);
+            for pubkey in account_keys.iter() {
+                if let Some(account) = bundle_accounts.get(pubkey) {
+                    account_overrides.set_account(pubkey, Some(account.clone()));
+                }
+            }
+            let simulation = self.simulate_transaction_with_overrides(
+                &transaction,
+                enable_cpi_recording,
+                false,
+                &account_overrides,
+            );
+            if simulation.result.is_ok() {
+                for (i, (pubkey, account)) in simulation.post_simulation_accounts.iter().enumerate()
+                {
+                    if message.is_writable(i) {
+                        // Closed accounts are gone once the transaction is committed
+                        let account = if account.lamports() == 0 {
+                            AccountSharedData::default()
+                        } else {
+                            account.clone()
+                        };
+                        bundle_accounts.insert(*pubkey, account);
+                    }
+                }
+            }
+
+            let post_accounts = account_addresses
+                .iter()
+                .map(|pubkey| {
+                    let account = bundle_accounts
+                        .get(pubkey)
+                        .cloned()
+                        .or_else(|| self.get_account_with_fixed_root(pubkey))
+                        .filter(|account| account.lamports() > 0);
+                    (*pubkey, account)
+                })
+                .collect();
+            let result = simulation.result.clone();
+            transaction_results.push(BundleTransactionSimulationResult {
+                transaction,
+                simulation,
+                post_accounts,
+            });
+            if let Err(err) = result {
+                return BundleSimulationResult {
+                    result: Err((index, err)),
+                    transaction_results,
+                };
+            }
+        }
+
+        BundleSimulationResult {
+            result: Ok(()),
+            transaction_results,
+        }
+    }
+
+    fn simulate_transaction_with_overrides(
+        &self,
+        transaction: &SanitizedTransaction,
+        enable_cpi_recording: bool,
+        enable_execution_trace: bool,
+        account_overrides: &AccountOverrides,
+    ) -> TransactionSimulationResult {
+        let number_of_accounts = transaction.message().account_keys().len();
         let batch = self.prepare_unlocked_batch_from_single_tx(transaction);
         let mut timings = ExecuteTimings::default();
 
@@ -4290,8 +4452,9 @@ impl Bank {
             enable_cpi_recording,
             true,
             true,
+            enable_execution_trace,
             &mut timings,
-            Some(&account_overrides),
+            Some(account_overrides),
             None,
             true,
         );
@@ -4328,13 +4491,14 @@ impl Bank {
                     TransactionError::InvalidProgramForExecution,
                 ));
         let flattened_result = execution_result.flattened_result();
-        let (logs, return_data, inner_instructions) = match execution_result {
+        let (logs, return_data, inner_instructions, execution_trace) = match execution_result {
             TransactionExecutionResult::Executed { details, .. } => (
                 details.log_messages,
                 details.return_data,
                 details.inner_instructions,
+                details.execution_trace,
             ),
-            TransactionExecutionResult::NotExecuted(_) => (None, None, None),
+            TransactionExecutionResult::NotExecuted(_) => (None, None, None, None),
         };
         let logs = logs.unwrap_or_default();
 
@@ -4345,13 +4509,20 @@ impl Bank {
             units_consumed,
             return_data,
             inner_instructions,
+            execution_trace,
         }
     }
 
-    fn get_account_overrides_for_simulation(&self, account_keys: &AccountKeys) -> AccountOverrides {
-        let mut account_overrides = AccountOverrides::default();
+    fn get_account_overrides_for_simulation(
+        &self,
+        account_keys: &AccountKeys,
+        account_overrides: Option<&AccountOverrides>,
+    ) -> AccountOverrides {
+        let mut account_overrides = account_overrides.cloned().unwrap_or_default();
         let slot_history_id = sysvar::slot_history::id();
-        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id) {
+        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id)
+            && account_overrides.get(&slot_history_id).is_none()
+        {
             let current_account = self.get_account_with_fixed_root(&slot_history_id);
             let slot_history = current_account
                 .as_ref()
@@ -4562,8 +4733,18 @@ impl Bank {
         }
     }
 
-    fn load_program_accounts(&self, pubkey: &Pubkey) -> Option<ProgramAccountLoadResult> {
-        let program_account = self.get_account_with_fixed_root(pubkey)?;
+    fn load_program_accounts(
+        &self,
+        pubkey: &Pubkey,
+        account_overrides: Option<&AccountOverrides>,
+    ) -> Option<ProgramAccountLoadResult> {
+        let get_account = |pubkey| {
+            account_overrides
+                .and_then(|overrides| overrides.get(pubkey))
+                .cloned()
+                .or_else(|| self.get_account_with_fixed_root(pubkey))
+        };
+        let program_account = get_account(pubkey)?;
 
         debug_assert!(solana_bpf_loader_program::check_loader_id(
             program_account.owner()
@@ -4591,9 +4772,7 @@ impl Bank {
             programdata_address,
         }) = program_account.state()
         {
-            if let Some(programdata_account) =
-                self.get_account_with_fixed_root(&programdata_address)
-            {
+            if let Some(programdata_account) = get_account(&programdata_address) {
                 if let Ok(UpgradeableLoaderState::ProgramData {
                     slot,
                     upgrade_authority_address: _,
@@ -4652,6 +4831,16 @@ impl Bank {
         pubkey: &Pubkey,
         reload: bool,
         recompile: Option<Arc<LoadedProgram>>,
+    ) -> Option<Arc<LoadedProgram>> {
+        self.load_program_with_overrides(pubkey, reload, recompile, None)
+    }
+
+    fn load_program_with_overrides(
+        &self,
+        pubkey: &Pubkey,
+        reload: bool,
+        recompile: Option<Arc<LoadedProgram>>,
+        account_overrides: Option<&AccountOverrides>,
     ) -> Option<Arc<LoadedProgram>> {
         let loaded_programs_cache = self.loaded_programs_cache.read().unwrap();
         let effective_epoch = if recompile.is_some() {
@@ -4665,7 +4854,7 @@ impl Bank {
             ..LoadProgramMetrics::default()
         };
 
-        let mut loaded_program = match self.load_program_accounts(pubkey)? {
+        let mut loaded_program = match self.load_program_accounts(pubkey, account_overrides)? {
             ProgramAccountLoadResult::InvalidAccountData => Ok(LoadedProgram::new_tombstone(
                 self.slot,
                 LoadedProgramType::Closed,
@@ -4760,10 +4949,12 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        enable_execution_trace: bool,
         timings: &mut ExecuteTimings,
         error_counters: &mut TransactionErrorMetrics,
         log_messages_bytes_limit: Option<usize>,
         programs_loaded_for_tx_batch: &LoadedProgramsForTxBatch,
+        sysvar_cache_override: Option<&SysvarCache>,
     ) -> TransactionExecutionResult {
         let transaction_accounts = std::mem::take(&mut loaded_transaction.accounts);
 
@@ -4815,6 +5006,8 @@ impl Bank {
             programs_loaded_for_tx_batch.upcoming_environments.clone(),
             programs_loaded_for_tx_batch.latest_root_epoch,
         );
+        let mut execution_trace = enable_execution_trace.then(ExecutionTrace::default);
+        let sysvar_cache = self.sysvar_cache.read().unwrap();
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = MessageProcessor::process_message(
             tx.message(),
@@ -4826,12 +5019,14 @@ impl Bank {
             self.feature_set.clone(),
             compute_budget,
             timings,
-            &self.sysvar_cache.read().unwrap(),
+            sysvar_cache_override.unwrap_or(&sysvar_cache),
             blockhash,
             lamports_per_signature,
             &mut executed_units,
+            execution_trace.as_mut(),
         );
         process_message_time.stop();
+        drop(sysvar_cache);
 
         saturating_add_assign!(
             timings.execute_accessories.process_message_us,
@@ -4918,6 +5113,8 @@ impl Bank {
                 return_data,
                 executed_units,
                 accounts_data_len_delta,
+                loaded_accounts_data_size: loaded_transaction.loaded_accounts_data_size,
+                execution_trace: execution_trace.map(ExecutionTrace::into_instructions),
             },
             programs_modified_by_tx: Box::new(programs_modified_by_tx),
         }
@@ -5015,6 +5212,55 @@ impl Bank {
         loaded_programs_for_txs.unwrap()
     }
 
+    /// Loads the programs whose program or program data accounts are overridden into the
+    /// batch, so that simulations run the overriding bytecode instead of the cached one
+    fn replenish_overridden_programs(
+        &self,
+        program_accounts_map: &HashMap<Pubkey, (&Pubkey, u64)>,
+        account_overrides: &AccountOverrides,
+        programs_loaded_for_tx_batch: &mut LoadedProgramsForTxBatch,
+    ) {
+        for (pubkey, (owner, _count)) in program_accounts_map {
+            let is_overridden = PROGRAM_OWNERS.contains(owner)
+                && (account_overrides.get(pubkey).is_some()
+                    || (bpf_loader_upgradeable::check_id(owner)
+                        && account_overrides
+                            .get(&bpf_loader_upgradeable::get_program_data_address(pubkey))
+                            .is_some()));
+            if !is_overridden {
+                continue;
+            }
+            let program = match account_overrides.get(pubkey) {
+                Some(program_account)
+                    if !solana_bpf_loader_program::check_loader_id(program_account.owner()) =>
+                {
+                    Some(Arc::new(LoadedProgram::new_tombstone(
+                        self.slot,
+                        LoadedProgramType::Closed,
+                    )))
+                }
+                _ => self.load_program_with_overrides(pubkey, false, None, Some(account_overrides)),
+            };
+            if let Some(program) = program {
+                programs_loaded_for_tx_batch.replenish(*pubkey, program);
+            }
+        }
+    }
+
+    /// Returns a copy of the sysvar cache with the overridden clock, if the clock sysvar is
+    /// overridden
+    fn sysvar_cache_with_overrides(
+        &self,
+        account_overrides: &AccountOverrides,
+    ) -> Option<SysvarCache> {
+        let clock = account_overrides
+            .get(&sysvar::clock::id())
+            .and_then(from_account::<sysvar::clock::Clock, _>)?;
+        let mut sysvar_cache = self.sysvar_cache.read().unwrap().clone();
+        sysvar_cache.set_clock(clock);
+        Some(sysvar_cache)
+    }
+
     /// Returns a hash map of executable program accounts (program accounts that are not writable
     /// in the given transactions), and their owners, for the transactions with a valid
     /// blockhash or nonce.
@@ -5077,6 +5323,7 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        enable_execution_trace: bool,
         timings: &mut ExecuteTimings,
         account_overrides: Option<&AccountOverrides>,
         log_messages_bytes_limit: Option<usize>,
@@ -5160,6 +5407,15 @@ impl Bank {
                 error_counters,
             };
         }
+        if let Some(account_overrides) = account_overrides {
+            self.replenish_overridden_programs(
+                &program_accounts_map,
+                account_overrides,
+                &mut programs_loaded_for_tx_batch.borrow_mut(),
+            );
+        }
+        let sysvar_cache_override =
+            account_overrides.and_then(|overrides| self.sysvar_cache_with_overrides(overrides));
 
         let mut load_time = Measure::start("accounts_load");
         let mut loaded_transactions = load_accounts(
@@ -5219,10 +5475,12 @@ impl Bank {
                         enable_cpi_recording,
                         enable_log_recording,
                         enable_return_data_recording,
+                        enable_execution_trace,
                         timings,
                         &mut error_counters,
                         log_messages_bytes_limit,
                         &programs_loaded_for_tx_batch.borrow(),
+                        sysvar_cache_override.as_ref(),
                     );
 
                     if let TransactionExecutionResult::Executed {
@@ -6314,6 +6572,7 @@ impl Bank {
             enable_cpi_recording,
             enable_log_recording,
             enable_return_data_recording,
+            false,
             timings,
             None,
             log_messages_bytes_limit,
@@ -7194,6 +7453,15 @@ impl Bank {
         &self,
         tx: VersionedTransaction,
         verification_mode: TransactionVerificationMode,
+    ) -> Result<SanitizedTransaction> {
+        self.verify_transaction_with_loader(tx, verification_mode, self)
+    }
+
+    fn verify_transaction_with_loader(
+        &self,
+        tx: VersionedTransaction,
+        verification_mode: TransactionVerificationMode,
+        address_loader: impl AddressLoader,
     ) -> Result<SanitizedTransaction> {
         let sanitized_tx = {
             let size =
@@ -7208,7 +7476,7 @@ impl Bank {
                 tx.message.hash()
             };
 
-            SanitizedTransaction::try_create(tx, message_hash, None, self)
+            SanitizedTransaction::try_create(tx, message_hash, None, address_loader)
         }?;
 
         if verification_mode == TransactionVerificationMode::HashAndVerifyPrecompiles
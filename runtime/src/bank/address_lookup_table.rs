@@ -1,13 +1,16 @@
 use {
     super::Bank,
     solana_sdk::{
-        address_lookup_table::error::AddressLookupError,
+        account::{AccountSharedData, ReadableAccount},
+        address_lookup_table::{self, error::AddressLookupError, state::AddressLookupTable},
         message::{
             v0::{LoadedAddresses, MessageAddressTableLookup},
             AddressLoaderError,
         },
+        pubkey::Pubkey,
         transaction::AddressLoader,
     },
+    std::collections::HashMap,
 };
 
 impl AddressLoader for &Bank {
@@ -34,3 +37,65 @@ impl AddressLoader for &Bank {
             .collect::<Result<_, AddressLookupError>>()?)
     }
 }
+
+/// Loads addresses for a transaction of a simulated bundle, preferring the lookup
+/// tables written by the earlier transactions of the bundle.
+///
+/// Tables written by the bundle are read as if the transaction landed in the slot
+/// after the bank's, so that addresses appended earlier in the bundle are usable.
+pub(super) struct BundleAddressLoader<'a> {
+    pub(super) bank: &'a Bank,
+    pub(super) bundle_accounts: &'a HashMap<Pubkey, AccountSharedData>,
+}
+
+impl AddressLoader for BundleAddressLoader<'_> {
+    fn load_addresses(
+        self,
+        address_table_lookups: &[MessageAddressTableLookup],
+    ) -> Result<LoadedAddresses, AddressLoaderError> {
+        let slot_hashes = self
+            .bank
+            .sysvar_cache
+            .read()
+            .unwrap()
+            .get_slot_hashes()
+            .map_err(|_| AddressLoaderError::SlotHashesSysvarNotFound)?;
+
+        Ok(address_table_lookups
+            .iter()
+            .map(|address_table_lookup| {
+                let Some(table_account) =
+                    self.bundle_accounts.get(&address_table_lookup.account_key)
+                else {
+                    return self.bank.rc.accounts.load_lookup_table_addresses(
+                        &self.bank.ancestors,
+                        address_table_lookup,
+                        &slot_hashes,
+                    );
+                };
+                if table_account.lamports() == 0 {
+                    return Err(AddressLookupError::LookupTableAccountNotFound);
+                }
+                if table_account.owner() != &address_lookup_table::program::id() {
+                    return Err(AddressLookupError::InvalidAccountOwner);
+                }
+
+                let current_slot = self.bank.slot().saturating_add(1);
+                let lookup_table = AddressLookupTable::deserialize(table_account.data())
+                    .map_err(|_ix_err| AddressLookupError::InvalidAccountData)?;
+                Ok(LoadedAddresses {
+                    writable: lookup_table.lookup(
+                        current_slot,
+                        &address_table_lookup.writable_indexes,
+                        &slot_hashes,
+                    )?,
+                    readonly: lookup_table.lookup(
+                        current_slot,
+                        &address_table_lookup.readonly_indexes,
+                        &slot_hashes,
+                    )?,
+                })
+            })
+            .collect::<Result<_, AddressLookupError>>()?)
+    }
+}
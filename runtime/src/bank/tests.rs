@@ -26,6 +26,7 @@ use {
     rayon::ThreadPoolBuilder,
     serde::{Deserialize, Serialize},
     solana_accounts_db::{
+        account_overrides::AccountOverrides,
         accounts::AccountAddressFilter,
         accounts_db::{AccountShrinkThreshold, DEFAULT_ACCOUNTS_SHRINK_RATIO},
         accounts_index::{
@@ -51,7 +52,7 @@ use {
     solana_sdk::{
         account::{
             accounts_equal, create_account_shared_data_with_fields as create_account, from_account,
-            Account, AccountSharedData, ReadableAccount, WritableAccount,
+            to_account, Account, AccountSharedData, ReadableAccount, WritableAccount,
         },
         account_utils::StateMut,
         bpf_loader,
@@ -239,6 +240,8 @@ fn new_execution_result(
             return_data: None,
             executed_units: 0,
             accounts_data_len_delta: 0,
+            loaded_accounts_data_size: 0,
+            execution_trace: None,
         },
         programs_modified_by_tx: Box::<LoadedProgramsForTxBatch>::default(),
     }
@@ -14192,10 +14195,130 @@ fn test_failed_simulation_compute_units() {
 
     bank.freeze();
     let sanitized = SanitizedTransaction::from_transaction_for_tests(transaction);
-    let simulation = bank.simulate_transaction(&sanitized, false);
+    let simulation = bank.simulate_transaction(&sanitized, false, false, None);
     assert_eq!(expected_consumed_units, simulation.units_consumed);
 }
 
+#[test]
+fn test_simulation_account_overrides() {
+    let (genesis_config, _mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let program_id = Pubkey::new_unique();
+    let bank =
+        Bank::new_with_mockup_builtin_for_tests(&genesis_config, program_id, MockBuiltin::vm).0;
+
+    const OVERRIDDEN_SLOT: Slot = 42;
+    declare_process_instruction!(MockBuiltin, 1, |invoke_context| {
+        let clock = invoke_context.get_sysvar_cache().get_clock()?;
+        if clock.slot != OVERRIDDEN_SLOT {
+            return Err(InstructionError::InvalidArgument);
+        }
+        Ok(())
+    });
+
+    let payer = Keypair::new();
+    let message = Message::new(
+        &[Instruction::new_with_bincode(program_id, &0, vec![])],
+        Some(&payer.pubkey()),
+    );
+    let transaction = Transaction::new(&[&payer], message, bank.last_blockhash());
+    let sanitized = SanitizedTransaction::from_transaction_for_tests(transaction);
+    bank.freeze();
+
+    // The payer does not exist and the program sees the bank's clock
+    let simulation = bank.simulate_transaction(&sanitized, false, false, None);
+    assert_eq!(simulation.result, Err(TransactionError::AccountNotFound));
+
+    let mut account_overrides = AccountOverrides::default();
+    account_overrides.set_account(
+        &payer.pubkey(),
+        Some(AccountSharedData::new(
+            LAMPORTS_PER_SOL,
+            0,
+            &system_program::id(),
+        )),
+    );
+    let simulation = bank.simulate_transaction(&sanitized, false, false, Some(&account_overrides));
+    assert_eq!(
+        simulation.result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+
+    let mut clock = bank.clock();
+    clock.slot = OVERRIDDEN_SLOT;
+    let mut clock_account = bank.get_account(&sysvar::clock::id()).unwrap();
+    to_account(&clock, &mut clock_account).unwrap();
+    account_overrides.set_account(&sysvar::clock::id(), Some(clock_account));
+    let simulation = bank.simulate_transaction(&sanitized, false, false, Some(&account_overrides));
+    assert_eq!(simulation.result, Ok(()));
+
+    // Nothing was committed
+    assert_eq!(bank.get_balance(&payer.pubkey()), 0);
+}
+
+#[test]
+fn test_simulate_bundle() {
+    let (genesis_config, mint_keypair) = create_genesis_config(10 * LAMPORTS_PER_SOL);
+    let bank = Bank::new_for_tests(&genesis_config);
+    bank.freeze();
+
+    // The second transfer is funded by the first one
+    let alice = Keypair::new();
+    let bob = Pubkey::new_unique();
+    let blockhash = bank.last_blockhash();
+    let fund_alice =
+        system_transaction::transfer(&mint_keypair, &alice.pubkey(), LAMPORTS_PER_SOL, blockhash);
+    let pay_bob = system_transaction::transfer(&alice, &bob, LAMPORTS_PER_SOL / 2, blockhash);
+    let simulation = bank.simulate_bundle(
+        vec![fund_alice.clone().into(), pay_bob.into()],
+        TransactionVerificationMode::FullVerification,
+        &[alice.pubkey(), bob],
+        false,
+    );
+    assert_eq!(simulation.result, Ok(()));
+    assert_eq!(simulation.transaction_results.len(), 2);
+    let post_accounts = &simulation.transaction_results[0].post_accounts;
+    assert_eq!(
+        post_accounts[0].1.as_ref().unwrap().lamports(),
+        LAMPORTS_PER_SOL
+    );
+    assert!(post_accounts[1].1.is_none());
+    let post_accounts = &simulation.transaction_results[1].post_accounts;
+    assert!(post_accounts[0].1.as_ref().unwrap().lamports() < LAMPORTS_PER_SOL / 2);
+    assert_eq!(
+        post_accounts[1].1.as_ref().unwrap().lamports(),
+        LAMPORTS_PER_SOL / 2
+    );
+    assert!(simulation
+        .transaction_results
+        .iter()
+        .all(|result| result.simulation.units_consumed > 0));
+
+    // Nothing was committed
+    assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    assert_eq!(bank.get_balance(&bob), 0);
+
+    // A failing transaction fails the bundle and stops the simulation
+    let overspend = system_transaction::transfer(&alice, &bob, 2 * LAMPORTS_PER_SOL, blockhash);
+    let simulation = bank.simulate_bundle(
+        vec![
+            fund_alice.clone().into(),
+            overspend.into(),
+            fund_alice.into(),
+        ],
+        TransactionVerificationMode::FullVerification,
+        &[],
+        false,
+    );
+    assert_matches!(
+        simulation.result,
+        Err((1, TransactionError::InstructionError(0, _)))
+    );
+    assert_eq!(simulation.transaction_results.len(), 2);
+}
+
 #[test]
 fn test_deploy_last_epoch_slot() {
     solana_logger::setup();
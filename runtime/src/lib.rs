@@ -4,6 +4,7 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod account_contention_cache;
 pub mod accounts;
 pub mod accounts_background_service;
 pub mod bank;
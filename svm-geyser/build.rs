@@ -0,0 +1,7 @@
+fn main() {
+    // The pod store stays open across a plugin reload, its RocksDB threads run
+    // code of this library, which must not be unmapped when it is unloaded
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("linux") {
+        println!("cargo:rustc-cdylib-link-arg=-Wl,-z,nodelete");
+    }
+}
@@ -1,27 +1,47 @@
-use std::path::{Path, PathBuf};
-use std::thread::sleep;
-use std::time::Duration;
+use std::path::PathBuf;
 use {
     crate::{
-        rocksdb_client::RocksDBConnection,
-        txn_settle::SvmTxnSelector,
-        txn_select::TxnStoreStruct,
+        pod::{PodSealingPolicy, SlotCommitment},
+        pod_metrics::{PodMetrics, PodMetricsConfig},
+        pod_retention::{PodPruner, PodRetentionPolicy},
+        pod_sink::PodExportConfig,
+        pod_writer::{PodWriter, PodWriterCommand},
+        rocksdb_client::{RocksDBConnection, RocksDBOptions},
+        slot_buffer::{BufferedTransaction, PodCommitment, SlotBuffer},
+        txn_select::{RocksDBAccountWrite, TxnStoreStruct},
+        txn_settle::{SvmTxnSelector, TransactionSelectorConfig},
     },
     log::*,
     serde_derive::{Deserialize, Serialize},
     serde_json,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
-        GeyserPlugin, GeyserPluginError, ReplicaTransactionInfoVersions, Result,
+        GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, ReplicaBlockInfoVersions,
+        ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions, Result, SlotStatus,
+    },
+    std::{
+        fs::File,
+        io::Read,
+        sync::{Arc, Mutex},
     },
-    std::{fs::File, io::Read},
 };
 use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaEntryInfoVersions;
 
+/// Directory of the pod store inside the configured `path`
+pub const POD_STORE_DIR: &str = "rocksdb_seq";
+
 #[derive(Default)]
 pub struct GeyserPluginRocksDB {
-    client: Option<RocksDBConnection>,
+    /// Applies the committed slots to the pod store, in notification order
+    writer: Option<PodWriter>,
+    pod_store_path: PathBuf,
+    rocksdb_options: RocksDBOptions,
     transaction_selector: Option<SvmTxnSelector>,
-    batch_starting_slot: Option<u64>,
+    sealing_policy: PodSealingPolicy,
+    pod_commitment: PodCommitment,
+    /// Record the accounts written by the selected transactions
+    account_diffs: bool,
+    slot_buffer: Mutex<SlotBuffer>,
+    metrics: Arc<PodMetrics>,
 }
 
 impl std::fmt::Debug for GeyserPluginRocksDB {
@@ -30,76 +50,295 @@ impl std::fmt::Debug for GeyserPluginRocksDB {
     }
 }
 
+/// The plugin config file
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct GeyserPluginRocksdbConfig {
+    /// Plugin library, read by the validator
+    pub libpath: Option<String>,
+    /// Plugin name, read by the validator
+    pub name: Option<String>,
+    /// Directory holding the pod store and the settled marker
+    pub path: PathBuf,
+    /// Deprecated and ignored, older config files still set it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub host: Option<String>,
+    #[serde(default)]
+    pub transaction_selector: TransactionSelectorConfig,
+    #[serde(default)]
+    pub pod_sealing: PodSealingPolicy,
+    #[serde(default)]
+    pub pod_commitment: PodCommitment,
+    #[serde(default)]
+    pub pod_retention: PodRetentionPolicy,
+    /// Record the accounts written by the selected transactions
+    #[serde(default)]
+    pub account_diffs: bool,
+    /// Reject the settings which make pods depend on the timing of the node,
+    /// every node loading the same config then writes the same pods
+    #[serde(default)]
+    pub deterministic_pods: bool,
+    #[serde(default)]
+    pub rocksdb: RocksDBOptions,
+    #[serde(default)]
+    pub metrics: PodMetricsConfig,
+    #[serde(default)]
+    pub pod_export: PodExportConfig,
 }
 
-static mut TX_COUNT_RECORD: u64 = 0;
+impl GeyserPluginRocksdbConfig {
+    /// Read and validate a config file
+    pub fn load(config_file: &str) -> Result<Self> {
+        let mut file = File::open(config_file)?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        Self::from_json(&contents).map_err(|e| GeyserPluginError::ConfigFileReadError {
+            msg: format!("Invalid config file {config_file}: {e}"),
+        })
+    }
+
+    pub fn from_json(json: &str) -> std::result::Result<Self, String> {
+        let config: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.path.as_os_str().is_empty() {
+            return Err("path must not be empty".to_string());
+        }
+        self.pod_sealing.validate()?;
+        if self.deterministic_pods {
+            // Slots are confirmed and pods time out at different times on each node
+            if self.pod_commitment != PodCommitment::Rooted {
+                return Err("deterministic_pods requires the rooted pod_commitment".to_string());
+            }
+            if self.pod_sealing.timeout_ms.is_some() {
+                return Err("deterministic_pods doesn't allow pod_sealing.timeout_ms".to_string());
+            }
+        }
+        self.pod_retention.validate()?;
+        self.rocksdb.validate()?;
+        self.metrics.validate()?;
+        self.pod_export.validate()
+    }
+
+    pub fn pod_store_path(&self) -> PathBuf {
+        self.path.join(POD_STORE_DIR)
+    }
+
+    pub fn create_transaction_selector(&self) -> Result<SvmTxnSelector> {
+        SvmTxnSelector::from_config(&self.transaction_selector)
+            .map_err(|msg| GeyserPluginError::ConfigFileReadError { msg })
+    }
+}
+
+/// The pod store of an unloaded plugin instance. `reloadPlugin` unloads the
+/// running instance before loading the new one, which takes the store over
+/// instead of reopening it. The library is linked with `-z nodelete` so that
+/// this state outlives the unload.
+struct ParkedPodStore {
+    path: PathBuf,
+    rocksdb_options: RocksDBOptions,
+    client: Arc<RocksDBConnection>,
+    /// Transactions of the slots which didn't reach the pod commitment yet
+    slot_buffer: SlotBuffer,
+}
+
+static PARKED_POD_STORE: Mutex<Option<ParkedPodStore>> = Mutex::new(None);
+
+/// Pod settings of a plugin config file, for the tools writing pods offline
+pub struct PodStoreConfig {
+    pub path: PathBuf,
+    pub transaction_selector: SvmTxnSelector,
+    pub sealing_policy: PodSealingPolicy,
+    /// The plugin records the account writes of the transactions, which the
+    /// ledger doesn't keep
+    pub account_diffs: bool,
+}
 
 impl GeyserPlugin for GeyserPluginRocksDB {
     fn name(&self) -> &'static str {
         "GeyserPluginRocksDB"
     }
 
-    fn on_load(&mut self, config_file: &str, is_bool: bool) -> Result<()> {
+    fn on_load(&mut self, config_file: &str, is_reload: bool) -> Result<()> {
         solana_logger::setup_with_default("info");
         info!(
-            "Loading plugin {:?} from config_file {:?}",
+            "Loading plugin {:?} from config_file {:?}, reload: {}",
             self.name(),
-            config_file
+            config_file,
+            is_reload
         );
-        let mut file = File::open(config_file)?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
+        let plugin_config = GeyserPluginRocksdbConfig::load(config_file)?;
+        if plugin_config.host.is_some() {
+            warn!("The host of {config_file:?} is deprecated and ignored");
+        }
+        self.transaction_selector = Some(plugin_config.create_transaction_selector()?);
+        self.sealing_policy = plugin_config.pod_sealing.clone();
+        self.pod_commitment = plugin_config.pod_commitment;
+        self.account_diffs = plugin_config.account_diffs;
+        self.pod_store_path = plugin_config.pod_store_path();
+        self.rocksdb_options = plugin_config.rocksdb.clone();
+        self.metrics = Arc::new(PodMetrics::new(plugin_config.metrics.clone()));
+        let seq_path = self.pod_store_path.clone();
 
-        let result: serde_json::Value = serde_json::from_str(&contents).unwrap();
-        self.transaction_selector = Some(Self::create_transaction_selector_from_config(&result));
+        let parked = PARKED_POD_STORE.lock().unwrap().take();
+        let config = match parked {
+            Some(parked)
+                if parked.path == seq_path && parked.rocksdb_options == self.rocksdb_options =>
+            {
+                info!("Taking over the open pod store {seq_path:?}");
+                // Only a reload continues the slots notified to the previous instance
+                if is_reload {
+                    *self.slot_buffer.lock().unwrap() = parked.slot_buffer;
+                }
+                parked.client
+            }
+            parked => {
+                // Close a store left open with other settings before opening this one
+                drop(parked);
+                let config = RocksDBConnection::open_with_options(&seq_path, &self.rocksdb_options)
+                    .map_err(|e| GeyserPluginError::ConfigFileReadError {
+                        msg: format!("Failed to open the pod store {seq_path:?}: {e}"),
+                    })?;
+                config.check_and_create_keys().map_err(|e| {
+                    GeyserPluginError::ConfigFileReadError {
+                        msg: format!("Failed to initialize the pod store {seq_path:?}: {e}"),
+                    }
+                })?;
+                config.migrate_legacy_batches().map_err(|e| {
+                    GeyserPluginError::ConfigFileReadError {
+                        msg: format!(
+                            "Failed to migrate {seq_path:?} to the binary pod format: {e}"
+                        ),
+                    }
+                })?;
+                Arc::new(config)
+            }
+        };
 
-        // Extract the path as a string without extra quotes
-        let path = result["path"].as_str().ok_or("Path not found or is not a string").unwrap();
-        let seq_path = Path::new(path).join("rocksdb_seq");
+        // Slots up to the watermark are already in the pods, replaying them after a restart
+        // must not append their transactions again
+        let last_processed_slot =
+            config
+                .get_last_processed_slot()
+                .map_err(|e| GeyserPluginError::ConfigFileReadError {
+                    msg: format!("Failed to read the last processed slot of {seq_path:?}: {e}"),
+                })?;
+        if let Some(last_processed_slot) = last_processed_slot {
+            info!("Resuming pod sequencing after slot {last_processed_slot}");
+            self.slot_buffer
+                .lock()
+                .unwrap()
+                .set_last_processed_slot(last_processed_slot);
+        }
 
-        let config = RocksDBConnection::open(&seq_path, false).unwrap();
-        config.check_and_create_keys().unwrap();
+        let exporters = plugin_config
+            .pod_export
+            .start_exporters(&config, &plugin_config.path)
+            .map_err(|e| GeyserPluginError::ConfigFileReadError {
+                msg: format!("Failed to start the pod exporters: {e}"),
+            })?;
 
-        self.client = Some(config);
+        // The settled marker is kept next to the pod store
+        let export_cursor_paths = plugin_config.pod_export.cursor_paths(&plugin_config.path);
+        let pruner = plugin_config.pod_retention.is_enabled().then(|| {
+            PodPruner::new(
+                plugin_config.pod_retention,
+                plugin_config.path,
+                export_cursor_paths,
+            )
+        });
+        self.writer = Some(PodWriter::new(
+            config,
+            self.sealing_policy.clone(),
+            pruner,
+            Arc::clone(&self.metrics),
+            exporters,
+        ));
 
         Ok(())
     }
 
+    fn on_unload(&mut self) {
+        info!("Unloading plugin: {:?}", self.name());
+        // The store is left open for the next instance, in case this is a reload
+        if let Some(client) = self.writer.take().and_then(|mut writer| writer.join()) {
+            *PARKED_POD_STORE.lock().unwrap() = Some(ParkedPodStore {
+                path: std::mem::take(&mut self.pod_store_path),
+                rocksdb_options: std::mem::take(&mut self.rocksdb_options),
+                client,
+                slot_buffer: std::mem::take(&mut *self.slot_buffer.lock().unwrap()),
+            });
+        }
+    }
+
     fn notify_transaction(
         &self,
         transaction_info: ReplicaTransactionInfoVersions,
         slot: u64,
     ) -> Result<()> {
-        match &self.client {
+        match &self.writer {
             None => {
-                return Err(GeyserPluginError::SlotStatusUpdateError {
+                return Err(self.notification_error(GeyserPluginError::SlotStatusUpdateError {
                     msg: "Failed to persist the transaction info to the Rocksdb. Rocksdb client not found.".to_string()
-                });
+                }));
             }
-            Some(client) => match transaction_info {
-                ReplicaTransactionInfoVersions::V0_0_2(transaction_info) => unsafe {
+            Some(_) => match Self::transaction_info_v2(&transaction_info) {
+                Some(transaction_info) => {
+                    let transaction_info = &transaction_info;
                     if let Some(transaction_selector) = &self.transaction_selector {
-                        if !transaction_selector.is_transaction_selected(
-                            transaction_info.is_vote,
-                            Box::new(transaction_info.transaction.message().account_keys().iter()),
-                        ) {
+                        let selected = transaction_selector.is_transaction_selected(
+                            transaction_info.transaction,
+                            transaction_info.transaction_status_meta,
+                        );
+                        self.metrics.record_transaction(selected);
+                        if !selected {
+                            // Writes recorded while the outcome was unknown
+                            if self.account_diffs {
+                                self.slot_buffer.lock().unwrap().take_account_writes(
+                                    slot,
+                                    &transaction_info.signature.to_string(),
+                                );
+                            }
                             return Ok(());
                         }
                     } else {
                         return Ok(());
                     }
 
-                    let txn_count = client.get_transaction_count().unwrap();
-
-                    let _ = TxnStoreStruct::tx_batch_store(client, transaction_info, slot);
+                    // Transactions are held back until their slot reaches the pod commitment
+                    let mut transaction =
+                        TxnStoreStruct::from_replica_transaction(transaction_info, slot);
+                    let mut slot_buffer = self.slot_buffer.lock().unwrap();
+                    if self.account_diffs {
+                        transaction.set_account_writes(
+                            slot_buffer
+                                .take_account_writes(slot, &transaction_info.signature.to_string()),
+                        );
+                    }
+                    let buffered = slot_buffer.push(
+                        slot,
+                        BufferedTransaction {
+                            index: transaction_info.index,
+                            signature: transaction_info.signature.to_string(),
+                            record: transaction.to_record(),
+                        },
+                    );
+                    if !buffered {
+                        self.metrics.record_dropped_transaction();
+                        warn!(
+                            "Dropping transaction {} of slot {slot}, at or below the newest root {:?}",
+                            transaction_info.signature,
+                            slot_buffer.newest_root(),
+                        );
+                    }
                 }
-                _ => {
-                    return Err(GeyserPluginError::SlotStatusUpdateError {
+                None => {
+                    return Err(self.notification_error(GeyserPluginError::SlotStatusUpdateError {
                         msg: "Failed to persist the transaction info to the RocksDB database. Unsupported format.".to_string()
-                    });
+                    }));
                 }
             },
         }
@@ -107,6 +346,131 @@ impl GeyserPlugin for GeyserPluginRocksDB {
         Ok(())
     }
 
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: u64,
+        is_startup: bool,
+    ) -> Result<()> {
+        // Accounts loaded from a snapshot aren't written by any transaction
+        if is_startup || !self.account_diffs {
+            return Ok(());
+        }
+
+        let account = match account {
+            ReplicaAccountInfoVersions::V0_0_3(account) => account,
+            _ => {
+                return Err(
+                    self.notification_error(GeyserPluginError::AccountsUpdateError {
+                        msg: "Failed to record the account write. Unsupported format.".to_string(),
+                    }),
+                );
+            }
+        };
+        let Some(txn) = account.txn else {
+            return Ok(());
+        };
+        // Only writes of transactions which make it into a pod are kept
+        match &self.transaction_selector {
+            Some(transaction_selector) if transaction_selector.may_select_transaction(txn) => {}
+            _ => return Ok(()),
+        }
+
+        if let Some(account_write) = RocksDBAccountWrite::from_replica_account(account) {
+            self.slot_buffer
+                .lock()
+                .unwrap()
+                .push_account_write(slot, account_write);
+        }
+        Ok(())
+    }
+
+    fn update_slot_status(
+        &self,
+        slot: u64,
+        parent: Option<u64>,
+        status: SlotStatus,
+    ) -> Result<()> {
+        let writer = match &self.writer {
+            None => {
+                return Err(self.notification_error(GeyserPluginError::SlotStatusUpdateError {
+                    msg: "Failed to persist the slot status to the Rocksdb. Rocksdb client not found.".to_string()
+                }));
+            }
+            Some(writer) => writer,
+        };
+        let send = |command| {
+            writer.send(command).map_err(|msg| {
+                self.notification_error(GeyserPluginError::SlotStatusUpdateError { msg })
+            })
+        };
+
+        // Slots are queued while the buffer is locked, so the writer sees them in commitment order
+        {
+            let mut slot_buffer = self.slot_buffer.lock().unwrap();
+            let committed_slots = match status {
+                SlotStatus::Processed => {
+                    if let Some(parent) = parent {
+                        slot_buffer.set_parent(slot, parent);
+                    }
+                    vec![]
+                }
+                SlotStatus::Confirmed if self.pod_commitment == PodCommitment::Confirmed => {
+                    slot_buffer.take_confirmed(slot)
+                }
+                SlotStatus::Confirmed => vec![],
+                SlotStatus::Rooted => slot_buffer.take_rooted(slot),
+            };
+            for (committed_slot, mut transactions) in committed_slots {
+                transactions.sort_by_key(|transaction| transaction.index);
+                send(PodWriterCommand::CommitSlot {
+                    slot: committed_slot,
+                    transactions,
+                })?;
+            }
+        }
+
+        send(PodWriterCommand::SlotStatus {
+            slot,
+            rooted: status == SlotStatus::Rooted,
+        })
+    }
+
+    fn notify_block_metadata(&self, blockinfo: ReplicaBlockInfoVersions) -> Result<()> {
+        let writer = match &self.writer {
+            None => {
+                return Err(self.notification_error(GeyserPluginError::SlotStatusUpdateError {
+                    msg: "Failed to persist the block info to the Rocksdb. Rocksdb client not found.".to_string()
+                }));
+            }
+            Some(writer) => writer,
+        };
+
+        // Only blocks carrying a bank hash can be committed to
+        let block_info = match blockinfo {
+            ReplicaBlockInfoVersions::V0_0_4(block_info) => block_info,
+            _ => return Ok(()),
+        };
+
+        let commitment = SlotCommitment {
+            slot: block_info.slot,
+            blockhash: block_info.blockhash.to_string(),
+            bank_hash: block_info.bank_hash.to_string(),
+            parent_bank_hash: block_info.parent_bank_hash.to_string(),
+            accounts_delta_hash: block_info.accounts_delta_hash.map(str::to_string),
+        };
+
+        writer
+            .send(PodWriterCommand::SlotCommitment(commitment))
+            .map_err(|msg| {
+                self.notification_error(GeyserPluginError::SlotStatusUpdateError { msg })
+            })
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        self.account_diffs
+    }
+
     fn transaction_notifications_enabled(&self) -> bool {
         self.transaction_selector
             .as_ref()
@@ -115,27 +479,51 @@ impl GeyserPlugin for GeyserPluginRocksDB {
 }
 
 impl GeyserPluginRocksDB {
-    fn create_transaction_selector_from_config(config: &serde_json::Value) -> SvmTxnSelector {
-        let transaction_selector = &config["transaction_selector"];
-
-        if transaction_selector.is_null() {
-            SvmTxnSelector::default()
-        } else {
-            let accounts = &transaction_selector["mentions"];
-            let accounts: Vec<String> = if accounts.is_array() {
-                accounts
-                    .as_array()
-                    .unwrap()
-                    .iter()
-                    .map(|val| val.as_str().unwrap().to_string())
-                    .collect()
-            } else {
-                Vec::default()
-            };
-            SvmTxnSelector::new(&accounts)
+    /// Count the notification errors returned to the validator
+    fn notification_error(&self, error: GeyserPluginError) -> GeyserPluginError {
+        self.metrics.record_notification_error();
+        error
+    }
+
+    /// The fields of a transaction notification stored in pods
+    fn transaction_info_v2<'a>(
+        transaction_info: &ReplicaTransactionInfoVersions<'a>,
+    ) -> Option<ReplicaTransactionInfoV2<'a>> {
+        match transaction_info {
+            ReplicaTransactionInfoVersions::V0_0_2(transaction_info) => {
+                Some((*transaction_info).clone())
+            }
+            ReplicaTransactionInfoVersions::V0_0_3(transaction_info) => {
+                Some(ReplicaTransactionInfoV2 {
+                    signature: transaction_info.signature,
+                    is_vote: transaction_info.is_vote,
+                    transaction: transaction_info.transaction,
+                    transaction_status_meta: transaction_info.transaction_status_meta,
+                    index: transaction_info.index,
+                })
+            }
+            ReplicaTransactionInfoVersions::V0_0_1(_) => None,
         }
     }
 
+    /// Read the pod store location, transaction selector and sealing policy the
+    /// plugin would use with `config_file`
+    pub fn load_pod_store_config(config_file: &str) -> Result<PodStoreConfig> {
+        let config = GeyserPluginRocksdbConfig::load(config_file)?;
+        Ok(PodStoreConfig {
+            path: config.pod_store_path(),
+            transaction_selector: config.create_transaction_selector()?,
+            sealing_policy: config.pod_sealing,
+            account_diffs: config.account_diffs,
+        })
+    }
+
+    /// Read the directory holding the settled marker of the plugin configured
+    /// with `config_file`
+    pub fn load_settled_marker_dir(config_file: &str) -> Result<PathBuf> {
+        Ok(GeyserPluginRocksdbConfig::load(config_file)?.path)
+    }
+
     pub fn new() -> Self {
         Self::default()
     }
@@ -154,15 +542,233 @@ pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use {super::*, serde_json};
+    use {
+        super::*,
+        crate::{
+            pod_sink::PodSinkConfig, rocksdb_client::RocksDBCompression,
+            txn_settle::TransactionStatusSelection,
+        },
+        std::path::Path,
+        tempfile::TempDir,
+    };
+
+    fn parse_config(sections: &str) -> std::result::Result<GeyserPluginRocksdbConfig, String> {
+        GeyserPluginRocksdbConfig::from_json(&format!(
+            "{{\"libpath\" : \"libsvm_geyser.so\", \"path\" : \"/tmp/pods\"{sections}}}"
+        ))
+    }
 
     #[test]
     fn test_accounts_selector_from_config() {
-        let config = "{\"transaction_selector\" : { \
-           \"mentions\" : [\"9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin\"] \
-        }}";
+        let config = parse_config(
+            ", \"transaction_selector\" : { \
+               \"mentions\" : [\"9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin\"] \
+            }",
+        )
+        .unwrap();
+        assert!(config.create_transaction_selector().unwrap().is_enabled());
 
-        let config: serde_json::Value = serde_json::from_str(config).unwrap();
-        GeyserPluginRocksDB::create_transaction_selector_from_config(&config);
+        let config = parse_config(
+            ", \"transaction_selector\" : { \
+               \"mentions\" : [\"*\"], \"exclude_votes\" : true, \"status\" : \"success\", \
+               \"include\" : { \"programs\" : [\"9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin\"] } \
+            }",
+        )
+        .unwrap();
+        assert!(config.transaction_selector.exclude_votes);
+        assert_eq!(
+            config.transaction_selector.status,
+            TransactionStatusSelection::Success
+        );
+        assert!(config.create_transaction_selector().is_ok());
+
+        let config =
+            parse_config(", \"transaction_selector\" : { \"mentions\" : [\"not-an-address\"] }")
+                .unwrap();
+        assert!(matches!(
+            config.create_transaction_selector(),
+            Err(GeyserPluginError::ConfigFileReadError { .. })
+        ));
+        for sections in [
+            ", \"transaction_selector\" : { \"mentions\" : [1] }",
+            ", \"transaction_selector\" : { \"status\" : \"pending\" }",
+            ", \"transaction_selector\" : { \"exclude\" : { \"owners\" : [] } }",
+        ] {
+            assert!(parse_config(sections).is_err());
+        }
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config = parse_config("").unwrap();
+        assert_eq!(
+            config.pod_store_path(),
+            Path::new("/tmp/pods").join(POD_STORE_DIR)
+        );
+        assert!(!config.create_transaction_selector().unwrap().is_enabled());
+        assert_eq!(config.pod_sealing, PodSealingPolicy::default());
+        assert_eq!(config.pod_commitment, PodCommitment::Rooted);
+        assert!(!config.pod_retention.is_enabled());
+        assert!(config.pod_retention.settled_only);
+        assert!(!config.account_diffs);
+        assert!(!config.deterministic_pods);
+        assert_eq!(config.rocksdb, RocksDBOptions::default());
+        assert_eq!(config.metrics, PodMetricsConfig::default());
+
+        // The path is the only required field
+        assert!(GeyserPluginRocksdbConfig::from_json("{}").is_err());
+        assert!(GeyserPluginRocksdbConfig::from_json("{\"path\" : \"\"}").is_err());
+        // The deprecated host is accepted and ignored
+        let config = parse_config(", \"host\" : \"localhost\"").unwrap();
+        assert_eq!(config.host.as_deref(), Some("localhost"));
+        assert!(parse_config(", \"unknown\" : \"localhost\"").is_err());
+    }
+
+    #[test]
+    fn test_config_sections() {
+        let config = parse_config(
+            ", \"pod_commitment\" : \"confirmed\", \"account_diffs\" : true, \
+             \"pod_sealing\" : { \
+               \"max_transactions\" : 100, \"timeout_ms\" : 2000, \"seal_on_root\" : true \
+             }, \
+             \"pod_retention\" : { \
+               \"max_pods\" : 1000, \"settled_only\" : false, \"archive_dir\" : \"/tmp/archive\" \
+             }, \
+             \"rocksdb\" : { \
+               \"compression\" : \"zstd\", \"write_buffer_size_mb\" : 64, \"sync_wal\" : true \
+             }, \
+             \"metrics\" : { \"enabled\" : false }, \
+             \"pod_export\" : { \"sinks\" : [ \
+               { \"type\" : \"directory\", \"name\" : \"files\", \"path\" : \"/tmp/export\" }, \
+               { \"type\" : \"http\", \"name\" : \"relayer\", \"url\" : \"http://127.0.0.1:8080\" } \
+             ] }",
+        )
+        .unwrap();
+        assert_eq!(config.pod_commitment, PodCommitment::Confirmed);
+        assert!(config.account_diffs);
+        assert_eq!(config.pod_sealing.max_transactions, Some(100));
+        assert_eq!(config.pod_sealing.timeout_ms, Some(2000));
+        assert!(config.pod_sealing.seal_on_root);
+        assert_eq!(config.pod_retention.max_pods, Some(1000));
+        assert!(!config.pod_retention.settled_only);
+        assert_eq!(
+            config.pod_retention.archive_dir,
+            Some(PathBuf::from("/tmp/archive"))
+        );
+        assert_eq!(config.rocksdb.compression, Some(RocksDBCompression::Zstd));
+        assert_eq!(config.rocksdb.write_buffer_size_mb, Some(64));
+        assert_eq!(config.rocksdb.max_write_buffer_number, None);
+        assert!(config.rocksdb.sync_wal);
+        assert!(!config.metrics.enabled);
+        assert_eq!(config.metrics.report_interval_ms, 10_000);
+        assert_eq!(
+            config.pod_export.sinks,
+            vec![
+                PodSinkConfig::Directory {
+                    name: "files".to_string(),
+                    path: PathBuf::from("/tmp/export"),
+                },
+                PodSinkConfig::Http {
+                    name: "relayer".to_string(),
+                    url: "http://127.0.0.1:8080".to_string(),
+                    timeout_ms: 10_000,
+                },
+            ]
+        );
+
+        for sections in [
+            ", \"pod_commitment\" : \"processed\"",
+            ", \"account_diffs\" : \"yes\"",
+            ", \"pod_sealing\" : { \"max_slots\" : 0 }",
+            ", \"pod_sealing\" : { \"max_slot\" : 4 }",
+            ", \"pod_retention\" : { \"max_age_secs\" : 0 }",
+            ", \"rocksdb\" : { \"compression\" : \"brotli\" }",
+            ", \"rocksdb\" : { \"max_write_buffer_number\" : 1 }",
+            ", \"metrics\" : { \"report_interval_ms\" : 0 }",
+            ", \"deterministic_pods\" : true, \"pod_commitment\" : \"confirmed\"",
+            ", \"deterministic_pods\" : true, \"pod_sealing\" : { \"timeout_ms\" : 100 }",
+            ", \"pod_export\" : { \"sinks\" : [{ \"type\" : \"s3\", \"name\" : \"a\" }] }",
+            ", \"pod_export\" : { \"sinks\" : [{ \"type\" : \"http\", \"name\" : \"../a\", \"url\" : \"http://a\" }] }",
+        ] {
+            assert!(parse_config(sections).is_err(), "{sections}");
+        }
+        assert!(parse_config(
+            ", \"deterministic_pods\" : true, \"pod_sealing\" : { \"seal_on_root\" : true }"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_reload_takes_over_the_pod_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let write_config = |mentions: &str| {
+            let config_file = temp_dir.path().join("config.json");
+            std::fs::write(
+                &config_file,
+                format!(
+                    "{{\"path\" : {:?}, \"transaction_selector\" : {{ \"mentions\" : [{mentions}] }}}}",
+                    temp_dir.path()
+                ),
+            )
+            .unwrap();
+            config_file.to_str().unwrap().to_string()
+        };
+
+        let mut plugin = GeyserPluginRocksDB::new();
+        plugin
+            .on_load(&write_config("\"all_votes\""), false)
+            .unwrap();
+        plugin.slot_buffer.lock().unwrap().push(
+            2,
+            BufferedTransaction {
+                index: 0,
+                signature: "signature".to_string(),
+                record: vec![],
+            },
+        );
+        plugin.on_unload();
+
+        // The store is still open, so only taking it over can succeed
+        let mut plugin = GeyserPluginRocksDB::new();
+        plugin.on_load(&write_config("\"*\""), true).unwrap();
+        assert!(
+            plugin
+                .transaction_selector
+                .as_ref()
+                .unwrap()
+                .select_all_transactions
+        );
+        assert_eq!(plugin.slot_buffer.lock().unwrap().pending_slots(), 1);
+        plugin.on_unload();
+        drop(PARKED_POD_STORE.lock().unwrap().take());
+        assert!(RocksDBConnection::open(&temp_dir.path().join(POD_STORE_DIR), false).is_ok());
+    }
+
+    #[test]
+    fn test_load_pod_store_config() {
+        let mut config_file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(
+            &mut config_file,
+            b"{\"path\" : \"/tmp/pods\", \"transaction_selector\" : { \"mentions\" : [\"*\"] }, \
+              \"pod_sealing\" : { \"max_slots\" : 4 }}",
+        )
+        .unwrap();
+        let config =
+            GeyserPluginRocksDB::load_pod_store_config(config_file.path().to_str().unwrap())
+                .unwrap();
+        assert_eq!(config.path, Path::new("/tmp/pods").join(POD_STORE_DIR));
+        assert!(config.transaction_selector.select_all_transactions);
+        assert_eq!(config.sealing_policy.max_slots, Some(4));
+        assert_eq!(
+            GeyserPluginRocksDB::load_settled_marker_dir(config_file.path().to_str().unwrap())
+                .unwrap(),
+            Path::new("/tmp/pods")
+        );
+
+        std::io::Write::write_all(&mut config_file, b"}").unwrap();
+        assert!(
+            GeyserPluginRocksDB::load_pod_store_config(config_file.path().to_str().unwrap())
+                .is_err()
+        );
     }
 }
@@ -1,4 +1,10 @@
 pub mod geyser_plugin;
+pub mod pod;
+pub mod pod_metrics;
+pub mod pod_retention;
+pub mod pod_sink;
+pub mod pod_writer;
 pub mod rocksdb_client;
+pub mod slot_buffer;
 pub mod txn_select;
 pub mod txn_settle;
@@ -0,0 +1,521 @@
+use {
+    serde_derive::{Deserialize, Serialize},
+    solana_merkle_tree::{
+        merkle_tree::{Proof, ProofEntry},
+        MerkleTree,
+    },
+    solana_sdk::{
+        clock::Slot,
+        hash::{hashv, Hash},
+    },
+    std::{collections::HashMap, error::Error},
+};
+
+// * Number of slots for which reported commitments are kept around
+const MAX_TRACKED_SLOTS: u64 = 512;
+// * Pod size used when no sealing policy is configured
+pub const DEFAULT_MAX_POD_TRANSACTIONS: usize = 25;
+// * Version of the binary encoding of pod records, headers and bookkeeping
+pub const POD_FORMAT_VERSION: u8 = 1;
+// * Prefix of the leaf hashes of `MerkleTree`
+const LEAF_PREFIX: &[u8] = &[0];
+
+/// Encode a value stored in a pod, prefixed with the pod format version
+pub fn encode_versioned<T: serde::Serialize>(value: &T) -> bincode::Result<Vec<u8>> {
+    let mut data = vec![POD_FORMAT_VERSION];
+    bincode::serialize_into(&mut data, value)?;
+    Ok(data)
+}
+
+/// Decode a value written by `encode_versioned`
+pub fn decode_versioned<T: serde::de::DeserializeOwned>(data: &[u8]) -> Result<T, Box<dyn Error>> {
+    match data.split_first() {
+        Some((&POD_FORMAT_VERSION, payload)) => Ok(bincode::deserialize(payload)?),
+        Some((version, _)) => Err(format!("Unsupported pod format version {version}").into()),
+        None => Err("Empty pod record".into()),
+    }
+}
+
+/// Why a pod was sealed
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SealReason {
+    #[default]
+    MaxTransactions,
+    MaxBytes,
+    MaxSlots,
+    Timeout,
+    Rooted,
+}
+
+/// Decides when the open pod is sealed, configured through the `pod_sealing`
+/// section of the plugin config. A pod is sealed as soon as any of the
+/// configured limits is reached.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PodSealingPolicy {
+    /// Maximum number of transactions in a pod
+    pub max_transactions: Option<usize>,
+    /// Maximum size of the serialized transaction records in a pod
+    pub max_bytes: Option<usize>,
+    /// Maximum number of slots covered by a pod
+    pub max_slots: Option<u64>,
+    /// Maximum time a pod stays open after its first transaction
+    pub timeout_ms: Option<u64>,
+    /// Seal the open pod every time a slot is rooted
+    pub seal_on_root: bool,
+}
+
+impl Default for PodSealingPolicy {
+    fn default() -> Self {
+        Self {
+            max_transactions: Some(DEFAULT_MAX_POD_TRANSACTIONS),
+            max_bytes: None,
+            max_slots: None,
+            timeout_ms: None,
+            seal_on_root: false,
+        }
+    }
+}
+
+impl PodSealingPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_transactions == Some(0) {
+            return Err("pod_sealing.max_transactions must be greater than 0".to_string());
+        }
+        if self.max_bytes == Some(0) {
+            return Err("pod_sealing.max_bytes must be greater than 0".to_string());
+        }
+        if self.max_slots == Some(0) {
+            return Err("pod_sealing.max_slots must be greater than 0".to_string());
+        }
+        if self.timeout_ms == Some(0) {
+            return Err("pod_sealing.timeout_ms must be greater than 0".to_string());
+        }
+        if self.max_transactions.is_none()
+            && self.max_bytes.is_none()
+            && self.max_slots.is_none()
+            && self.timeout_ms.is_none()
+            && !self.seal_on_root
+        {
+            return Err("pod_sealing must set at least one sealing condition".to_string());
+        }
+        Ok(())
+    }
+
+    /// Check if the open pod must be sealed before a transaction of `slot` is added
+    pub fn seal_before(&self, open_pod: &OpenPod, slot: Slot) -> Option<SealReason> {
+        self.max_slots
+            .filter(|max_slots| slot >= open_pod.first_slot.saturating_add(*max_slots))
+            .map(|_| SealReason::MaxSlots)
+    }
+
+    /// Check if the open pod is full after a transaction was added
+    pub fn seal_after(&self, open_pod: &OpenPod) -> Option<SealReason> {
+        if self.max_transactions.map_or(false, |max_transactions| {
+            open_pod.transaction_count >= max_transactions as u64
+        })
+        {
+            Some(SealReason::MaxTransactions)
+        } else if self
+            .max_bytes
+            .map_or(false, |max_bytes| open_pod.byte_count >= max_bytes)
+        {
+            Some(SealReason::MaxBytes)
+        } else {
+            None
+        }
+    }
+
+    /// Check if the open pod has been open for too long
+    pub fn is_expired(&self, open_pod: &OpenPod, now_ms: u64) -> bool {
+        self.timeout_ms.map_or(false, |timeout_ms| {
+            now_ms.saturating_sub(open_pod.opened_at_ms) >= timeout_ms
+        })
+    }
+}
+
+/// Bookkeeping for the pod currently being filled
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct OpenPod {
+    pub first_slot: Slot,
+    pub last_slot: Slot,
+    pub byte_count: usize,
+    pub opened_at_ms: u64,
+    pub transaction_count: u64,
+}
+
+impl OpenPod {
+    pub fn new(slot: Slot, opened_at_ms: u64) -> Self {
+        Self {
+            first_slot: slot,
+            last_slot: slot,
+            byte_count: 0,
+            opened_at_ms,
+            transaction_count: 0,
+        }
+    }
+}
+
+/// Commitment to the bank state before and after a slot, as reported through
+/// the block metadata notification.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotCommitment {
+    pub slot: Slot,
+    pub blockhash: String,
+    /// Bank hash at the end of the slot
+    pub bank_hash: String,
+    /// Bank hash of the parent slot, the state the slot was executed on
+    pub parent_bank_hash: String,
+    pub accounts_delta_hash: Option<String>,
+}
+
+/// Where a transaction was stored, indexed by signature
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionLocation {
+    pub pod_number: u64,
+    /// Position of the transaction in the pod
+    pub index: u64,
+    pub slot: Slot,
+}
+
+/// Header written alongside every sealed pod.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodHeader {
+    pub pod_number: u64,
+    /// Set once the pod is sealed, no transaction is added to it afterwards
+    pub sealed: bool,
+    pub seal_reason: SealReason,
+    /// Pod sealed right before this one, `None` for the first pod
+    pub parent_pod_number: Option<u64>,
+    pub transaction_count: u64,
+    /// Total size of the stored transaction records
+    pub byte_count: u64,
+    pub first_slot: Slot,
+    pub last_slot: Slot,
+    /// Merkle root over the stored transaction records, in pod order
+    pub transactions_root: String,
+    /// Bank state of `first_slot`, its `parent_bank_hash` is the state before the pod
+    pub first_slot_state: Option<SlotCommitment>,
+    /// Bank state of `last_slot`, its `bank_hash` is the state after the pod
+    pub last_slot_state: Option<SlotCommitment>,
+}
+
+impl PodHeader {
+    pub fn new<T: AsRef<[u8]>>(
+        pod_number: u64,
+        transactions: &[T],
+        first_slot: Slot,
+        last_slot: Slot,
+        seal_reason: SealReason,
+        commitments: &SlotCommitmentTracker,
+    ) -> Self {
+        Self {
+            pod_number,
+            sealed: true,
+            seal_reason,
+            parent_pod_number: pod_number.checked_sub(1).filter(|parent| *parent > 0),
+            transaction_count: transactions.len() as u64,
+            byte_count: transactions
+                .iter()
+                .map(|transaction| transaction.as_ref().len() as u64)
+                .sum(),
+            first_slot,
+            last_slot,
+            transactions_root: transactions_root(transactions).to_string(),
+            first_slot_state: commitments.get(first_slot).cloned(),
+            last_slot_state: commitments.get(last_slot).cloned(),
+        }
+    }
+
+    /// Bank hash the first transaction of the pod was executed on
+    pub fn pre_state_root(&self) -> Option<&str> {
+        self.first_slot_state
+            .as_ref()
+            .map(|state| state.parent_bank_hash.as_str())
+    }
+
+    /// Bank hash at the end of the last slot covered by the pod
+    pub fn post_state_root(&self) -> Option<&str> {
+        self.last_slot_state
+            .as_ref()
+            .map(|state| state.bank_hash.as_str())
+    }
+
+    /// Check if both slot commitments have been recorded
+    pub fn is_complete(&self) -> bool {
+        self.first_slot_state.is_some() && self.last_slot_state.is_some()
+    }
+
+    /// Record the commitment of a slot covered by this pod.
+    /// Returns true if the header was updated.
+    pub fn set_slot_commitment(&mut self, commitment: &SlotCommitment) -> bool {
+        let mut updated = false;
+        if commitment.slot == self.first_slot && self.first_slot_state.is_none() {
+            self.first_slot_state = Some(commitment.clone());
+            updated = true;
+        }
+        if commitment.slot == self.last_slot && self.last_slot_state.is_none() {
+            self.last_slot_state = Some(commitment.clone());
+            updated = true;
+        }
+        updated
+    }
+}
+
+/// Merkle root over the transaction records of a pod
+pub fn transactions_root<T: AsRef<[u8]>>(transactions: &[T]) -> Hash {
+    MerkleTree::new(transactions)
+        .get_root()
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Leaf hash of a transaction record, the `leaf` of its `TransactionProof`
+pub fn hash_leaf(record: &[u8]) -> Hash {
+    hashv(&[LEAF_PREFIX, record])
+}
+
+/// Step of a merkle path, `target` hashes the path so far with the sibling
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionProofEntry {
+    pub target: Hash,
+    pub left_sibling: Option<Hash>,
+    pub right_sibling: Option<Hash>,
+}
+
+/// Merkle path from a transaction record to the `transactions_root` of its pod
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionProof {
+    /// Leaf hash of the record
+    pub leaf: Hash,
+    pub entries: Vec<TransactionProofEntry>,
+}
+
+impl TransactionProof {
+    /// Proof of the record at `index` among the records of a pod
+    pub fn new<T: AsRef<[u8]>>(transactions: &[T], index: usize) -> Option<Self> {
+        let tree = MerkleTree::new(transactions);
+        let path = tree.find_path(index)?;
+        Some(Self {
+            leaf: *tree.get_leaf(index)?,
+            entries: path
+                .entries()
+                .iter()
+                .map(|entry| TransactionProofEntry {
+                    target: *entry.target(),
+                    left_sibling: entry.left_sibling().copied(),
+                    right_sibling: entry.right_sibling().copied(),
+                })
+                .collect(),
+        })
+    }
+
+    pub fn verify(&self, transactions_root: &Hash) -> bool {
+        let mut proof = Proof::default();
+        for entry in &self.entries {
+            if entry.left_sibling.is_some() == entry.right_sibling.is_some() {
+                return false;
+            }
+            proof.push(ProofEntry::new(
+                &entry.target,
+                entry.left_sibling.as_ref(),
+                entry.right_sibling.as_ref(),
+            ));
+        }
+        let root = self.entries.last().map_or(&self.leaf, |entry| &entry.target);
+        root == transactions_root && proof.verify(self.leaf)
+    }
+}
+
+/// Keeps the commitments of recently frozen slots and the sealed pods that are
+/// still waiting for one of them.
+///
+/// Transactions are notified before the bank of their slot is frozen, so a pod
+/// sealed in the middle of a slot only learns the commitment of that slot later.
+#[derive(Default)]
+pub struct SlotCommitmentTracker {
+    commitments: HashMap<Slot, SlotCommitment>,
+    pending_pods: HashMap<Slot, Vec<u64>>,
+}
+
+impl SlotCommitmentTracker {
+    pub fn get(&self, slot: Slot) -> Option<&SlotCommitment> {
+        self.commitments.get(&slot)
+    }
+
+    /// Remember the slots a freshly sealed header is still missing
+    pub fn track_pending(&mut self, header: &PodHeader) {
+        if header.first_slot_state.is_none() {
+            self.pending_pods
+                .entry(header.first_slot)
+                .or_default()
+                .push(header.pod_number);
+        }
+        if header.last_slot_state.is_none() && header.last_slot != header.first_slot {
+            self.pending_pods
+                .entry(header.last_slot)
+                .or_default()
+                .push(header.pod_number);
+        }
+    }
+
+    /// Record a slot commitment and return the sealed pods waiting for it
+    pub fn insert(&mut self, commitment: SlotCommitment) -> Vec<u64> {
+        let slot = commitment.slot;
+        self.commitments.insert(slot, commitment);
+        let min_slot = slot.saturating_sub(MAX_TRACKED_SLOTS);
+        self.commitments.retain(|slot, _| *slot >= min_slot);
+        self.pending_pods.retain(|slot, _| *slot >= min_slot);
+        self.pending_pods.remove(&slot).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn commitment(slot: Slot) -> SlotCommitment {
+        SlotCommitment {
+            slot,
+            blockhash: Hash::new_unique().to_string(),
+            bank_hash: Hash::new_unique().to_string(),
+            parent_bank_hash: Hash::new_unique().to_string(),
+            accounts_delta_hash: Some(Hash::new_unique().to_string()),
+        }
+    }
+
+    #[test]
+    fn test_versioned_encoding() {
+        let header = PodHeader {
+            pod_number: 3,
+            sealed: true,
+            parent_pod_number: Some(2),
+            last_slot_state: Some(commitment(7)),
+            ..PodHeader::default()
+        };
+        let data = encode_versioned(&header).unwrap();
+        assert_eq!(data[0], POD_FORMAT_VERSION);
+        assert_eq!(decode_versioned::<PodHeader>(&data).unwrap(), header);
+
+        let mut unknown_version = data;
+        unknown_version[0] = POD_FORMAT_VERSION + 1;
+        assert!(decode_versioned::<PodHeader>(&unknown_version).is_err());
+        assert!(decode_versioned::<PodHeader>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_transactions_root() {
+        let transactions = ["txn-1", "txn-2", "txn-3"];
+        let root = transactions_root(&transactions);
+        assert_eq!(root, *MerkleTree::new(&transactions).get_root().unwrap());
+        assert_ne!(root, transactions_root(&["txn-1", "txn-3", "txn-2"]));
+        assert_eq!(transactions_root::<&str>(&[]), Hash::default());
+    }
+
+    #[test]
+    fn test_transaction_proof() {
+        let transactions = ["txn-1", "txn-2", "txn-3", "txn-4", "txn-5"];
+        let root = transactions_root(&transactions);
+        for index in 0..transactions.len() {
+            let proof = TransactionProof::new(&transactions, index).unwrap();
+            assert_eq!(proof.leaf, hash_leaf(transactions[index].as_bytes()));
+            assert!(proof.verify(&root));
+            assert!(!proof.verify(&transactions_root(&["txn-1"])));
+        }
+        assert_eq!(TransactionProof::new(&transactions, 5), None);
+
+        let mut proof = TransactionProof::new(&transactions, 2).unwrap();
+        proof.leaf = TransactionProof::new(&transactions, 3).unwrap().leaf;
+        assert!(!proof.verify(&root));
+
+        // A single record is its own root
+        let proof = TransactionProof::new(&["txn-1"], 0).unwrap();
+        assert!(proof.entries.is_empty());
+        assert!(proof.verify(&transactions_root(&["txn-1"])));
+    }
+
+    #[test]
+    fn test_pod_header_pending_commitments() {
+        let mut tracker = SlotCommitmentTracker::default();
+        assert!(tracker.insert(commitment(10)).is_empty());
+
+        let mut header = PodHeader::new(
+            1,
+            &["txn-1", "txn-2"],
+            10,
+            11,
+            SealReason::MaxTransactions,
+            &tracker,
+        );
+        assert_eq!(header.parent_pod_number, None);
+        assert_eq!(header.transaction_count, 2);
+        assert_eq!(header.byte_count, 10);
+        assert_eq!(header.first_slot_state, tracker.get(10).cloned());
+        assert_eq!(
+            header.pre_state_root(),
+            Some(tracker.get(10).unwrap().parent_bank_hash.as_str())
+        );
+        assert!(header.last_slot_state.is_none());
+        assert!(!header.is_complete());
+
+        tracker.track_pending(&header);
+        let slot_11 = commitment(11);
+        assert_eq!(tracker.insert(slot_11.clone()), vec![1]);
+        assert!(header.set_slot_commitment(&slot_11));
+        assert!(!header.set_slot_commitment(&slot_11));
+        assert!(header.is_complete());
+        assert_eq!(header.post_state_root(), Some(slot_11.bank_hash.as_str()));
+
+        let header = PodHeader::new(2, &["txn-3"], 12, 12, SealReason::Rooted, &tracker);
+        assert_eq!(header.parent_pod_number, Some(1));
+        tracker.track_pending(&header);
+        assert_eq!(tracker.insert(commitment(12)), vec![2]);
+    }
+
+    #[test]
+    fn test_sealing_policy() {
+        assert!(PodSealingPolicy::default().validate().is_ok());
+        let policy: PodSealingPolicy = serde_json::from_str("{}").unwrap();
+        assert_eq!(policy, PodSealingPolicy::default());
+        assert!(serde_json::from_str::<PodSealingPolicy>("{\"max_txns\": 1}").is_err());
+
+        let policy: PodSealingPolicy = serde_json::from_str(
+            "{\"max_transactions\": null, \"max_bytes\": 100, \"max_slots\": 2, \"timeout_ms\": 1000}",
+        )
+        .unwrap();
+        assert!(policy.validate().is_ok());
+
+        let open_pod = OpenPod {
+            first_slot: 10,
+            last_slot: 11,
+            byte_count: 60,
+            opened_at_ms: 5_000,
+            transaction_count: 1_000,
+        };
+        assert_eq!(policy.seal_before(&open_pod, 11), None);
+        assert_eq!(policy.seal_before(&open_pod, 12), Some(SealReason::MaxSlots));
+        assert_eq!(policy.seal_after(&open_pod), None);
+        let full_pod = OpenPod {
+            byte_count: 100,
+            ..open_pod.clone()
+        };
+        assert_eq!(policy.seal_after(&full_pod), Some(SealReason::MaxBytes));
+        assert!(!policy.is_expired(&open_pod, 5_999));
+        assert!(policy.is_expired(&open_pod, 6_000));
+
+        let policy = PodSealingPolicy {
+            max_transactions: None,
+            ..PodSealingPolicy::default()
+        };
+        assert!(policy.validate().is_err());
+        let policy = PodSealingPolicy {
+            max_transactions: Some(0),
+            ..PodSealingPolicy::default()
+        };
+        assert!(policy.validate().is_err());
+    }
+}
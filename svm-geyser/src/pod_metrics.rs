@@ -0,0 +1,206 @@
+//! Metrics of the pod sequencing, reported through solana-metrics.
+//!
+//! Counters are accumulated by the notification and writer threads and
+//! reported as the `svm_geyser-pods` datapoint every `report_interval_ms`.
+//! Every sealed pod is reported on its own as a `svm_geyser-pod_sealed`
+//! datapoint.
+
+use {
+    crate::pod::PodHeader,
+    serde_derive::{Deserialize, Serialize},
+    solana_metrics::datapoint_info,
+    solana_sdk::{clock::Slot, timing::AtomicInterval},
+    std::sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Metrics reported by the plugin, configured through the `metrics` section
+/// of the plugin config
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PodMetricsConfig {
+    pub enabled: bool,
+    /// Interval between two reports of the pod counters
+    pub report_interval_ms: u64,
+}
+
+impl Default for PodMetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            report_interval_ms: 10_000,
+        }
+    }
+}
+
+impl PodMetricsConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.report_interval_ms == 0 {
+            return Err("metrics.report_interval_ms must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct PodMetrics {
+    config: PodMetricsConfig,
+    last_report: AtomicInterval,
+    transactions_notified: AtomicU64,
+    transactions_selected: AtomicU64,
+    /// Selected transactions notified for a slot at or below the newest root
+    transactions_dropped: AtomicU64,
+    slots_written: AtomicU64,
+    write_us: AtomicU64,
+    notification_errors: AtomicU64,
+    writer_errors: AtomicU64,
+    /// Latest rooted slot seen by the writer
+    root_slot: AtomicU64,
+    /// Latest slot whose transactions are in the pods
+    last_pod_slot: AtomicU64,
+}
+
+impl PodMetrics {
+    pub fn new(config: PodMetricsConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Count a transaction notification and whether the selector kept it
+    pub fn record_transaction(&self, selected: bool) {
+        self.transactions_notified.fetch_add(1, Ordering::Relaxed);
+        if selected {
+            self.transactions_selected.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Count a selected transaction which came too late to be added to a pod
+    pub fn record_dropped_transaction(&self) {
+        self.transactions_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count the write of a committed slot to the pod store
+    pub fn record_slot_write(&self, write_us: u64) {
+        self.slots_written.fetch_add(1, Ordering::Relaxed);
+        self.write_us.fetch_add(write_us, Ordering::Relaxed);
+    }
+
+    pub fn record_notification_error(&self) {
+        self.notification_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_writer_error(&self) {
+        self.writer_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_root(&self, root_slot: Slot, last_pod_slot: Option<Slot>) {
+        self.root_slot.store(root_slot, Ordering::Relaxed);
+        if let Some(last_pod_slot) = last_pod_slot {
+            self.last_pod_slot.store(last_pod_slot, Ordering::Relaxed);
+        }
+    }
+
+    /// `seal_latency_ms` is the time the pod stayed open, when known
+    pub fn report_sealed_pod(&self, header: &PodHeader, seal_latency_ms: Option<u64>) {
+        if !self.is_enabled() {
+            return;
+        }
+        datapoint_info!(
+            "svm_geyser-pod_sealed",
+            ("pod_number", header.pod_number, i64),
+            ("transactions", header.transaction_count, i64),
+            ("bytes", header.byte_count, i64),
+            ("slots", header.last_slot.saturating_sub(header.first_slot) + 1, i64),
+            ("seal_latency_ms", seal_latency_ms, Option<i64>),
+            ("seal_reason", format!("{:?}", header.seal_reason), String),
+        );
+    }
+
+    /// Report the counters once the report interval elapsed
+    pub fn maybe_report(&self) {
+        if self.is_enabled()
+            && self
+                .last_report
+                .should_update(self.config.report_interval_ms)
+        {
+            self.report();
+        }
+    }
+
+    fn report(&self) {
+        let root_slot = self.root_slot.load(Ordering::Relaxed);
+        let last_pod_slot = self.last_pod_slot.load(Ordering::Relaxed);
+        datapoint_info!(
+            "svm_geyser-pods",
+            (
+                "transactions_notified",
+                self.transactions_notified.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "transactions_selected",
+                self.transactions_selected.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "transactions_dropped",
+                self.transactions_dropped.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "slots_written",
+                self.slots_written.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            ("write_us", self.write_us.swap(0, Ordering::Relaxed), i64),
+            (
+                "notification_errors",
+                self.notification_errors.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "writer_errors",
+                self.writer_errors.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            ("root_slot", root_slot, i64),
+            ("last_pod_slot", last_pod_slot, i64),
+            ("slot_lag", root_slot.saturating_sub(last_pod_slot), i64),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pod_metrics_counters_reset_on_report() {
+        let metrics = PodMetrics::new(PodMetricsConfig::default());
+        metrics.record_transaction(true);
+        metrics.record_transaction(false);
+        metrics.record_dropped_transaction();
+        metrics.record_slot_write(150);
+        metrics.record_writer_error();
+        metrics.record_root(10, Some(8));
+        metrics.record_root(11, None);
+        assert_eq!(metrics.transactions_notified.load(Ordering::Relaxed), 2);
+        assert_eq!(metrics.transactions_selected.load(Ordering::Relaxed), 1);
+        assert_eq!(metrics.transactions_dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(metrics.write_us.load(Ordering::Relaxed), 150);
+
+        metrics.report();
+        assert_eq!(metrics.transactions_notified.load(Ordering::Relaxed), 0);
+        assert_eq!(metrics.transactions_selected.load(Ordering::Relaxed), 0);
+        assert_eq!(metrics.transactions_dropped.load(Ordering::Relaxed), 0);
+        assert_eq!(metrics.writer_errors.load(Ordering::Relaxed), 0);
+        // Gauges keep their latest value
+        assert_eq!(metrics.root_slot.load(Ordering::Relaxed), 11);
+        assert_eq!(metrics.last_pod_slot.load(Ordering::Relaxed), 8);
+    }
+}
@@ -0,0 +1,358 @@
+//! Retention of sealed pods.
+//!
+//! Pods are pruned oldest first once they fall out of the `pod_retention`
+//! limits of the plugin config. By default only the pods up to the settled
+//! marker are pruned, the marker is a file holding a pod number, written next
+//! to the pod store by the relayer or the validator `settle-pods` command.
+//! Pruned pods can be archived to zstd compressed files before deletion.
+//! Pods the `pod_export` sinks didn't export yet are never pruned.
+
+use {
+    crate::{
+        pod::{decode_versioned, encode_versioned, PodHeader},
+        pod_sink::read_cursor,
+        rocksdb_client::RocksDBConnection,
+    },
+    serde_derive::{Deserialize, Serialize},
+    std::{
+        error::Error,
+        fs::{self, File},
+        io::{self, Write},
+        path::{Path, PathBuf},
+    },
+};
+
+/// Name of the settled marker file
+pub const SETTLED_POD_FILE: &str = "settled_pod_number";
+// * Bounds the time the writer spends pruning on a single root
+const MAX_PODS_PRUNED_PER_PASS: usize = 64;
+
+/// Decides which sealed pods are pruned, configured through the `pod_retention`
+/// section of the plugin config. A pod is pruned as soon as any of the
+/// configured limits is exceeded, pods are kept forever when none is set.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PodRetentionPolicy {
+    /// Number of most recent sealed pods to keep
+    pub max_pods: Option<u64>,
+    /// Time a pod is kept after being sealed, pods migrated from the legacy
+    /// batches count as sealed at the migration
+    pub max_age_secs: Option<u64>,
+    /// Only prune the pods up to the settled marker
+    pub settled_only: bool,
+    /// Directory the pruned pods are archived to, they are dropped when unset
+    pub archive_dir: Option<PathBuf>,
+}
+
+impl Default for PodRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_pods: None,
+            max_age_secs: None,
+            settled_only: true,
+            archive_dir: None,
+        }
+    }
+}
+
+impl PodRetentionPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_pods == Some(0) {
+            return Err("pod_retention.max_pods must be greater than 0".to_string());
+        }
+        if self.max_age_secs == Some(0) {
+            return Err("pod_retention.max_age_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_pods.is_some() || self.max_age_secs.is_some()
+    }
+
+    /// Check if a sealed pod falls out of the retention limits
+    pub fn is_expired(
+        &self,
+        pod_number: u64,
+        latest_pod_number: u64,
+        sealed_at_ms: Option<u64>,
+        now_ms: u64,
+    ) -> bool {
+        self.max_pods.map_or(false, |max_pods| {
+            latest_pod_number.saturating_sub(pod_number) >= max_pods
+        }) || self
+            .max_age_secs
+            .zip(sealed_at_ms)
+            .map_or(false, |(max_age_secs, sealed_at_ms)| {
+                now_ms.saturating_sub(sealed_at_ms) >= max_age_secs.saturating_mul(1000)
+            })
+    }
+}
+
+/// Read the settled marker in `dir`, `None` until one is written
+pub fn read_settled_pod_file(dir: &Path) -> Result<Option<u64>, Box<dyn Error>> {
+    let path = dir.join(SETTLED_POD_FILE);
+    match fs::read_to_string(&path) {
+        Ok(contents) => {
+            Ok(Some(contents.trim().parse().map_err(|e| {
+                format!("Invalid settled marker {path:?}: {e}")
+            })?))
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Mark the pods up to `pod_number` as settled. The marker is replaced
+/// atomically, the plugin picks it up on the next rooted slot.
+pub fn write_settled_pod_file(dir: &Path, pod_number: u64) -> io::Result<()> {
+    let path = dir.join(SETTLED_POD_FILE);
+    let tmp_path = dir.join(format!("{SETTLED_POD_FILE}.tmp"));
+    fs::write(&tmp_path, pod_number.to_string())?;
+    fs::rename(tmp_path, path)
+}
+
+/// A pruned pod as written to the archive
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedPod {
+    pub header: PodHeader,
+    /// Transaction records, in pod order
+    pub records: Vec<Vec<u8>>,
+}
+
+pub fn archived_pod_path(dir: &Path, pod_number: u64) -> PathBuf {
+    dir.join(format!("pod-{pod_number}.bin.zst"))
+}
+
+/// Encoding of a pod in the archive, also used by the pod sinks
+pub fn encode_archived_pod(pod: &ArchivedPod) -> Result<Vec<u8>, Box<dyn Error>> {
+    Ok(zstd::encode_all(encode_versioned(pod)?.as_slice(), 0)?)
+}
+
+pub fn decode_archived_pod(data: &[u8]) -> Result<ArchivedPod, Box<dyn Error>> {
+    decode_versioned(&zstd::decode_all(data)?)
+}
+
+/// Write a pod to the archive, the file is synced before the pod gets deleted
+pub fn write_archived_pod(dir: &Path, pod: &ArchivedPod) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    let path = archived_pod_path(dir, pod.header.pod_number);
+    let tmp_path = path.with_extension("tmp");
+    let data = encode_archived_pod(pod)?;
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(&data)?;
+    file.sync_all()?;
+    fs::rename(tmp_path, &path)?;
+    Ok(path)
+}
+
+pub fn read_archived_pod(path: &Path) -> Result<ArchivedPod, Box<dyn Error>> {
+    decode_archived_pod(&fs::read(path)?)
+}
+
+/// Applies a retention policy to the pod store
+pub struct PodPruner {
+    policy: PodRetentionPolicy,
+    /// Directory holding the settled marker
+    settled_marker_dir: PathBuf,
+    /// Cursors of the pod exporters, pods are kept until every sink exported them
+    export_cursor_paths: Vec<PathBuf>,
+}
+
+impl PodPruner {
+    pub fn new(
+        policy: PodRetentionPolicy,
+        settled_marker_dir: PathBuf,
+        export_cursor_paths: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            policy,
+            settled_marker_dir,
+            export_cursor_paths,
+        }
+    }
+
+    /// Record the settled marker and prune the expired pods, oldest first.
+    /// Returns the number of pruned pods.
+    pub fn prune(&self, client: &RocksDBConnection, now_ms: u64) -> Result<usize, Box<dyn Error>> {
+        if let Some(settled_pod_number) = read_settled_pod_file(&self.settled_marker_dir)? {
+            client.set_settled_pod_number(settled_pod_number)?;
+        }
+
+        let latest_pod_number = client.get_latest_batch_number()?;
+        let mut last_prunable_pod_number = if self.policy.settled_only {
+            latest_pod_number.min(client.get_settled_pod_number()?.unwrap_or_default())
+        } else {
+            latest_pod_number
+        };
+        for cursor_path in &self.export_cursor_paths {
+            // A sink which didn't write its cursor yet exports every pod
+            let cursor = read_cursor(cursor_path)?.unwrap_or_default();
+            last_prunable_pod_number = last_prunable_pod_number.min(cursor);
+        }
+
+        let mut pruned_count = 0;
+        let mut pod_number = client.get_pruned_pod_number()? + 1;
+        while pod_number <= last_prunable_pod_number && pruned_count < MAX_PODS_PRUNED_PER_PASS {
+            let sealed_at_ms = client.get_pod_sealed_at(pod_number)?;
+            if !self
+                .policy
+                .is_expired(pod_number, latest_pod_number, sealed_at_ms, now_ms)
+            {
+                break;
+            }
+            let pod = ArchivedPod {
+                header: client
+                    .get_pod_header(pod_number)?
+                    .ok_or_else(|| format!("Header of pod {pod_number} not found"))?,
+                records: client.get_pod_records(pod_number)?,
+            };
+            if let Some(archive_dir) = &self.policy.archive_dir {
+                write_archived_pod(archive_dir, &pod)?;
+            }
+            client.prune_pod(pod_number, &pod.records)?;
+            pruned_count += 1;
+            pod_number += 1;
+        }
+        Ok(pruned_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            pod::{PodSealingPolicy, SlotCommitmentTracker},
+            pod_sink::write_cursor,
+            slot_buffer::BufferedTransaction,
+            txn_select::TxnStoreStruct,
+        },
+        tempfile::TempDir,
+    };
+
+    fn transaction_record(index: usize) -> Vec<u8> {
+        let mut transaction = TxnStoreStruct::new();
+        transaction.signature = format!("signature-{index}");
+        transaction.index = index as i64;
+        transaction.to_record()
+    }
+
+    fn seal_pods(client: &RocksDBConnection, count: usize) {
+        let policy = PodSealingPolicy {
+            max_transactions: Some(1),
+            ..PodSealingPolicy::default()
+        };
+        let transactions = (0..count)
+            .map(|index| BufferedTransaction {
+                index,
+                signature: format!("signature-{index}"),
+                record: transaction_record(index),
+            })
+            .collect();
+        client
+            .save_transaction_batch(transactions, 1, &policy, &SlotCommitmentTracker::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_retention_policy_is_expired() {
+        let policy = PodRetentionPolicy {
+            max_pods: Some(2),
+            max_age_secs: Some(10),
+            ..PodRetentionPolicy::default()
+        };
+        assert!(policy.validate().is_ok());
+        assert!(policy.is_expired(1, 3, Some(50_000), 50_000));
+        assert!(!policy.is_expired(2, 3, Some(50_000), 50_000));
+        assert!(policy.is_expired(2, 3, Some(50_000), 60_000));
+        // Only the pod count applies to pods without a seal time
+        assert!(!policy.is_expired(2, 3, None, 50_000));
+        assert!(policy.is_expired(1, 3, None, 50_000));
+
+        assert!(!PodRetentionPolicy::default().is_enabled());
+        let policy = PodRetentionPolicy {
+            max_pods: Some(0),
+            ..PodRetentionPolicy::default()
+        };
+        assert!(policy.validate().is_err());
+    }
+
+    #[test]
+    fn test_prune_settled_pods() {
+        let temp_dir = TempDir::new().unwrap();
+        let client = RocksDBConnection::open(&temp_dir.path().join("pods"), false).unwrap();
+        client.check_and_create_keys().unwrap();
+        seal_pods(&client, 5);
+        assert_eq!(client.get_latest_batch_number().unwrap(), 5);
+
+        let archive_dir = temp_dir.path().join("archive");
+        let pruner = PodPruner::new(
+            PodRetentionPolicy {
+                max_pods: Some(1),
+                archive_dir: Some(archive_dir.clone()),
+                ..PodRetentionPolicy::default()
+            },
+            temp_dir.path().to_path_buf(),
+            vec![],
+        );
+        // Nothing is settled yet
+        assert_eq!(pruner.prune(&client, 0).unwrap(), 0);
+        assert_eq!(client.get_pruned_slot().unwrap(), None);
+
+        write_settled_pod_file(temp_dir.path(), 3).unwrap();
+        assert_eq!(read_settled_pod_file(temp_dir.path()).unwrap(), Some(3));
+        assert_eq!(pruner.prune(&client, 0).unwrap(), 3);
+        assert_eq!(client.get_settled_pod_number().unwrap(), Some(3));
+        assert_eq!(client.get_pruned_pod_number().unwrap(), 3);
+        assert_eq!(client.get_pruned_slot().unwrap(), Some(1));
+        assert_eq!(client.get_pod_header(3).unwrap(), None);
+        assert_eq!(
+            client.get_transaction_location("signature-2").unwrap(),
+            None
+        );
+        assert!(client.get_pod_header(4).unwrap().is_some());
+
+        let archived = read_archived_pod(&archived_pod_path(&archive_dir, 2)).unwrap();
+        assert_eq!(archived.header.pod_number, 2);
+        assert_eq!(archived.records, vec![transaction_record(1)]);
+
+        // The marker never moves back and the latest pods are kept
+        write_settled_pod_file(temp_dir.path(), 1).unwrap();
+        assert_eq!(pruner.prune(&client, 0).unwrap(), 0);
+        write_settled_pod_file(temp_dir.path(), 10).unwrap();
+        assert_eq!(pruner.prune(&client, 0).unwrap(), 1);
+        assert_eq!(client.get_settled_pod_number().unwrap(), Some(10));
+        assert!(client.get_pod_header(5).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_prune_stops_at_export_cursor() {
+        let temp_dir = TempDir::new().unwrap();
+        let client = RocksDBConnection::open(&temp_dir.path().join("pods"), false).unwrap();
+        client.check_and_create_keys().unwrap();
+        seal_pods(&client, 5);
+
+        let cursor_path = temp_dir.path().join("files.cursor");
+        let pruner = PodPruner::new(
+            PodRetentionPolicy {
+                max_pods: Some(1),
+                settled_only: false,
+                ..PodRetentionPolicy::default()
+            },
+            temp_dir.path().to_path_buf(),
+            vec![cursor_path.clone()],
+        );
+        // The sink didn't export any pod yet
+        assert_eq!(pruner.prune(&client, 0).unwrap(), 0);
+
+        write_cursor(&cursor_path, 2).unwrap();
+        assert_eq!(pruner.prune(&client, 0).unwrap(), 2);
+        assert_eq!(client.get_pruned_pod_number().unwrap(), 2);
+        assert!(client.get_pod_header(3).unwrap().is_some());
+
+        write_cursor(&cursor_path, 5).unwrap();
+        assert_eq!(pruner.prune(&client, 0).unwrap(), 2);
+        assert_eq!(client.get_pruned_pod_number().unwrap(), 4);
+    }
+}
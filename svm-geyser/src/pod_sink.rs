@@ -0,0 +1,687 @@
+//! Export of sealed pods to external consumers.
+//!
+//! Every sink configured in the `pod_export` section of the plugin config gets
+//! its own exporter thread, which reads the sealed pods from the pod store and
+//! hands them to the sink in pod order. The number of the last delivered pod
+//! is kept in a cursor file, so a restarted validator resumes where the sink
+//! stopped. A failed delivery is retried with an exponential backoff, a slow
+//! or unavailable consumer only delays its own exporter.
+//!
+//! ```json
+//! "pod_export" : {
+//!     "sinks" : [
+//!         { "type" : "directory", "name" : "files", "path" : "/var/pods" },
+//!         { "type" : "http", "name" : "relayer", "url" : "http://127.0.0.1:8080/pods" },
+//!         { "type" : "unix_socket", "name" : "da", "path" : "/tmp/pods.sock" }
+//!     ]
+//! }
+//! ```
+//!
+//! Pods are delivered in the archive encoding, see `encode_archived_pod`.
+
+use {
+    crate::{
+        pod_retention::{encode_archived_pod, write_archived_pod, ArchivedPod},
+        rocksdb_client::RocksDBConnection,
+    },
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender},
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::timing::timestamp,
+    std::{
+        collections::HashSet,
+        error::Error,
+        fs, io,
+        path::{Path, PathBuf},
+        sync::Arc,
+        thread::{Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+/// Directory of the cursors inside the plugin `path`, unless configured
+pub const DEFAULT_CURSOR_DIR: &str = "pod_export";
+// * Fallback wake up of the exporters, for the headers waiting for their slot states
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Consumer of the sealed pods. Pods are delivered in order, a pod is
+/// delivered again when the previous attempt failed.
+pub trait PodSink: Send {
+    fn export(&mut self, pod: &ArchivedPod) -> Result<(), Box<dyn Error>>;
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PodSinkConfig {
+    /// Writes each pod to `pod-<pod number>.bin.zst`, as the pod archive
+    Directory { name: String, path: PathBuf },
+    /// POSTs each pod to a local HTTP endpoint, the pod number is sent in the
+    /// `x-pod-number` header
+    Http {
+        name: String,
+        url: String,
+        #[serde(default = "default_http_timeout_ms")]
+        timeout_ms: u64,
+    },
+    /// Writes each pod to a Unix socket, framed by its length as a little
+    /// endian u32
+    UnixSocket { name: String, path: PathBuf },
+}
+
+fn default_http_timeout_ms() -> u64 {
+    10_000
+}
+
+impl PodSinkConfig {
+    /// Name of the sink, also the name of its cursor file
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Directory { name, .. }
+            | Self::Http { name, .. }
+            | Self::UnixSocket { name, .. } => name,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.name();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "pod_export sink name {name:?} must be made of letters, digits, '-' and '_'"
+            ));
+        }
+        match self {
+            Self::Http {
+                url, timeout_ms, ..
+            } => {
+                if !url.starts_with("http://") {
+                    return Err(format!(
+                        "pod_export sink {name} url must start with http://"
+                    ));
+                }
+                if *timeout_ms == 0 {
+                    return Err(format!(
+                        "pod_export sink {name} timeout_ms must be greater than 0"
+                    ));
+                }
+            }
+            #[cfg(not(unix))]
+            Self::UnixSocket { .. } => {
+                return Err(format!(
+                    "pod_export sink {name} is a Unix socket, which this platform doesn't support"
+                ));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn create_sink(&self) -> Result<Box<dyn PodSink>, Box<dyn Error>> {
+        Ok(match self {
+            Self::Directory { path, .. } => Box::new(DirectorySink { path: path.clone() }),
+            Self::Http {
+                url, timeout_ms, ..
+            } => Box::new(HttpSink {
+                client: None,
+                url: url.clone(),
+                timeout: Duration::from_millis(*timeout_ms),
+            }),
+            #[cfg(unix)]
+            Self::UnixSocket { path, .. } => Box::new(UnixSocketSink {
+                path: path.clone(),
+                stream: None,
+            }),
+            #[cfg(not(unix))]
+            Self::UnixSocket { .. } => return Err("Unix sockets are not supported".into()),
+        })
+    }
+}
+
+/// The `pod_export` section of the plugin config
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PodExportConfig {
+    pub sinks: Vec<PodSinkConfig>,
+    /// Directory of the sink cursors, `pod_export` in the plugin `path` when unset
+    pub cursor_dir: Option<PathBuf>,
+    /// Delay before the first retry of a failed delivery, doubled on every failure
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Time a sealed pod waits for the bank state of its first and last slots
+    /// before being exported without it
+    pub max_header_wait_ms: u64,
+}
+
+impl Default for PodExportConfig {
+    fn default() -> Self {
+        Self {
+            sinks: vec![],
+            cursor_dir: None,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 30_000,
+            max_header_wait_ms: 30_000,
+        }
+    }
+}
+
+impl PodExportConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names = HashSet::new();
+        for sink in &self.sinks {
+            sink.validate()?;
+            if !names.insert(sink.name()) {
+                return Err(format!(
+                    "pod_export sink {} is configured twice",
+                    sink.name()
+                ));
+            }
+        }
+        if self.initial_backoff_ms == 0 {
+            return Err("pod_export.initial_backoff_ms must be greater than 0".to_string());
+        }
+        if self.max_backoff_ms < self.initial_backoff_ms {
+            return Err(
+                "pod_export.max_backoff_ms must not be less than initial_backoff_ms".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Cursor files of the configured sinks, `plugin_path` is the plugin
+    /// `path` holding the default cursor directory
+    pub fn cursor_paths(&self, plugin_path: &Path) -> Vec<PathBuf> {
+        let cursor_dir = self.resolved_cursor_dir(plugin_path);
+        self.sinks
+            .iter()
+            .map(|sink_config| cursor_dir.join(format!("{}.cursor", sink_config.name())))
+            .collect()
+    }
+
+    fn resolved_cursor_dir(&self, plugin_path: &Path) -> PathBuf {
+        self.cursor_dir
+            .clone()
+            .unwrap_or_else(|| plugin_path.join(DEFAULT_CURSOR_DIR))
+    }
+
+    /// Start an exporter per configured sink, `plugin_path` is the plugin
+    /// `path` holding the default cursor directory
+    pub fn start_exporters(
+        &self,
+        client: &Arc<RocksDBConnection>,
+        plugin_path: &Path,
+    ) -> Result<Vec<PodExporter>, Box<dyn Error>> {
+        fs::create_dir_all(self.resolved_cursor_dir(plugin_path))?;
+        self.sinks
+            .iter()
+            .zip(self.cursor_paths(plugin_path))
+            .map(|(sink_config, cursor_path)| {
+                let sink = sink_config.create_sink().map_err(|e| {
+                    format!(
+                        "Failed to create pod_export sink {}: {e}",
+                        sink_config.name()
+                    )
+                })?;
+                PodExporter::new(
+                    sink_config.name().to_string(),
+                    sink,
+                    Arc::clone(client),
+                    cursor_path,
+                    self,
+                )
+            })
+            .collect()
+    }
+}
+
+struct DirectorySink {
+    path: PathBuf,
+}
+
+impl PodSink for DirectorySink {
+    fn export(&mut self, pod: &ArchivedPod) -> Result<(), Box<dyn Error>> {
+        write_archived_pod(&self.path, pod)?;
+        Ok(())
+    }
+}
+
+struct HttpSink {
+    /// Built on the exporter thread, the blocking client must not be created
+    /// or dropped on a thread running an async runtime
+    client: Option<reqwest::blocking::Client>,
+    url: String,
+    timeout: Duration,
+}
+
+impl PodSink for HttpSink {
+    fn export(&mut self, pod: &ArchivedPod) -> Result<(), Box<dyn Error>> {
+        let client = match &mut self.client {
+            Some(client) => client,
+            client => client.insert(
+                reqwest::blocking::Client::builder()
+                    .timeout(self.timeout)
+                    .build()?,
+            ),
+        };
+        client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .header("x-pod-number", pod.header.pod_number.to_string())
+            .body(encode_archived_pod(pod)?)
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+struct UnixSocketSink {
+    path: PathBuf,
+    /// Reconnected after a failed write
+    stream: Option<std::os::unix::net::UnixStream>,
+}
+
+#[cfg(unix)]
+impl PodSink for UnixSocketSink {
+    fn export(&mut self, pod: &ArchivedPod) -> Result<(), Box<dyn Error>> {
+        use std::io::Write;
+
+        let data = encode_archived_pod(pod)?;
+        let len = u32::try_from(data.len())
+            .map_err(|_| format!("Pod {} is too large to frame", pod.header.pod_number))?;
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            stream => stream.insert(std::os::unix::net::UnixStream::connect(&self.path)?),
+        };
+        let result = stream
+            .write_all(&len.to_le_bytes())
+            .and_then(|()| stream.write_all(&data))
+            .and_then(|()| stream.flush());
+        if result.is_err() {
+            self.stream = None;
+        }
+        Ok(result?)
+    }
+}
+
+pub(crate) fn read_cursor(path: &Path) -> Result<Option<u64>, Box<dyn Error>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(
+            contents
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid cursor {path:?}: {e}"))?,
+        )),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub(crate) fn write_cursor(path: &Path, pod_number: u64) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, pod_number.to_string())?;
+    fs::rename(tmp_path, path)
+}
+
+/// Delivers the sealed pods to a sink on its own thread
+pub struct PodExporter {
+    sender: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PodExporter {
+    pub fn new(
+        name: String,
+        sink: Box<dyn PodSink>,
+        client: Arc<RocksDBConnection>,
+        cursor_path: PathBuf,
+        config: &PodExportConfig,
+    ) -> Result<Self, Box<dyn Error>> {
+        let cursor = read_cursor(&cursor_path)?.unwrap_or_default();
+        info!("Exporting pods to sink {name} after pod {cursor}");
+        // A single pending wake up is enough, the exporter reads the store
+        let (sender, receiver) = bounded(1);
+        let state = PodExporterState {
+            name,
+            sink,
+            client,
+            cursor_path,
+            cursor,
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+            max_header_wait_ms: config.max_header_wait_ms,
+        };
+        let thread = Builder::new()
+            .name("svmPodExporter".to_string())
+            .spawn(move || state.run(receiver))?;
+        Ok(Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    /// Wake the exporter up after a pod was sealed or its header completed,
+    /// never blocks
+    pub fn notify(&self) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.try_send(());
+        }
+    }
+
+    /// Stop the exporter, the pods it didn't deliver are exported after a restart
+    pub fn join(&mut self) {
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("A pod exporter thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for PodExporter {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+struct PodExporterState {
+    name: String,
+    sink: Box<dyn PodSink>,
+    client: Arc<RocksDBConnection>,
+    cursor_path: PathBuf,
+    /// Last delivered pod
+    cursor: u64,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_header_wait_ms: u64,
+}
+
+impl PodExporterState {
+    fn run(mut self, receiver: Receiver<()>) {
+        let mut backoff = self.initial_backoff;
+        loop {
+            let running = match self.export_sealed_pods() {
+                Ok(()) => {
+                    backoff = self.initial_backoff;
+                    receiver.recv_timeout(POLL_INTERVAL) != Err(RecvTimeoutError::Disconnected)
+                }
+                Err(e) => {
+                    warn!("{e}, retrying in {backoff:?}");
+                    // Wake ups don't cut the backoff short, only a stop does
+                    let deadline = Instant::now() + backoff;
+                    backoff = backoff.saturating_mul(2).min(self.max_backoff);
+                    loop {
+                        match receiver.recv_deadline(deadline) {
+                            Ok(()) => continue,
+                            Err(RecvTimeoutError::Timeout) => break true,
+                            Err(RecvTimeoutError::Disconnected) => break false,
+                        }
+                    }
+                }
+            };
+            if !running {
+                break;
+            }
+        }
+    }
+
+    /// Deliver the sealed pods after the cursor, in order
+    fn export_sealed_pods(&mut self) -> Result<(), Box<dyn Error>> {
+        let latest_pod_number = self.client.get_latest_batch_number()?;
+        let pruned_pod_number = self.client.get_pruned_pod_number()?;
+        // The pruner keeps the pods the sinks didn't export, only a sink added
+        // after the pods were pruned or a lost cursor is behind
+        if self.cursor < pruned_pod_number {
+            warn!(
+                "Pods {} to {pruned_pod_number} were pruned before their export to sink {}",
+                self.cursor + 1,
+                self.name
+            );
+            self.advance_cursor(pruned_pod_number)?;
+        }
+
+        for pod_number in self.cursor + 1..=latest_pod_number {
+            let header = self
+                .client
+                .get_pod_header(pod_number)?
+                .ok_or_else(|| format!("Header of pod {pod_number} not found"))?;
+            if !header.is_complete() {
+                let sealed_at_ms = self.client.get_pod_sealed_at(pod_number)?;
+                if timestamp().saturating_sub(sealed_at_ms.unwrap_or_default())
+                    < self.max_header_wait_ms
+                {
+                    break;
+                }
+                warn!("Exporting pod {pod_number} without the bank state of its slots");
+            }
+            let pod = ArchivedPod {
+                header,
+                records: self.client.get_pod_records(pod_number)?,
+            };
+            self.sink.export(&pod).map_err(|e| {
+                format!(
+                    "Failed to export pod {pod_number} to sink {}: {e}",
+                    self.name
+                )
+            })?;
+            self.advance_cursor(pod_number)?;
+        }
+        Ok(())
+    }
+
+    fn advance_cursor(&mut self, pod_number: u64) -> Result<(), Box<dyn Error>> {
+        write_cursor(&self.cursor_path, pod_number).map_err(|e| {
+            format!(
+                "Failed to write the cursor {:?} of sink {}: {e}",
+                self.cursor_path, self.name
+            )
+        })?;
+        self.cursor = pod_number;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            pod::{PodSealingPolicy, SlotCommitment, SlotCommitmentTracker},
+            pod_retention::{decode_archived_pod, read_archived_pod},
+            slot_buffer::BufferedTransaction,
+        },
+        solana_sdk::hash::Hash,
+        std::{
+            io::{BufRead, BufReader, Read, Write},
+            net::TcpListener,
+            sync::Mutex,
+            thread::sleep,
+        },
+        tempfile::TempDir,
+    };
+
+    /// Seal a pod per transaction, the headers are complete
+    fn seal_pods(client: &RocksDBConnection, slot: u64, count: usize) {
+        let policy = PodSealingPolicy {
+            max_transactions: Some(1),
+            ..PodSealingPolicy::default()
+        };
+        let mut commitments = SlotCommitmentTracker::default();
+        commitments.insert(SlotCommitment {
+            slot,
+            blockhash: Hash::new_unique().to_string(),
+            bank_hash: Hash::new_unique().to_string(),
+            parent_bank_hash: Hash::new_unique().to_string(),
+            accounts_delta_hash: None,
+        });
+        let transactions = (0..count)
+            .map(|index| BufferedTransaction {
+                index,
+                signature: format!("signature-{slot}-{index}"),
+                record: format!("txn-{slot}-{index}").into_bytes(),
+            })
+            .collect();
+        client
+            .save_transaction_batch(transactions, slot, &policy, &commitments)
+            .unwrap();
+    }
+
+    /// Records the delivered pods, after failing a number of attempts
+    struct FlakySink {
+        failures: usize,
+        exported: Arc<Mutex<Vec<u64>>>,
+    }
+
+    impl PodSink for FlakySink {
+        fn export(&mut self, pod: &ArchivedPod) -> Result<(), Box<dyn Error>> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err("consumer unavailable".into());
+            }
+            self.exported.lock().unwrap().push(pod.header.pod_number);
+            Ok(())
+        }
+    }
+
+    fn wait_for_exports(exported: &Mutex<Vec<u64>>, count: usize) {
+        let start = Instant::now();
+        while exported.lock().unwrap().len() < count {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "pods not exported"
+            );
+            sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_pod_exporter_retries_and_resumes_from_cursor() {
+        let temp_dir = TempDir::new().unwrap();
+        let client =
+            Arc::new(RocksDBConnection::open(&temp_dir.path().join("pods"), false).unwrap());
+        client.check_and_create_keys().unwrap();
+        seal_pods(&client, 1, 3);
+        let config = PodExportConfig {
+            initial_backoff_ms: 1,
+            max_backoff_ms: 4,
+            ..PodExportConfig::default()
+        };
+        let cursor_path = temp_dir.path().join("flaky.cursor");
+        let exported = Arc::<Mutex<Vec<u64>>>::default();
+        let start_exporter = |failures| {
+            let sink = Box::new(FlakySink {
+                failures,
+                exported: Arc::clone(&exported),
+            });
+            PodExporter::new(
+                "flaky".to_string(),
+                sink,
+                Arc::clone(&client),
+                cursor_path.clone(),
+                &config,
+            )
+            .unwrap()
+        };
+
+        let mut exporter = start_exporter(3);
+        wait_for_exports(&exported, 3);
+        exporter.join();
+        assert_eq!(*exported.lock().unwrap(), vec![1, 2, 3]);
+        assert_eq!(read_cursor(&cursor_path).unwrap(), Some(3));
+
+        seal_pods(&client, 2, 2);
+        let exporter = start_exporter(0);
+        exporter.notify();
+        wait_for_exports(&exported, 5);
+        drop(exporter);
+        assert_eq!(*exported.lock().unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_directory_and_unix_socket_sinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let client = RocksDBConnection::open(&temp_dir.path().join("pods"), false).unwrap();
+        client.check_and_create_keys().unwrap();
+        seal_pods(&client, 1, 1);
+        let pod = ArchivedPod {
+            header: client.get_pod_header(1).unwrap().unwrap(),
+            records: client.get_pod_records(1).unwrap(),
+        };
+
+        let dir = temp_dir.path().join("export");
+        let sink_config = PodSinkConfig::Directory {
+            name: "files".to_string(),
+            path: dir.clone(),
+        };
+        sink_config.create_sink().unwrap().export(&pod).unwrap();
+        assert_eq!(read_archived_pod(&dir.join("pod-1.bin.zst")).unwrap(), pod);
+
+        let socket_path = temp_dir.path().join("pods.sock");
+        let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+        let sink_config = PodSinkConfig::UnixSocket {
+            name: "da".to_string(),
+            path: socket_path,
+        };
+        let mut sink = sink_config.create_sink().unwrap();
+        sink.export(&pod).unwrap();
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut len = [0; 4];
+        stream.read_exact(&mut len).unwrap();
+        let mut data = vec![0; u32::from_le_bytes(len) as usize];
+        stream.read_exact(&mut data).unwrap();
+        assert_eq!(decode_archived_pod(&data).unwrap(), pod);
+    }
+
+    #[test]
+    fn test_http_sink_posts_pods() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/pods", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut pod_number = None;
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_ascii_lowercase();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("x-pod-number: ") {
+                    pod_number = Some(value.to_string());
+                }
+                if let Some(value) = line.strip_prefix("content-length: ") {
+                    content_length = value.parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .unwrap();
+            (pod_number, decode_archived_pod(&body).unwrap())
+        });
+
+        let temp_dir = TempDir::new().unwrap();
+        let client = RocksDBConnection::open(temp_dir.path(), false).unwrap();
+        client.check_and_create_keys().unwrap();
+        seal_pods(&client, 1, 1);
+        let pod = ArchivedPod {
+            header: client.get_pod_header(1).unwrap().unwrap(),
+            records: client.get_pod_records(1).unwrap(),
+        };
+        let sink_config = PodSinkConfig::Http {
+            name: "relayer".to_string(),
+            url,
+            timeout_ms: default_http_timeout_ms(),
+        };
+        assert!(sink_config.validate().is_ok());
+        sink_config.create_sink().unwrap().export(&pod).unwrap();
+        assert_eq!(server.join().unwrap(), (Some("1".to_string()), pod));
+    }
+}
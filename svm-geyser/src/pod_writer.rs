@@ -0,0 +1,369 @@
+//! Single writer of the pod store.
+//!
+//! Slot status and block metadata notifications arrive on several validator
+//! threads. They are turned into commands and applied in order by one thread,
+//! so reading the pod bookkeeping and writing it back never interleave.
+
+use {
+    crate::{
+        pod::{
+            OpenPod, PodHeader, PodSealingPolicy, SealReason, SlotCommitment, SlotCommitmentTracker,
+        },
+        pod_metrics::PodMetrics,
+        pod_retention::PodPruner,
+        pod_sink::PodExporter,
+        rocksdb_client::RocksDBConnection,
+        slot_buffer::BufferedTransaction,
+    },
+    crossbeam_channel::{unbounded, Receiver, Sender},
+    log::*,
+    solana_measure::measure::Measure,
+    solana_sdk::{clock::Slot, timing::timestamp},
+    std::{
+        error::Error,
+        sync::Arc,
+        thread::{Builder, JoinHandle},
+    },
+};
+
+#[derive(Debug)]
+pub enum PodWriterCommand {
+    /// Transactions of a slot which reached the pod commitment, in block order
+    CommitSlot {
+        slot: Slot,
+        transactions: Vec<BufferedTransaction>,
+    },
+    /// Drives root and time based sealing, rooted slots also drive pruning
+    /// and move the last processed slot
+    SlotStatus { slot: Slot, rooted: bool },
+    /// Bank state of a frozen slot, completes the headers waiting for it
+    SlotCommitment(SlotCommitment),
+}
+
+pub struct PodWriter {
+    sender: Option<Sender<PodWriterCommand>>,
+    thread: Option<JoinHandle<Arc<RocksDBConnection>>>,
+}
+
+impl PodWriter {
+    /// `exporters` are woken up as pods get ready for export, they are stopped
+    /// with the writer
+    pub fn new(
+        client: Arc<RocksDBConnection>,
+        sealing_policy: PodSealingPolicy,
+        pruner: Option<PodPruner>,
+        metrics: Arc<PodMetrics>,
+        exporters: Vec<PodExporter>,
+    ) -> Self {
+        let (sender, receiver) = unbounded();
+        let state = PodWriterState {
+            client,
+            sealing_policy,
+            pruner,
+            metrics,
+            exporters,
+            slot_commitments: SlotCommitmentTracker::default(),
+        };
+        let thread = Builder::new()
+            .name("svmPodWriter".to_string())
+            .spawn(move || state.run(receiver))
+            .unwrap();
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    /// Queue a command, commands are applied in the order they are sent
+    pub fn send(&self, command: PodWriterCommand) -> Result<(), String> {
+        self.sender
+            .as_ref()
+            .ok_or_else(|| "The pod writer was stopped".to_string())?
+            .send(command)
+            .map_err(|e| format!("The pod writer thread exited, dropping {:?}", e.0))
+    }
+
+    /// Apply the queued commands and stop the writer thread and the exporters,
+    /// handing back the pod store
+    pub fn join(&mut self) -> Option<Arc<RocksDBConnection>> {
+        self.sender.take();
+        let thread = self.thread.take()?;
+        match thread.join() {
+            Ok(client) => Some(client),
+            Err(_) => {
+                error!("The pod writer thread panicked");
+                None
+            }
+        }
+    }
+}
+
+impl Drop for PodWriter {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+struct PodWriterState {
+    client: Arc<RocksDBConnection>,
+    sealing_policy: PodSealingPolicy,
+    pruner: Option<PodPruner>,
+    metrics: Arc<PodMetrics>,
+    exporters: Vec<PodExporter>,
+    slot_commitments: SlotCommitmentTracker,
+}
+
+impl PodWriterState {
+    fn run(mut self, receiver: Receiver<PodWriterCommand>) -> Arc<RocksDBConnection> {
+        for command in receiver.iter() {
+            if let Err(e) = self.process(command) {
+                error!("{e}");
+                self.metrics.record_writer_error();
+            }
+            self.metrics.maybe_report();
+        }
+        // The exporters share the store, they are stopped before handing it back
+        self.exporters.clear();
+        self.client
+    }
+
+    fn process(&mut self, command: PodWriterCommand) -> Result<(), Box<dyn Error>> {
+        match command {
+            PodWriterCommand::CommitSlot { slot, transactions } => {
+                let open_pod = self.open_pod_for_metrics()?;
+                let mut write_time = Measure::start("write");
+                let sealed_headers = self
+                    .client
+                    .save_transaction_batch(
+                        transactions,
+                        slot,
+                        &self.sealing_policy,
+                        &self.slot_commitments,
+                    )
+                    .map_err(|e| {
+                        format!("Failed to persist the transactions of slot {slot}: {e}")
+                    })?;
+                write_time.stop();
+                self.metrics.record_slot_write(write_time.as_us());
+                // Pods after the first one were opened and sealed by this write
+                let mut opened_at_ms = open_pod.map(|open_pod| open_pod.opened_at_ms);
+                for header in &sealed_headers {
+                    self.report_sealed_pod(header, opened_at_ms.take())?;
+                    self.slot_commitments.track_pending(header);
+                }
+                if !sealed_headers.is_empty() {
+                    self.notify_exporters();
+                }
+            }
+            PodWriterCommand::SlotStatus { slot, rooted } => {
+                let seal_reason = if self.sealing_policy.seal_on_root && rooted {
+                    Some(SealReason::Rooted)
+                } else if self.sealing_policy.timeout_ms.is_some() {
+                    // Slot updates are frequent enough to drive the sealing timeout
+                    let open_pod = self
+                        .client
+                        .get_open_pod()
+                        .map_err(|e| format!("Failed to read the open pod at slot {slot}: {e}"))?;
+                    open_pod
+                        .filter(|open_pod| self.sealing_policy.is_expired(open_pod, timestamp()))
+                        .map(|_| SealReason::Timeout)
+                } else {
+                    None
+                };
+
+                if let Some(seal_reason) = seal_reason {
+                    let open_pod = self.open_pod_for_metrics()?;
+                    let header = self
+                        .client
+                        .seal_open_batch(seal_reason, &self.slot_commitments)
+                        .map_err(|e| format!("Failed to seal the open pod at slot {slot}: {e}"))?;
+                    if let Some(header) = header {
+                        self.report_sealed_pod(
+                            &header,
+                            open_pod.map(|open_pod| open_pod.opened_at_ms),
+                        )?;
+                        self.slot_commitments.track_pending(&header);
+                        self.notify_exporters();
+                    }
+                }
+
+                if rooted {
+                    // Every slot up to the root was committed before its status
+                    let last_processed_slot =
+                        self.client.advance_last_processed_slot(slot).map_err(|e| {
+                            format!("Failed to record the last processed slot {slot}: {e}")
+                        })?;
+                    self.metrics.record_root(slot, Some(last_processed_slot));
+                }
+
+                if let Some(pruner) = self.pruner.as_ref().filter(|_| rooted) {
+                    let pruned_count = pruner
+                        .prune(&self.client, timestamp())
+                        .map_err(|e| format!("Failed to prune pods at slot {slot}: {e}"))?;
+                    if pruned_count > 0 {
+                        info!("Pruned {pruned_count} pods at slot {slot}");
+                    }
+                }
+            }
+            PodWriterCommand::SlotCommitment(commitment) => {
+                for pod_number in self.slot_commitments.insert(commitment.clone()) {
+                    let mut header = match self.client.get_pod_header(pod_number).map_err(|e| {
+                        format!("Failed to read the header of pod {pod_number}: {e}")
+                    })? {
+                        Some(header) => header,
+                        None => continue,
+                    };
+                    if header.set_slot_commitment(&commitment) {
+                        self.client.save_pod_header(&header).map_err(|e| {
+                            format!("Failed to update the header of pod {pod_number}: {e}")
+                        })?;
+                        if header.is_complete() {
+                            self.notify_exporters();
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn notify_exporters(&self) {
+        for exporter in &self.exporters {
+            exporter.notify();
+        }
+    }
+
+    /// The open pod before a write, for the seal latency
+    fn open_pod_for_metrics(&self) -> Result<Option<OpenPod>, Box<dyn Error>> {
+        if !self.metrics.is_enabled() {
+            return Ok(None);
+        }
+        self.client
+            .get_open_pod()
+            .map_err(|e| format!("Failed to read the open pod: {e}").into())
+    }
+
+    fn report_sealed_pod(
+        &self,
+        header: &PodHeader,
+        opened_at_ms: Option<u64>,
+    ) -> Result<(), Box<dyn Error>> {
+        if !self.metrics.is_enabled() {
+            return Ok(());
+        }
+        let sealed_at_ms = self.client.get_pod_sealed_at(header.pod_number)?;
+        let seal_latency_ms = opened_at_ms
+            .zip(sealed_at_ms)
+            .map(|(opened_at_ms, sealed_at_ms)| sealed_at_ms.saturating_sub(opened_at_ms));
+        self.metrics.report_sealed_pod(header, seal_latency_ms);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::hash::Hash, tempfile::TempDir};
+
+    fn transaction(slot: Slot, index: usize) -> BufferedTransaction {
+        BufferedTransaction {
+            index,
+            signature: format!("signature-{slot}-{index}"),
+            record: format!("txn-{slot}-{index}").into_bytes(),
+        }
+    }
+
+    #[test]
+    fn test_pod_writer_applies_commands_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let client = RocksDBConnection::open(temp_dir.path(), false).unwrap();
+        client.check_and_create_keys().unwrap();
+        let policy = PodSealingPolicy {
+            max_transactions: Some(2),
+            seal_on_root: true,
+            ..PodSealingPolicy::default()
+        };
+        let mut writer = PodWriter::new(Arc::new(client), policy, None, Arc::default(), vec![]);
+
+        let commitment = SlotCommitment {
+            slot: 5,
+            blockhash: Hash::new_unique().to_string(),
+            bank_hash: Hash::new_unique().to_string(),
+            parent_bank_hash: Hash::new_unique().to_string(),
+            accounts_delta_hash: None,
+        };
+        writer
+            .send(PodWriterCommand::SlotCommitment(commitment.clone()))
+            .unwrap();
+        for slot in [5, 6] {
+            writer
+                .send(PodWriterCommand::CommitSlot {
+                    slot,
+                    transactions: vec![transaction(slot, 0), transaction(slot, 1)],
+                })
+                .unwrap();
+        }
+        // Replayed slot
+        writer
+            .send(PodWriterCommand::CommitSlot {
+                slot: 6,
+                transactions: vec![transaction(6, 1), transaction(6, 2)],
+            })
+            .unwrap();
+        writer
+            .send(PodWriterCommand::SlotStatus {
+                slot: 6,
+                rooted: true,
+            })
+            .unwrap();
+        let client = writer.join().unwrap();
+        assert!(writer.join().is_none());
+        assert!(writer
+            .send(PodWriterCommand::SlotStatus {
+                slot: 7,
+                rooted: true,
+            })
+            .is_err());
+
+        assert_eq!(client.get_latest_batch_number().unwrap(), 3);
+        let header = client.get_pod_header(1).unwrap().unwrap();
+        assert_eq!(header.first_slot_state, Some(commitment));
+        assert_eq!(
+            client.get_pod_records(2).unwrap(),
+            vec![b"txn-6-0".to_vec(), b"txn-6-1".to_vec()]
+        );
+        let header = client.get_pod_header(3).unwrap().unwrap();
+        assert_eq!(header.seal_reason, SealReason::Rooted);
+        assert_eq!(
+            client.get_pod_records(3).unwrap(),
+            vec![b"txn-6-2".to_vec()]
+        );
+        assert_eq!(client.get_last_processed_slot().unwrap(), Some(6));
+    }
+
+    #[test]
+    fn test_pod_writer_moves_last_processed_slot_on_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let client = RocksDBConnection::open(temp_dir.path(), false).unwrap();
+        client.check_and_create_keys().unwrap();
+        let mut writer = PodWriter::new(
+            Arc::new(client),
+            PodSealingPolicy::default(),
+            None,
+            Arc::default(),
+            vec![],
+        );
+        writer
+            .send(PodWriterCommand::CommitSlot {
+                slot: 3,
+                transactions: vec![transaction(3, 0)],
+            })
+            .unwrap();
+        for (slot, rooted) in [(4, false), (5, true), (7, false)] {
+            writer
+                .send(PodWriterCommand::SlotStatus { slot, rooted })
+                .unwrap();
+        }
+        let client = writer.join().unwrap();
+        assert_eq!(client.get_last_processed_slot().unwrap(), Some(5));
+    }
+}
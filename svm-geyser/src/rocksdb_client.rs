@@ -1,11 +1,23 @@
 use std::fs::{create_dir, create_dir_all};
 use std::io::ErrorKind::AlreadyExists;
 use std::path::{Path, PathBuf};
-use std::thread::sleep;
-use std::time::Duration;
 use {
-    rocksdb::{DBWithThreadMode, MultiThreaded, Options, WriteBatch, WriteOptions},
-    std::error::Error,
+    crate::{
+        pod::{
+            decode_versioned, encode_versioned, OpenPod, PodHeader, PodSealingPolicy, SealReason,
+            SlotCommitmentTracker, TransactionLocation, DEFAULT_MAX_POD_TRANSACTIONS,
+            POD_FORMAT_VERSION,
+        },
+        slot_buffer::BufferedTransaction,
+        txn_select::TxnStoreStruct,
+    },
+    log::*,
+    rocksdb::{
+        DBCompressionType, DBWithThreadMode, MultiThreaded, Options, WriteBatch, WriteOptions,
+    },
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::{clock::Slot, timing::timestamp},
+    std::{collections::HashSet, error::Error},
 };
 
 // * Set initial values
@@ -17,11 +29,84 @@ const BATCH_COUNT_KEY: &str = "batch_count";
 const BATCH_COUNT: &str = "1";
 const BATCH_START_INDEX_KEY: &str = "batch_start_index";
 const BATCH_START_INDEX: &str = "0";
+const OPEN_POD_KEY: &str = "open_pod";
+const POD_KEY_PREFIX: &str = "pod/";
+const POD_FORMAT_VERSION_KEY: &str = "pod_format_version";
+const LAST_PROCESSED_SLOT_KEY: &str = "last_processed_slot";
+const SIGNATURE_KEY_PREFIX: &str = "signature/";
+const SETTLED_POD_NUMBER_KEY: &str = "settled_pod_number";
+const PRUNED_POD_NUMBER_KEY: &str = "pruned_pod_number";
+const PRUNED_SLOT_KEY: &str = "pruned_slot";
 const SECONDARY_DB_PATH: &str = "secondary";
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RocksDBCompression {
+    None,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
+impl From<RocksDBCompression> for DBCompressionType {
+    fn from(compression: RocksDBCompression) -> Self {
+        match compression {
+            RocksDBCompression::None => DBCompressionType::None,
+            RocksDBCompression::Snappy => DBCompressionType::Snappy,
+            RocksDBCompression::Lz4 => DBCompressionType::Lz4,
+            RocksDBCompression::Zstd => DBCompressionType::Zstd,
+        }
+    }
+}
+
+/// Tuning of the primary pod store, configured through the `rocksdb` section
+/// of the plugin config. Unset values keep the RocksDB defaults.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RocksDBOptions {
+    pub compression: Option<RocksDBCompression>,
+    /// Size of a memtable
+    pub write_buffer_size_mb: Option<usize>,
+    /// Number of memtables held in memory before writes stall
+    pub max_write_buffer_number: Option<i32>,
+    /// Sync the write ahead log on every write, so that the pods survive a
+    /// machine crash and not only a validator crash
+    pub sync_wal: bool,
+}
+
+impl RocksDBOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.write_buffer_size_mb == Some(0) {
+            return Err("rocksdb.write_buffer_size_mb must be greater than 0".to_string());
+        }
+        if self
+            .max_write_buffer_number
+            .map_or(false, |max_write_buffer_number| max_write_buffer_number < 2)
+        {
+            return Err("rocksdb.max_write_buffer_number must be at least 2".to_string());
+        }
+        Ok(())
+    }
+
+    fn to_options(&self) -> Options {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        if let Some(compression) = self.compression {
+            options.set_compression_type(compression.into());
+        }
+        if let Some(write_buffer_size_mb) = self.write_buffer_size_mb {
+            options.set_write_buffer_size(write_buffer_size_mb.saturating_mul(1024 * 1024));
+        }
+        if let Some(max_write_buffer_number) = self.max_write_buffer_number {
+            options.set_max_write_buffer_number(max_write_buffer_number);
+        }
+        options
+    }
+}
 
 pub struct RocksDBConnection {
     pub db: DBWithThreadMode<MultiThreaded>,
+    sync_wal: bool,
 }
 
 impl RocksDBConnection {
@@ -31,71 +116,61 @@ impl RocksDBConnection {
             Ok(()) => (),
             Err(e) => {
                 if e.kind() != AlreadyExists {
-                    println!("Error creating dirs: {:?}", e);
+                    error!("Error creating dirs: {:?}", e);
                     return Err(Box::new(e));
                 }
             }
         };
 
-        let mut opts = Options::default();
-        opts.create_if_missing(true);
-
         if secondary {
-            sleep(Duration::from_secs(5));
-            let secondary_db_path = folder_path.join(SECONDARY_DB_PATH);
-            match DBWithThreadMode::open_as_secondary(&opts, folder_path, &secondary_db_path) {
-                Ok(db) => Ok(Self { db }),
-                Err(e) => {
-                    println!("{:?}", e);
-                    Err(Box::new(e))
-                }
-            }
+            Self::open_secondary(folder_path, &folder_path.join(SECONDARY_DB_PATH))
         } else {
-            match DBWithThreadMode::open_default(&folder_path) {
-                Ok(db) => Ok(Self { db }),
-                Err(e) => Err(Box::new(e))
+            Self::open_with_options(folder_path, &RocksDBOptions::default())
+        }
+    }
+
+    /// Open the primary instance with the given tuning
+    pub fn open_with_options(
+        folder_path: &Path,
+        options: &RocksDBOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        create_dir_all(folder_path)?;
+        let db = DBWithThreadMode::open(&options.to_options(), folder_path)?;
+        Ok(Self {
+            db,
+            sync_wal: options.sync_wal,
+        })
+    }
+
+    /// Open a secondary instance keeping its own files in `secondary_path`.
+    /// Every secondary instance opened at the same time needs its own path.
+    pub fn open_secondary(folder_path: &Path, secondary_path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        match DBWithThreadMode::open_as_secondary(&opts, folder_path, secondary_path) {
+            Ok(db) => Ok(Self {
+                db,
+                sync_wal: false,
+            }),
+            Err(e) => {
+                error!("Error opening the secondary instance: {:?}", e);
+                Err(Box::new(e))
             }
         }
     }
 
-    // pub fn open(folder_path: &Path, secondary: bool) -> Result<Self, Box<dyn Error>> {
-    //     // let seq_path = folder_path.join("rocksdb_seq");
-    //
-    //     // Attempt to create the directory and handle any potential errors
-    //     if let Err(e) = create_dir_all(&folder_path) {
-    //         if e.kind() != AlreadyExists {
-    //             println!("Error creating dirs: {:?}", e);
-    //             return Err(Box::new(e));
-    //         }
-    //     }
-    //
-    //     // Set up RocksDB options
-    //     let mut opts = Options::default();
-    //     opts.create_if_missing(true);
-    //
-    //     // Open the database in secondary mode if specified, otherwise open as primary
-    //     if secondary {
-    //         sleep(Duration::from_secs(5));
-    //         let secondary_db_path = folder_path.join(SECONDARY_DB_PATH);
-    //         println!("secondary : {:?}", secondary_db_path);
-    //         match DBWithThreadMode::open_as_secondary(&opts, folder_path, &secondary_db_path) {
-    //             Ok(db) => Ok(Self { db }),
-    //             Err(e) => {
-    //                 println!("Error opening DB as secondary: {:?}", e);
-    //                 Err(Box::new(e))
-    //             }
-    //         }
-    //     } else {
-    //         println!("primary : {:?}", folder_path);
-    //         match DBWithThreadMode::open_default(&folder_path) {
-    //             Ok(db) => Ok(Self { db }),
-    //             Err(e) => {
-    //                 println!("Error opening DB as primary: {:?}", e);
-    //                 Err(Box::new(e))
-    //             }
-    //         }
-    //     }
-    // }
+    fn write_options(&self) -> WriteOptions {
+        let mut write_options = WriteOptions::default();
+        write_options.set_sync(self.sync_wal);
+        write_options
+    }
+
+    /// Apply the changes made by the primary instance since the secondary was
+    /// opened or last caught up
+    pub fn try_catch_up_with_primary(&self) -> Result<(), Box<dyn Error>> {
+        self.db.try_catch_up_with_primary()?;
+        Ok(())
+    }
 
     pub fn check_and_create_keys(&self) -> Result<(), Box<dyn Error>> {
         // * Check if keys exist more efficiently
@@ -118,7 +193,7 @@ impl RocksDBConnection {
 
         // * Prepare writes for missing keys
         let mut write_batch = WriteBatch::default();
-        let write_options = WriteOptions::default();
+        let write_options = self.write_options();
 
         for key in &keys_to_check {
             if !existing_keys.contains(key) {
@@ -134,7 +209,7 @@ impl RocksDBConnection {
 
         if !write_batch.is_empty() {
             self.db.write_opt(write_batch, &write_options)?;
-            println!(
+            info!(
                 "Keys created: {:?}",
                 keys_to_check
                     .iter()
@@ -142,7 +217,7 @@ impl RocksDBConnection {
                     .collect::<Vec<_>>()
             );
         } else {
-            println!("Keys already exist");
+            debug!("Keys already exist");
         }
 
         Ok(())
@@ -182,18 +257,18 @@ impl RocksDBConnection {
                     Ok(txn_count_str) => match txn_count_str.parse::<u64>() {
                         Ok(count) => Ok(Some(count)),
                         Err(parse_err) => {
-                            println!("Error parsing transaction count: {}", parse_err);
+                            error!("Error parsing transaction count: {}", parse_err);
                             Ok(None)
                         }
                     }
                     Err(utf8_err) => {
-                        println!("Error decoding transaction count data: {}", utf8_err);
+                        error!("Error decoding transaction count data: {}", utf8_err);
                         Ok(None)
                     }
                 }
             }
             Err(db_err) => {
-                println!("Error getting transaction count from RocksDB: {}", db_err);
+                error!("Error getting transaction count from RocksDB: {}", db_err);
                 Ok(None)
             }
             _ => Ok(None),
@@ -203,7 +278,7 @@ impl RocksDBConnection {
     pub fn save_transaction(&self, txn_count: u64, txn_data: &[u8], slot: u64) -> Result<(), Box<dyn Error>> {
         let txn_rocksdb_key = format!("txn-{:?}", txn_count);
         let mut write_batch = WriteBatch::default();
-        let write_options = WriteOptions::default();
+        let write_options = self.write_options();
         write_batch.put(txn_rocksdb_key.as_bytes(), txn_data);
         let new_txn_count_str = (txn_count + 1).to_string();
         write_batch.put(TRANSACTION_COUNT_KEY.as_bytes(), new_txn_count_str.as_bytes());
@@ -214,15 +289,24 @@ impl RocksDBConnection {
         }
     }
 
-    pub fn save_transaction_batch(&self, txn_data: String, slot: u64) -> Result<(), Box<dyn Error>> {
-        // Get the current batch start index
-        let batch_start_index = match self.get_batch_start_index() {
-            Ok(Some(batch_start_index)) => batch_start_index,
-            _ => return Err("Error getting batch_start_index from store data".into()),
-        };
-
+    /// Append the committed transactions of a slot to the current pod, sealing
+    /// pods along the way as the policy requires.
+    ///
+    /// Every transaction record is written under its own key, so appending to a
+    /// pod never rewrites the records already stored in it.
+    ///
+    /// Everything is written in a single batch along with the signature index
+    /// and the last processed slot. Transactions whose signature is already
+    /// indexed, replayed after a restart, are skipped.
+    pub fn save_transaction_batch(
+        &self,
+        transactions: Vec<BufferedTransaction>,
+        slot: u64,
+        policy: &PodSealingPolicy,
+        commitments: &SlotCommitmentTracker,
+    ) -> Result<Vec<PodHeader>, Box<dyn Error>> {
         // Get the current batch count
-        let batch_count = match self.get_batch_count() {
+        let mut batch_count = match self.get_batch_count() {
             Ok(Some(batch_count)) => batch_count,
             _ => return Err("Error getting batch_count from store data".into()),
         };
@@ -233,109 +317,473 @@ impl RocksDBConnection {
             _ => return Err("Error getting transaction_count from store data".into()),
         };
 
-        // Retrieve the existing batch data
-        let batch_rocksdb_key = format!("batch-{:?}", batch_count);
-        let existing_data = self.db.get(&batch_rocksdb_key)?;
-        let mut data_array: Vec<String> = if let Some(value) = existing_data {
-            serde_json::from_slice(&value)?
-        } else {
-            vec![]
-        };
+        let mut open_pod = self
+            .get_open_pod()?
+            .unwrap_or_else(|| OpenPod::new(slot, timestamp()));
+        // Records of the open pod written by this call, the older ones are read back on seal
+        let mut new_records: Vec<Vec<u8>> = vec![];
 
         // Prepare a write batch
         let mut write_batch = WriteBatch::default();
-        let write_options = WriteOptions::default();
+        let write_options = self.write_options();
+        let mut sealed_headers = vec![];
+        let mut new_transaction_count = transaction_count;
 
-        // Check if the current batch has reached its limit
-        if data_array.len() >= 25 {
-            // Increment the batch count
-            let new_batch_count = batch_count + 1;
+        let stored = self
+            .db
+            .multi_get(transactions.iter().map(|txn| Self::signature_key(&txn.signature)));
+        let mut seen_signatures = HashSet::new();
+        for (txn, stored) in transactions.into_iter().zip(stored) {
+            if stored?.is_some() || !seen_signatures.insert(txn.signature.clone()) {
+                warn!("Skipping transaction {} of slot {slot}, it is already stored", txn.signature);
+                continue;
+            }
+            let txn_data = txn.record;
 
-            // Update the batch count in the database
-            write_batch.put(BATCH_COUNT_KEY.as_bytes(), new_batch_count.to_string().as_bytes());
+            // Seal the current pod first if the transaction can't be part of it
+            if open_pod.transaction_count > 0 {
+                if let Some(seal_reason) = policy.seal_before(&open_pod, slot) {
+                    let header = self.seal_batch(
+                        &mut write_batch,
+                        batch_count,
+                        &open_pod,
+                        std::mem::take(&mut new_records),
+                        seal_reason,
+                        commitments,
+                    )?;
+                    sealed_headers.push(header);
+                    batch_count += 1;
+                    open_pod = OpenPod::default();
+                }
+            }
 
-            // Update the batch key and reset data_array for the new batch
-            let new_batch_rocksdb_key = format!("batch-{:?}", new_batch_count);
-            data_array = vec![txn_data]; // Initialize the new batch with the current transaction
+            // The first transaction of a pod opens it
+            if open_pod.transaction_count == 0 {
+                open_pod = OpenPod::new(slot, timestamp());
+            }
+            write_batch.put(
+                Self::pod_record_key(batch_count, open_pod.transaction_count),
+                &txn_data,
+            );
+            let location = TransactionLocation {
+                pod_number: batch_count,
+                index: open_pod.transaction_count,
+                slot,
+            };
+            write_batch.put(Self::signature_key(&txn.signature), encode_versioned(&location)?);
+            open_pod.last_slot = open_pod.last_slot.max(slot);
+            open_pod.byte_count += txn_data.len();
+            open_pod.transaction_count += 1;
+            new_transaction_count += 1;
+            new_records.push(txn_data);
 
-            // Serialize the updated array
-            let updated_data = serde_json::to_vec(&data_array)?;
+            // Seal the pod once it has reached one of its limits
+            if let Some(seal_reason) = policy.seal_after(&open_pod) {
+                let header = self.seal_batch(
+                    &mut write_batch,
+                    batch_count,
+                    &open_pod,
+                    std::mem::take(&mut new_records),
+                    seal_reason,
+                    commitments,
+                )?;
+                sealed_headers.push(header);
+                batch_count += 1;
+                open_pod = OpenPod::default();
+            }
+        }
 
-            // Store the updated array in RocksDB
-            write_batch.put(new_batch_rocksdb_key.as_bytes(), &updated_data);
+        if open_pod.transaction_count == 0 {
+            write_batch.delete(OPEN_POD_KEY.as_bytes());
         } else {
-            // Add the transaction to the current batch
-            data_array.push(txn_data);
-
-            // Serialize the updated array
-            let updated_data = serde_json::to_vec(&data_array)?;
-
-            // Store the updated array in RocksDB
-            write_batch.put(batch_rocksdb_key.as_bytes(), &updated_data);
+            write_batch.put(OPEN_POD_KEY.as_bytes(), encode_versioned(&open_pod)?);
         }
 
-        // Increment the transaction count
-        let new_transaction_count = transaction_count + 1;
+        // Update the transaction count
         write_batch.put(TRANSACTION_COUNT_KEY.as_bytes(), new_transaction_count.to_string().as_bytes());
+        let last_processed_slot = self.get_last_processed_slot()?.map_or(slot, |last| last.max(slot));
+        write_batch.put(LAST_PROCESSED_SLOT_KEY.as_bytes(), last_processed_slot.to_string().as_bytes());
 
         // Write the batch to RocksDB
         match self.db.write_opt(write_batch, &write_options) {
-            Ok(_) => Ok(()),
+            Ok(_) => Ok(sealed_headers),
             Err(e) => Err(Box::new(e)),
         }
     }
 
-    pub fn get_batch_data(&self, batch_number: u64) -> Result<Vec<String>, Box<dyn Error>> {
-        let batch_rocksdb_key = format!("batch-{:?}", batch_number);
-        match self.db.get(&batch_rocksdb_key) {
-            Ok(Some(data)) => {
-                println!("{:?}", data.len());
-                let data_array: Vec<String> = serde_json::from_slice(&data)?;
-                Ok(data_array)
-            }
-            Ok(None) => {
-                println!("none", );
-                Err(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    "Batch data not found",
-                )))
-            }
-            Err(e) => {
-                println!("{:?}", e);
-                Err(Box::new(e))
+    /// Seal the current pod regardless of its size, used for time and root based sealing.
+    /// Returns None if the current pod is still empty.
+    pub fn seal_open_batch(
+        &self,
+        seal_reason: SealReason,
+        commitments: &SlotCommitmentTracker,
+    ) -> Result<Option<PodHeader>, Box<dyn Error>> {
+        let batch_count = match self.get_batch_count() {
+            Ok(Some(batch_count)) => batch_count,
+            _ => return Err("Error getting batch_count from store data".into()),
+        };
+        let open_pod = match self.get_open_pod()? {
+            Some(open_pod) if open_pod.transaction_count > 0 => open_pod,
+            _ => return Ok(None),
+        };
+
+        let mut write_batch = WriteBatch::default();
+        let header = self.seal_batch(
+            &mut write_batch,
+            batch_count,
+            &open_pod,
+            vec![],
+            seal_reason,
+            commitments,
+        )?;
+        write_batch.delete(OPEN_POD_KEY.as_bytes());
+        self.db.write_opt(write_batch, &self.write_options())?;
+        Ok(Some(header))
+    }
+
+    /// Write the header of the pod and move on to the next one. `new_records` are
+    /// the trailing records of the pod which are not in the database yet.
+    fn seal_batch(
+        &self,
+        write_batch: &mut WriteBatch,
+        batch_count: u64,
+        open_pod: &OpenPod,
+        new_records: Vec<Vec<u8>>,
+        seal_reason: SealReason,
+        commitments: &SlotCommitmentTracker,
+    ) -> Result<PodHeader, Box<dyn Error>> {
+        let stored_count = open_pod.transaction_count - new_records.len() as u64;
+        let mut records = self.get_pod_records_range(batch_count, stored_count)?;
+        records.extend(new_records);
+
+        let header = PodHeader::new(
+            batch_count,
+            &records,
+            open_pod.first_slot,
+            open_pod.last_slot,
+            seal_reason,
+            commitments,
+        );
+        write_batch.put(
+            Self::pod_header_key(batch_count),
+            encode_versioned(&header)?,
+        );
+        write_batch.put(
+            Self::pod_sealed_at_key(batch_count),
+            timestamp().to_string().as_bytes(),
+        );
+
+        // Following transactions go to the next pod
+        let new_batch_count = batch_count + 1;
+        write_batch.put(BATCH_COUNT_KEY.as_bytes(), new_batch_count.to_string().as_bytes());
+        Ok(header)
+    }
+
+    /// Highest slot whose transactions were written
+    pub fn get_last_processed_slot(&self) -> Result<Option<Slot>, Box<dyn Error>> {
+        match self.db.get(LAST_PROCESSED_SLOT_KEY.as_bytes())? {
+            Some(data) => Ok(Some(String::from_utf8(data)?.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Move the watermark to a rooted slot once the transactions of every
+    /// slot up to it were written, it never goes back
+    pub fn advance_last_processed_slot(&self, slot: Slot) -> Result<Slot, Box<dyn Error>> {
+        match self.get_last_processed_slot()? {
+            Some(last_processed_slot) if last_processed_slot >= slot => Ok(last_processed_slot),
+            _ => {
+                self.db.put_opt(
+                    LAST_PROCESSED_SLOT_KEY.as_bytes(),
+                    slot.to_string().as_bytes(),
+                    &self.write_options(),
+                )?;
+                Ok(slot)
             }
         }
     }
 
-    pub fn get_pod_len(&self, batch_number: u64) -> Result<u64, Box<dyn Error>> {
-        match self.get_batch_data(batch_number) {
-            Ok(pod_data) => {
-                let len_pod_data = pod_data.len() as u64;
-                Ok(len_pod_data)
+    fn signature_key(signature: &str) -> String {
+        format!("{SIGNATURE_KEY_PREFIX}{signature}")
+    }
+
+    /// Look up the pod a transaction was stored in
+    pub fn get_transaction_location(
+        &self,
+        signature: &str,
+    ) -> Result<Option<TransactionLocation>, Box<dyn Error>> {
+        match self.db.get(Self::signature_key(signature))? {
+            Some(data) => Ok(Some(decode_versioned(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_open_pod(&self) -> Result<Option<OpenPod>, Box<dyn Error>> {
+        match self.db.get(OPEN_POD_KEY.as_bytes())? {
+            Some(data) => Ok(Some(decode_versioned(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn pod_header_key(pod_number: u64) -> String {
+        format!("{POD_KEY_PREFIX}{pod_number}/header")
+    }
+
+    fn pod_record_key(pod_number: u64, index: u64) -> String {
+        format!("{POD_KEY_PREFIX}{pod_number}/{index}")
+    }
+
+    fn pod_sealed_at_key(pod_number: u64) -> String {
+        format!("{POD_KEY_PREFIX}{pod_number}/sealed_at")
+    }
+
+    /// Time the pod was sealed, `None` until it is sealed
+    pub fn get_pod_sealed_at(&self, pod_number: u64) -> Result<Option<u64>, Box<dyn Error>> {
+        match self.db.get(Self::pod_sealed_at_key(pod_number))? {
+            Some(data) => Ok(Some(String::from_utf8(data)?.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Highest pod the consumers are done with
+    pub fn get_settled_pod_number(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        match self.db.get(SETTLED_POD_NUMBER_KEY.as_bytes())? {
+            Some(data) => Ok(Some(String::from_utf8(data)?.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Move the settled marker forward, it never goes back
+    pub fn set_settled_pod_number(&self, pod_number: u64) -> Result<(), Box<dyn Error>> {
+        if self
+            .get_settled_pod_number()?
+            .map_or(true, |settled_pod_number| pod_number > settled_pod_number)
+        {
+            self.db.put_opt(
+                SETTLED_POD_NUMBER_KEY.as_bytes(),
+                pod_number.to_string().as_bytes(),
+                &self.write_options(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Highest pruned pod, 0 until a pod is pruned
+    pub fn get_pruned_pod_number(&self) -> Result<u64, Box<dyn Error>> {
+        match self.db.get(PRUNED_POD_NUMBER_KEY.as_bytes())? {
+            Some(data) => Ok(String::from_utf8(data)?.parse()?),
+            None => Ok(0),
+        }
+    }
+
+    /// Last slot of the pruned pods, `None` until a pod is pruned
+    pub fn get_pruned_slot(&self) -> Result<Option<Slot>, Box<dyn Error>> {
+        match self.db.get(PRUNED_SLOT_KEY.as_bytes())? {
+            Some(data) => Ok(Some(String::from_utf8(data)?.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Delete a sealed pod along with the signature index of its `records`.
+    /// Pods are pruned in order, starting from the first one.
+    pub fn prune_pod(&self, pod_number: u64, records: &[Vec<u8>]) -> Result<(), Box<dyn Error>> {
+        let pruned_pod_number = self.get_pruned_pod_number()?;
+        if pod_number != pruned_pod_number + 1 {
+            return Err(format!(
+                "Pod {pod_number} can't be pruned, the last pruned pod is {pruned_pod_number}"
+            )
+            .into());
+        }
+        let header = self
+            .get_pod_header(pod_number)?
+            .filter(|header| header.sealed)
+            .ok_or_else(|| format!("Pod {pod_number} can't be pruned before it is sealed"))?;
+        let pruned_slot = self
+            .get_pruned_slot()?
+            .unwrap_or_default()
+            .max(header.last_slot);
+
+        let mut write_batch = WriteBatch::default();
+        for (index, record) in records.iter().enumerate() {
+            let transaction = TxnStoreStruct::from_record(record)?;
+            write_batch.delete(Self::signature_key(&transaction.signature));
+            write_batch.delete(Self::pod_record_key(pod_number, index as u64));
+        }
+        write_batch.delete(Self::pod_header_key(pod_number));
+        write_batch.delete(Self::pod_sealed_at_key(pod_number));
+        write_batch.put(
+            PRUNED_POD_NUMBER_KEY.as_bytes(),
+            pod_number.to_string().as_bytes(),
+        );
+        write_batch.put(
+            PRUNED_SLOT_KEY.as_bytes(),
+            pruned_slot.to_string().as_bytes(),
+        );
+        self.db.write_opt(write_batch, &self.write_options())?;
+        Ok(())
+    }
+
+    pub fn get_pod_header(&self, pod_number: u64) -> Result<Option<PodHeader>, Box<dyn Error>> {
+        match self.db.get(Self::pod_header_key(pod_number))? {
+            Some(data) => Ok(Some(decode_versioned(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn is_pod_sealed(&self, pod_number: u64) -> Result<bool, Box<dyn Error>> {
+        Ok(self
+            .get_pod_header(pod_number)?
+            .map_or(false, |header| header.sealed))
+    }
+
+    pub fn save_pod_header(&self, header: &PodHeader) -> Result<(), Box<dyn Error>> {
+        self.db.put_opt(
+            Self::pod_header_key(header.pod_number),
+            encode_versioned(header)?,
+            &self.write_options(),
+        )?;
+        Ok(())
+    }
+
+    /// Read the records of a sealed pod, in pod order
+    pub fn get_pod_records(&self, pod_number: u64) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        match self.get_pod_header(pod_number)? {
+            Some(header) => self.get_pod_records_range(pod_number, header.transaction_count),
+            None => Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Pod not found",
+            ))),
+        }
+    }
+
+    /// Read the records appended to the open pod so far, with its number
+    pub fn get_open_pod_records(&self) -> Result<Option<(u64, Vec<Vec<u8>>)>, Box<dyn Error>> {
+        let Some(open_pod) = self.get_open_pod()? else {
+            return Ok(None);
+        };
+        let pod_number = self
+            .get_batch_count()?
+            .ok_or("Error getting batch_count from store data")?;
+        let records = self.get_pod_records_range(pod_number, open_pod.transaction_count)?;
+        Ok(Some((pod_number, records)))
+    }
+
+    fn get_pod_records_range(
+        &self,
+        pod_number: u64,
+        transaction_count: u64,
+    ) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        let keys = (0..transaction_count).map(|index| Self::pod_record_key(pod_number, index));
+        self.db
+            .multi_get(keys)
+            .into_iter()
+            .enumerate()
+            .map(|(index, record)| -> Result<Vec<u8>, Box<dyn Error>> {
+                match record? {
+                    Some(record) => Ok(record),
+                    None => Err(format!("Missing record {index} of pod {pod_number}").into()),
+                }
+            })
+            .collect()
+    }
+
+    /// Rewrite a store using the JSON batches of earlier releases into the
+    /// versioned binary pod format. Batches are converted one at a time and the
+    /// format version is written last, so an interrupted migration resumes on
+    /// the next start.
+    pub fn migrate_legacy_batches(&self) -> Result<(), Box<dyn Error>> {
+        if self.db.get(POD_FORMAT_VERSION_KEY.as_bytes())?.is_some() {
+            return Ok(());
+        }
+        let mut batch_count = match self.get_batch_count() {
+            Ok(Some(batch_count)) => batch_count,
+            _ => return Err("Error getting batch_count from store data".into()),
+        };
+        let no_commitments = SlotCommitmentTracker::default();
+
+        for pod_number in 1..=batch_count {
+            let legacy_key = format!("batch-{:?}", pod_number);
+            let data_array: Vec<String> = match self.db.get(&legacy_key)? {
+                Some(data) => serde_json::from_slice(&data)?,
+                None => continue,
+            };
+            let transactions = data_array
+                .iter()
+                .map(|data_str| serde_json::from_str::<TxnStoreStruct>(data_str))
+                .collect::<Result<Vec<_>, _>>()?;
+            let records = transactions
+                .iter()
+                .map(encode_versioned)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut write_batch = WriteBatch::default();
+            for (index, (record, transaction)) in records.iter().zip(&transactions).enumerate() {
+                write_batch.put(Self::pod_record_key(pod_number, index as u64), record);
+                let location = TransactionLocation {
+                    pod_number,
+                    index: index as u64,
+                    slot: transaction.slot(),
+                };
+                write_batch.put(
+                    Self::signature_key(&transaction.signature),
+                    encode_versioned(&location)?,
+                );
             }
-            Err(e) => Err(e),
+            let first_slot = transactions.first().map(TxnStoreStruct::slot).unwrap_or_default();
+            let last_slot = transactions.iter().map(TxnStoreStruct::slot).max().unwrap_or_default();
+
+            // Batches used to roll over when the next transaction arrived, so a
+            // full batch at batch_count is sealed too
+            if pod_number < batch_count || records.len() >= DEFAULT_MAX_POD_TRANSACTIONS {
+                let header = PodHeader::new(
+                    pod_number,
+                    &records,
+                    first_slot,
+                    last_slot,
+                    SealReason::MaxTransactions,
+                    &no_commitments,
+                );
+                write_batch.put(Self::pod_header_key(pod_number), encode_versioned(&header)?);
+                // Batches didn't record their seal time, the retention age of
+                // migrated pods starts at the migration
+                write_batch.put(
+                    Self::pod_sealed_at_key(pod_number),
+                    timestamp().to_string().as_bytes(),
+                );
+                if pod_number == batch_count {
+                    batch_count += 1;
+                    write_batch.put(BATCH_COUNT_KEY.as_bytes(), batch_count.to_string().as_bytes());
+                }
+            } else if !records.is_empty() {
+                let open_pod = OpenPod {
+                    first_slot,
+                    last_slot,
+                    byte_count: records.iter().map(Vec::len).sum(),
+                    opened_at_ms: timestamp(),
+                    transaction_count: records.len() as u64,
+                };
+                write_batch.put(OPEN_POD_KEY.as_bytes(), encode_versioned(&open_pod)?);
+            }
+            write_batch.delete(legacy_key.as_bytes());
+            self.db.write_opt(write_batch, &self.write_options())?;
         }
+
+        self.db.put_opt(
+            POD_FORMAT_VERSION_KEY.as_bytes(),
+            POD_FORMAT_VERSION.to_string().as_bytes(),
+            &self.write_options(),
+        )?;
+        info!("Migrated {} pods to pod format version {}", batch_count, POD_FORMAT_VERSION);
+        Ok(())
     }
 
     // New method to get the latest batch number
     pub fn get_latest_batch_number(&self) -> Result<u64, Box<dyn Error>> {
         match self.db.get(BATCH_COUNT_KEY.as_bytes()) {
             Ok(Some(data)) => {
+                // The pod at batch_count is still open, every pod before it is sealed
                 let batch_count: u64 = String::from_utf8(data)?.parse()?;
-                match self.get_pod_len(batch_count) {
-                    Ok(pod_data_len) => {
-                        if pod_data_len == 25 {
-                            Ok(batch_count)
-                        } else {
-                            if batch_count > 0 {
-                                Ok(batch_count - 1)
-                            } else {
-                                Ok(0)
-                            }
-                        }
-                    }
-                    Err(e) => Err(e)
-                }
+                Ok(batch_count.saturating_sub(1))
             }
             Ok(None) => Err(Box::new(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -345,3 +793,243 @@ impl RocksDBConnection {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use {
+        super::*,
+        crate::pod_retention::{PodPruner, PodRetentionPolicy},
+        tempfile::TempDir,
+    };
+
+    fn open_connection(temp_dir: &TempDir) -> RocksDBConnection {
+        let connection = RocksDBConnection::open(temp_dir.path(), false).unwrap();
+        connection.check_and_create_keys().unwrap();
+        connection
+    }
+
+    fn record(data: &str) -> Vec<u8> {
+        encode_versioned(&data.to_string()).unwrap()
+    }
+
+    fn transaction(data: &str) -> BufferedTransaction {
+        BufferedTransaction {
+            index: 0,
+            signature: format!("signature-{data}"),
+            record: record(data),
+        }
+    }
+
+    #[test]
+    fn test_save_transaction_batch_seals_pod() {
+        let temp_dir = TempDir::new().unwrap();
+        let connection = open_connection(&temp_dir);
+        let policy = PodSealingPolicy::default();
+        let commitments = SlotCommitmentTracker::default();
+
+        for i in 0..DEFAULT_MAX_POD_TRANSACTIONS - 1 {
+            let sealed = connection
+                .save_transaction_batch(
+                    vec![transaction(&format!("txn-{i}"))],
+                    10 + i as u64 / 10,
+                    &policy,
+                    &commitments,
+                )
+                .unwrap();
+            assert!(sealed.is_empty());
+        }
+        assert!(!connection.is_pod_sealed(1).unwrap());
+        assert!(connection.get_pod_records(1).is_err());
+        assert_eq!(connection.get_latest_batch_number().unwrap(), 0);
+
+        let mut sealed = connection
+            .save_transaction_batch(vec![transaction("txn-last")], 13, &policy, &commitments)
+            .unwrap();
+        assert_eq!(sealed.len(), 1);
+        let header = sealed.pop().unwrap();
+        assert_eq!(header.pod_number, 1);
+        assert_eq!(header.seal_reason, SealReason::MaxTransactions);
+        assert_eq!(header.transaction_count, DEFAULT_MAX_POD_TRANSACTIONS as u64);
+        assert_eq!(header.first_slot, 10);
+        assert_eq!(header.last_slot, 13);
+
+        let records = connection.get_pod_records(1).unwrap();
+        assert_eq!(records.len(), DEFAULT_MAX_POD_TRANSACTIONS);
+        assert_eq!(records[0], record("txn-0"));
+        assert_eq!(
+            header.transactions_root,
+            crate::pod::transactions_root(&records).to_string()
+        );
+        assert_eq!(connection.get_pod_header(1).unwrap(), Some(header));
+        assert!(connection.is_pod_sealed(1).unwrap());
+        assert_eq!(connection.get_latest_batch_number().unwrap(), 1);
+
+        let sealed = connection
+            .save_transaction_batch(vec![transaction("txn-next")], 14, &policy, &commitments)
+            .unwrap();
+        assert!(sealed.is_empty());
+        assert_eq!(connection.get_open_pod().unwrap().unwrap().transaction_count, 1);
+    }
+
+    #[test]
+    fn test_save_transaction_batch_skips_stored_transactions() {
+        let temp_dir = TempDir::new().unwrap();
+        let connection = open_connection(&temp_dir);
+        let policy = PodSealingPolicy::default();
+        let commitments = SlotCommitmentTracker::default();
+        assert_eq!(connection.get_last_processed_slot().unwrap(), None);
+
+        connection
+            .save_transaction_batch(
+                vec![transaction("txn-1"), transaction("txn-2"), transaction("txn-1")],
+                5,
+                &policy,
+                &commitments,
+            )
+            .unwrap();
+        assert_eq!(connection.get_open_pod().unwrap().unwrap().transaction_count, 2);
+        assert_eq!(connection.get_last_processed_slot().unwrap(), Some(5));
+        assert_eq!(
+            connection.get_transaction_location("signature-txn-2").unwrap(),
+            Some(TransactionLocation {
+                pod_number: 1,
+                index: 1,
+                slot: 5,
+            })
+        );
+
+        // Transactions replayed after a restart are not appended again
+        connection
+            .save_transaction_batch(
+                vec![transaction("txn-2"), transaction("txn-3")],
+                6,
+                &policy,
+                &commitments,
+            )
+            .unwrap();
+        assert_eq!(connection.get_open_pod().unwrap().unwrap().transaction_count, 3);
+        assert_eq!(connection.get_transaction_count().unwrap(), Some(3));
+        assert_eq!(connection.get_last_processed_slot().unwrap(), Some(6));
+
+        // The watermark never moves back
+        connection
+            .save_transaction_batch(vec![transaction("txn-4")], 4, &policy, &commitments)
+            .unwrap();
+        assert_eq!(connection.get_last_processed_slot().unwrap(), Some(6));
+        assert!(connection.get_transaction_location("signature-txn-5").unwrap().is_none());
+
+        // Rooted slots without selected transactions move it too
+        assert_eq!(connection.advance_last_processed_slot(9).unwrap(), 9);
+        assert_eq!(connection.advance_last_processed_slot(8).unwrap(), 9);
+        assert_eq!(connection.get_last_processed_slot().unwrap(), Some(9));
+    }
+
+    #[test]
+    fn test_sealing_policy_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let connection = open_connection(&temp_dir);
+        let policy = PodSealingPolicy {
+            max_transactions: None,
+            max_slots: Some(2),
+            ..PodSealingPolicy::default()
+        };
+        let commitments = SlotCommitmentTracker::default();
+
+        let sealed = connection
+            .save_transaction_batch(vec![transaction("txn-5a"), transaction("txn-5b")], 5, &policy, &commitments)
+            .unwrap();
+        assert!(sealed.is_empty());
+        let sealed = connection
+            .save_transaction_batch(vec![transaction("txn-6")], 6, &policy, &commitments)
+            .unwrap();
+        assert!(sealed.is_empty());
+
+        // A transaction past the slot range seals the pod before being added
+        let sealed = connection
+            .save_transaction_batch(vec![transaction("txn-7")], 7, &policy, &commitments)
+            .unwrap();
+        assert_eq!(sealed.len(), 1);
+        assert_eq!(sealed[0].seal_reason, SealReason::MaxSlots);
+        assert_eq!(sealed[0].transaction_count, 3);
+        assert_eq!((sealed[0].first_slot, sealed[0].last_slot), (5, 6));
+
+        // Root and time based sealing close partial pods
+        let header = connection
+            .seal_open_batch(SealReason::Rooted, &commitments)
+            .unwrap()
+            .unwrap();
+        assert_eq!(header.pod_number, 2);
+        assert_eq!(header.parent_pod_number, Some(1));
+        assert_eq!(header.transaction_count, 1);
+        assert_eq!(connection.get_pod_records(2).unwrap(), vec![record("txn-7")]);
+        assert!(connection.get_open_pod().unwrap().is_none());
+        assert!(connection
+            .seal_open_batch(SealReason::Timeout, &commitments)
+            .unwrap()
+            .is_none());
+        assert_eq!(connection.get_latest_batch_number().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_migrate_legacy_batches() {
+        let temp_dir = TempDir::new().unwrap();
+        let connection = open_connection(&temp_dir);
+
+        // Two full legacy batches, the second one not rolled over yet
+        let transaction = |slot: i64| {
+            let mut transaction = serde_json::to_value(TxnStoreStruct::new()).unwrap();
+            transaction["slot"] = slot.into();
+            // Fields the legacy batches didn't have
+            let meta = transaction["meta"].as_object_mut().unwrap();
+            for field in ["loaded_addresses", "return_data", "compute_units_consumed"] {
+                meta.remove(field);
+            }
+            transaction.to_string()
+        };
+        for pod_number in 1..=2 {
+            let data_array: Vec<String> = (0..DEFAULT_MAX_POD_TRANSACTIONS as i64)
+                .map(|i| transaction(pod_number * 100 + i))
+                .collect();
+            connection
+                .db
+                .put(
+                    format!("batch-{:?}", pod_number),
+                    serde_json::to_vec(&data_array).unwrap(),
+                )
+                .unwrap();
+        }
+        connection.db.put(BATCH_COUNT_KEY, "2").unwrap();
+
+        connection.migrate_legacy_batches().unwrap();
+        assert_eq!(connection.get_latest_batch_number().unwrap(), 2);
+        let header = connection.get_pod_header(2).unwrap().unwrap();
+        assert_eq!(header.parent_pod_number, Some(1));
+        assert_eq!((header.first_slot, header.last_slot), (200, 224));
+        let records = connection.get_pod_records(1).unwrap();
+        assert_eq!(records.len(), DEFAULT_MAX_POD_TRANSACTIONS);
+        let first: TxnStoreStruct = decode_versioned(&records[0]).unwrap();
+        assert_eq!(first.slot(), 100);
+        assert!(connection.db.get("batch-1").unwrap().is_none());
+        assert!(connection.get_open_pod().unwrap().is_none());
+
+        // Migrated pods are kept until they reach the retention age
+        let sealed_at_ms = connection.get_pod_sealed_at(1).unwrap();
+        assert!(sealed_at_ms.is_some());
+        let pruner = PodPruner::new(
+            PodRetentionPolicy {
+                max_age_secs: Some(60),
+                settled_only: false,
+                ..PodRetentionPolicy::default()
+            },
+            temp_dir.path().to_path_buf(),
+            vec![],
+        );
+        let now_ms = timestamp();
+        assert_eq!(pruner.prune(&connection, now_ms).unwrap(), 0);
+        assert_eq!(pruner.prune(&connection, now_ms + 60_000).unwrap(), 2);
+
+        // Running it again is a no-op
+        connection.migrate_legacy_batches().unwrap();
+        assert_eq!(connection.get_latest_batch_number().unwrap(), 2);
+    }
+}
@@ -0,0 +1,255 @@
+use {
+    crate::txn_select::RocksDBAccountWrite,
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::clock::Slot,
+    std::collections::{BTreeMap, HashMap},
+};
+
+/// Commitment a slot must reach before its transactions are added to pods
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PodCommitment {
+    #[default]
+    Rooted,
+    Confirmed,
+}
+
+/// A selected transaction waiting for its slot to be committed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BufferedTransaction {
+    /// The transaction's index in the block
+    pub index: usize,
+    pub signature: String,
+    /// The serialized transaction record
+    pub record: Vec<u8>,
+}
+
+/// Holds the selected transactions of every slot that has not reached the
+/// configured commitment yet, so that transactions from abandoned forks never
+/// make it into a pod.
+#[derive(Default)]
+pub struct SlotBuffer {
+    transactions: BTreeMap<Slot, Vec<BufferedTransaction>>,
+    /// Account writes waiting for their transaction, keyed by signature
+    account_writes: BTreeMap<Slot, HashMap<String, Vec<RocksDBAccountWrite>>>,
+    parents: HashMap<Slot, Slot>,
+    newest_root: Option<Slot>,
+}
+
+impl SlotBuffer {
+    /// Returns false if the transaction was dropped because its slot is at or
+    /// below the newest root
+    #[must_use]
+    pub fn push(&mut self, slot: Slot, transaction: BufferedTransaction) -> bool {
+        // Late notifications for a slot at or below the root can't be committed anymore
+        if self.newest_root.map_or(false, |root| slot <= root) {
+            return false;
+        }
+        self.transactions.entry(slot).or_default().push(transaction);
+        true
+    }
+
+    /// Newest rooted or already written slot
+    pub fn newest_root(&self) -> Option<Slot> {
+        self.newest_root
+    }
+
+    /// Account updates are notified while the bank commits, before the
+    /// transaction itself is notified.
+    pub fn push_account_write(&mut self, slot: Slot, account_write: RocksDBAccountWrite) {
+        if self.newest_root.map_or(false, |root| slot <= root) {
+            return;
+        }
+        self.account_writes
+            .entry(slot)
+            .or_default()
+            .entry(account_write.txn_signature.clone())
+            .or_default()
+            .push(account_write);
+    }
+
+    /// Take the account writes of a transaction
+    pub fn take_account_writes(&mut self, slot: Slot, signature: &str) -> Vec<RocksDBAccountWrite> {
+        self.account_writes
+            .get_mut(&slot)
+            .and_then(|account_writes| account_writes.remove(signature))
+            .unwrap_or_default()
+    }
+
+    /// Slots up to `slot` were written before a restart, the transactions
+    /// replayed for them are ignored like those of rooted slots
+    pub fn set_last_processed_slot(&mut self, slot: Slot) {
+        self.newest_root = Some(self.newest_root.map_or(slot, |root| root.max(slot)));
+    }
+
+    /// Remember the parent of a processed slot
+    pub fn set_parent(&mut self, slot: Slot, parent: Slot) {
+        self.parents.insert(slot, parent);
+    }
+
+    /// The slot was rooted: take its transactions and drop every older slot that
+    /// was not committed, those belong to abandoned forks.
+    pub fn take_rooted(&mut self, slot: Slot) -> Vec<(Slot, Vec<BufferedTransaction>)> {
+        let mut committed = self.take_confirmed(slot);
+        if let Some(transactions) = self.transactions.remove(&slot) {
+            committed.push((slot, transactions));
+        }
+        self.newest_root = Some(self.newest_root.map_or(slot, |root| root.max(slot)));
+        self.transactions = self.transactions.split_off(&(slot + 1));
+        let parents = &self.parents;
+        self.transactions
+            .retain(|buffered_slot, _| !Self::is_abandoned(parents, *buffered_slot, slot));
+        self.account_writes = self.account_writes.split_off(&(slot + 1));
+        self.account_writes
+            .retain(|buffered_slot, _| !Self::is_abandoned(parents, *buffered_slot, slot));
+        self.parents
+            .retain(|buffered_slot, _| *buffered_slot > slot);
+        committed
+    }
+
+    /// Check if a slot newer than the root does not descend from it
+    fn is_abandoned(parents: &HashMap<Slot, Slot>, slot: Slot, root: Slot) -> bool {
+        let mut current = slot;
+        while let Some(parent) = parents.get(&current) {
+            if *parent <= root {
+                return *parent != root;
+            }
+            current = *parent;
+        }
+        false
+    }
+
+    /// The slot was confirmed: take its transactions along with those of its
+    /// ancestors which are still buffered, oldest slot first.
+    pub fn take_confirmed(&mut self, slot: Slot) -> Vec<(Slot, Vec<BufferedTransaction>)> {
+        let mut ancestors = vec![slot];
+        let mut current = slot;
+        while let Some(parent) = self.parents.get(&current) {
+            if self.newest_root.map_or(false, |root| *parent <= root) {
+                break;
+            }
+            ancestors.push(*parent);
+            current = *parent;
+        }
+
+        ancestors
+            .into_iter()
+            .rev()
+            .filter_map(|slot| {
+                self.transactions
+                    .remove(&slot)
+                    .map(|transactions| (slot, transactions))
+            })
+            .collect()
+    }
+
+    /// Number of slots still waiting to be committed
+    pub fn pending_slots(&self) -> usize {
+        self.transactions.len()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn transaction(index: usize) -> BufferedTransaction {
+        BufferedTransaction {
+            index,
+            signature: format!("signature-{index}"),
+            record: format!("txn-{index}").into_bytes(),
+        }
+    }
+
+    #[test]
+    fn test_rooted_slots_drop_abandoned_forks() {
+        let mut buffer = SlotBuffer::default();
+        // 1 <- 2 <- 4 is the rooted fork, 3 was abandoned
+        assert!(buffer.push(2, transaction(0)));
+        assert!(buffer.push(3, transaction(0)));
+        assert!(buffer.push(4, transaction(0)));
+        assert!(buffer.push(4, transaction(1)));
+        buffer.set_parent(2, 1);
+        buffer.set_parent(3, 1);
+        buffer.set_parent(4, 2);
+
+        assert_eq!(buffer.take_rooted(2), vec![(2, vec![transaction(0)])]);
+        assert_eq!(buffer.pending_slots(), 1);
+        assert_eq!(
+            buffer.take_rooted(4),
+            vec![(4, vec![transaction(0), transaction(1)])]
+        );
+        assert_eq!(buffer.pending_slots(), 0);
+
+        // Notifications behind the root are ignored
+        assert!(!buffer.push(3, transaction(1)));
+        assert_eq!(buffer.pending_slots(), 0);
+    }
+
+    #[test]
+    fn test_account_writes() {
+        let account_write = |slot: Slot, signature: &str| RocksDBAccountWrite {
+            pubkey: "pubkey".to_string(),
+            owner: "owner".to_string(),
+            lamports: slot,
+            data_hash: "hash".to_string(),
+            write_version: slot,
+            txn_signature: signature.to_string(),
+        };
+        let mut buffer = SlotBuffer::default();
+        buffer.set_parent(2, 1);
+        buffer.set_parent(3, 1);
+        buffer.push_account_write(2, account_write(2, "a"));
+        buffer.push_account_write(2, account_write(2, "b"));
+        buffer.push_account_write(3, account_write(3, "c"));
+
+        assert_eq!(
+            buffer.take_account_writes(2, "a"),
+            vec![account_write(2, "a")]
+        );
+        assert!(buffer.take_account_writes(2, "a").is_empty());
+        assert!(buffer.take_account_writes(3, "a").is_empty());
+
+        // Rooting drops the writes never claimed by a transaction
+        buffer.take_rooted(2);
+        assert!(buffer.take_account_writes(2, "b").is_empty());
+        assert!(buffer.take_account_writes(3, "c").is_empty());
+        buffer.push_account_write(2, account_write(2, "d"));
+        assert!(buffer.take_account_writes(2, "d").is_empty());
+    }
+
+    #[test]
+    fn test_confirmed_slots_commit_ancestors() {
+        let mut buffer = SlotBuffer::default();
+        assert!(buffer.push(5, transaction(0)));
+        assert!(buffer.push(6, transaction(0)));
+        assert!(buffer.push(7, transaction(0)));
+        buffer.set_parent(5, 4);
+        buffer.set_parent(6, 5);
+        buffer.set_parent(7, 5);
+
+        assert_eq!(
+            buffer.take_confirmed(6),
+            vec![(5, vec![transaction(0)]), (6, vec![transaction(0)])]
+        );
+        assert!(buffer.take_confirmed(6).is_empty());
+
+        // Rooting a confirmed slot doesn't commit it twice, 7 is dropped
+        assert!(buffer.take_rooted(6).is_empty());
+        assert_eq!(buffer.pending_slots(), 0);
+    }
+
+    #[test]
+    fn test_last_processed_slot() {
+        let mut buffer = SlotBuffer::default();
+        buffer.set_last_processed_slot(10);
+        // Slots replayed after a restart were already written
+        assert!(!buffer.push(9, transaction(0)));
+        assert!(!buffer.push(10, transaction(0)));
+        assert_eq!(buffer.pending_slots(), 0);
+
+        assert!(buffer.push(11, transaction(0)));
+        buffer.set_parent(11, 10);
+        assert_eq!(buffer.take_rooted(11), vec![(11, vec![transaction(0)])]);
+    }
+}
@@ -1,14 +1,36 @@
 use solana_sdk::instruction::CompiledInstruction;
 use solana_sdk::message::v0::{LoadedAddresses, MessageAddressTableLookup};
-use solana_sdk::message::{v0, Message, MessageHeader, SanitizedMessage};
+use solana_sdk::message::{
+    v0, Message, MessageHeader, SanitizedMessage, SimpleAddressLoader, VersionedMessage,
+};
 use solana_sdk::reward_type::RewardType;
-use solana_transaction_status::{InnerInstructions, Reward, TransactionTokenBalance};
+use solana_transaction_status::{
+    InnerInstruction, InnerInstructions, Reward, TransactionTokenBalance,
+};
 use {
-    crate::rocksdb_client::RocksDBConnection,
+    crate::pod::{decode_versioned, encode_versioned},
     serde_derive::{Deserialize, Serialize},
-    solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaTransactionInfoV2,
-    solana_sdk::transaction::TransactionError,
-    solana_transaction_status::TransactionStatusMeta,
+    solana_account_decoder::parse_token::UiTokenAmount,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaAccountInfoV3, ReplicaTransactionInfoV2,
+    },
+    solana_sdk::{
+        clock::UnixTimestamp,
+        hash::{hash, Hash},
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{MessageHash, SanitizedTransaction, TransactionError, VersionedTransaction},
+        transaction_context::TransactionReturnData,
+    },
+    solana_transaction_status::{
+        ConfirmedTransactionWithStatusMeta, TransactionStatusMeta, TransactionWithStatusMeta,
+        VersionedTransactionWithStatusMeta,
+    },
+    std::{
+        collections::{btree_map::Entry, BTreeMap},
+        error::Error,
+        str::FromStr,
+    },
 };
 
 const MAX_TRANSACTION_STATUS_LEN: usize = 256;
@@ -35,10 +57,29 @@ impl From<&CompiledInstruction> for RocksDBCompiledInstruction {
     }
 }
 
+impl TryFrom<&RocksDBCompiledInstruction> for CompiledInstruction {
+    type Error = Box<dyn Error>;
+
+    fn try_from(instruction: &RocksDBCompiledInstruction) -> Result<Self, Self::Error> {
+        Ok(Self {
+            program_id_index: instruction.program_id_index as u8,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|account_idx| *account_idx as u8)
+                .collect(),
+            data: bs58::decode(&instruction.data).into_vec()?,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RocksDBInnerInstructions {
     pub index: i16,
     pub instructions: Vec<RocksDBCompiledInstruction>,
+    /// Invocation stack height of each instruction
+    #[serde(default)]
+    pub stack_heights: Vec<Option<u32>>,
 }
 
 impl From<&InnerInstructions> for RocksDBInnerInstructions {
@@ -50,16 +91,51 @@ impl From<&InnerInstructions> for RocksDBInnerInstructions {
                 .iter()
                 .map(|instruction| RocksDBCompiledInstruction::from(&instruction.instruction))
                 .collect(),
+            stack_heights: instructions
+                .instructions
+                .iter()
+                .map(|instruction| instruction.stack_height)
+                .collect(),
         }
     }
 }
 
+impl TryFrom<&RocksDBInnerInstructions> for InnerInstructions {
+    type Error = Box<dyn Error>;
+
+    fn try_from(instructions: &RocksDBInnerInstructions) -> Result<Self, Self::Error> {
+        Ok(Self {
+            index: instructions.index as u8,
+            instructions: instructions
+                .instructions
+                .iter()
+                .enumerate()
+                .map(|(i, instruction)| {
+                    Ok(InnerInstruction {
+                        instruction: CompiledInstruction::try_from(instruction)?,
+                        stack_height: instructions.stack_heights.get(i).copied().flatten(),
+                    })
+                })
+                .collect::<Result<_, Box<dyn Error>>>()?,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RocksDBTransactionTokenBalance {
     pub account_index: i16,
     pub mint: String,
     pub ui_token_amount: Option<f64>,
     pub owner: String,
+    /// Raw amount in base units, `ui_token_amount` is only an approximation of it
+    #[serde(default)]
+    pub amount: String,
+    #[serde(default)]
+    pub decimals: u8,
+    #[serde(default)]
+    pub ui_amount_string: String,
+    #[serde(default)]
+    pub program_id: String,
 }
 
 impl From<&TransactionTokenBalance> for RocksDBTransactionTokenBalance {
@@ -69,6 +145,27 @@ impl From<&TransactionTokenBalance> for RocksDBTransactionTokenBalance {
             mint: token_balance.mint.clone(),
             ui_token_amount: token_balance.ui_token_amount.ui_amount,
             owner: token_balance.owner.clone(),
+            amount: token_balance.ui_token_amount.amount.clone(),
+            decimals: token_balance.ui_token_amount.decimals,
+            ui_amount_string: token_balance.ui_token_amount.ui_amount_string.clone(),
+            program_id: token_balance.program_id.clone(),
+        }
+    }
+}
+
+impl From<&RocksDBTransactionTokenBalance> for TransactionTokenBalance {
+    fn from(token_balance: &RocksDBTransactionTokenBalance) -> Self {
+        Self {
+            account_index: token_balance.account_index as u8,
+            mint: token_balance.mint.clone(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: token_balance.ui_token_amount,
+                decimals: token_balance.decimals,
+                amount: token_balance.amount.clone(),
+                ui_amount_string: token_balance.ui_amount_string.clone(),
+            },
+            owner: token_balance.owner.clone(),
+            program_id: token_balance.program_id.clone(),
         }
     }
 }
@@ -101,6 +198,17 @@ pub struct RocksDBReward {
     pub commission: Option<i16>,
 }
 
+impl From<&RocksDBRewardType> for RewardType {
+    fn from(reward_type: &RocksDBRewardType) -> Self {
+        match reward_type {
+            RocksDBRewardType::Fee => Self::Fee,
+            RocksDBRewardType::Rent => Self::Rent,
+            RocksDBRewardType::Staking => Self::Staking,
+            RocksDBRewardType::Voting => Self::Voting,
+        }
+    }
+}
+
 fn get_reward_type(reward: &Option<RewardType>) -> Option<RocksDBRewardType> {
     reward.as_ref().map(RocksDBRewardType::from)
 }
@@ -120,6 +228,21 @@ impl From<&Reward> for RocksDBReward {
     }
 }
 
+impl From<&RocksDBReward> for Reward {
+    fn from(reward: &RocksDBReward) -> Self {
+        Self {
+            pubkey: reward.pubkey.clone(),
+            lamports: reward.lamports,
+            post_balance: reward.post_balance as u64,
+            reward_type: reward.reward_type.as_ref().map(RewardType::from),
+            commission: reward
+                .commission
+                .as_ref()
+                .map(|commission| *commission as u8),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum RocksDBTransactionErrorCode {
     AccountInUse,
@@ -226,8 +349,38 @@ impl From<&TransactionError> for RocksDBTransactionErrorCode {
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct RocksDBTransactionError {
-    error_code: RocksDBTransactionErrorCode,
-    error_detail: Option<String>,
+    pub error_code: RocksDBTransactionErrorCode,
+    /// Human readable description, truncated to `MAX_TRANSACTION_STATUS_LEN`
+    pub error_detail: Option<String>,
+    /// The exact error the transaction failed with
+    #[serde(default)]
+    pub error: Option<TransactionError>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RocksDBTransactionReturnData {
+    pub program_id: String,
+    pub data: Vec<u8>,
+}
+
+impl From<&TransactionReturnData> for RocksDBTransactionReturnData {
+    fn from(return_data: &TransactionReturnData) -> Self {
+        Self {
+            program_id: return_data.program_id.to_string(),
+            data: return_data.data.clone(),
+        }
+    }
+}
+
+impl TryFrom<&RocksDBTransactionReturnData> for TransactionReturnData {
+    type Error = Box<dyn Error>;
+
+    fn try_from(return_data: &RocksDBTransactionReturnData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            program_id: Pubkey::from_str(&return_data.program_id)?,
+            data: return_data.data.clone(),
+        })
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -241,35 +394,44 @@ pub struct RocksDBTransactionStatusMeta {
     pub pre_token_balances: Option<Vec<RocksDBTransactionTokenBalance>>,
     pub post_token_balances: Option<Vec<RocksDBTransactionTokenBalance>>,
     pub rewards: Option<Vec<RocksDBReward>>,
+    #[serde(default)]
+    pub loaded_addresses: RocksDBLoadedAddresses,
+    #[serde(default)]
+    pub return_data: Option<RocksDBTransactionReturnData>,
+    #[serde(default)]
+    pub compute_units_consumed: Option<u64>,
 }
 
 fn get_transaction_error(result: &Result<(), TransactionError>) -> Option<RocksDBTransactionError> {
-    if result.is_ok() {
-        return None;
-    }
+    let error = result.as_ref().err()?;
+    let error_detail = if let TransactionError::InstructionError(idx, instruction_error) = error {
+        format!(
+            "InstructionError: idx ({}), error: ({})",
+            idx, instruction_error
+        )
+    } else {
+        error.to_string()
+    };
 
-    let error = result.as_ref().err().unwrap();
     Some(RocksDBTransactionError {
         error_code: RocksDBTransactionErrorCode::from(error),
-        error_detail: {
-            if let TransactionError::InstructionError(idx, instruction_error) = error {
-                let mut error_detail = format!(
-                    "InstructionError: idx ({}), error: ({})",
-                    idx, instruction_error
-                );
-                if error_detail.len() > MAX_TRANSACTION_STATUS_LEN {
-                    error_detail = error_detail
-                        .to_string()
-                        .split_off(MAX_TRANSACTION_STATUS_LEN);
-                }
-                Some(error_detail)
-            } else {
-                None
-            }
-        },
+        error_detail: Some(truncate_error_detail(error_detail)),
+        error: Some(error.clone()),
     })
 }
 
+/// Keep the beginning of the detail, which names the failure
+fn truncate_error_detail(mut error_detail: String) -> String {
+    if error_detail.len() > MAX_TRANSACTION_STATUS_LEN {
+        let mut len = MAX_TRANSACTION_STATUS_LEN;
+        while !error_detail.is_char_boundary(len) {
+            len -= 1;
+        }
+        error_detail.truncate(len);
+    }
+    error_detail
+}
+
 impl From<&TransactionStatusMeta> for RocksDBTransactionStatusMeta {
     fn from(meta: &TransactionStatusMeta) -> Self {
         Self {
@@ -308,10 +470,73 @@ impl From<&TransactionStatusMeta> for RocksDBTransactionStatusMeta {
                 .rewards
                 .as_ref()
                 .map(|rewards| rewards.iter().map(RocksDBReward::from).collect()),
+            loaded_addresses: RocksDBLoadedAddresses::from(&meta.loaded_addresses),
+            return_data: meta
+                .return_data
+                .as_ref()
+                .map(RocksDBTransactionReturnData::from),
+            compute_units_consumed: meta.compute_units_consumed,
         }
     }
 }
 
+impl TryFrom<&RocksDBTransactionStatusMeta> for TransactionStatusMeta {
+    type Error = Box<dyn Error>;
+
+    fn try_from(meta: &RocksDBTransactionStatusMeta) -> Result<Self, Self::Error> {
+        Ok(Self {
+            status: match &meta.error {
+                Some(RocksDBTransactionError {
+                    error: Some(error), ..
+                }) => Err(error.clone()),
+                Some(_) => return Err("Transaction error detail not recorded".into()),
+                None => Ok(()),
+            },
+            fee: meta.fee as u64,
+            pre_balances: meta
+                .pre_balances
+                .iter()
+                .map(|balance| *balance as u64)
+                .collect(),
+            post_balances: meta
+                .post_balances
+                .iter()
+                .map(|balance| *balance as u64)
+                .collect(),
+            inner_instructions: meta
+                .inner_instructions
+                .as_ref()
+                .map(|instructions| {
+                    instructions
+                        .iter()
+                        .map(InnerInstructions::try_from)
+                        .collect::<Result<_, _>>()
+                })
+                .transpose()?,
+            log_messages: meta.log_messages.clone(),
+            pre_token_balances: meta
+                .pre_token_balances
+                .as_ref()
+                .map(|balances| balances.iter().map(TransactionTokenBalance::from).collect()),
+            post_token_balances: meta
+                .post_token_balances
+                .as_ref()
+                .map(|balances| balances.iter().map(TransactionTokenBalance::from).collect()),
+            rewards: meta
+                .rewards
+                .as_ref()
+                .map(|rewards| rewards.iter().map(Reward::from).collect()),
+            loaded_addresses: LoadedAddresses::try_from(&meta.loaded_addresses)?,
+            return_data: meta
+                .return_data
+                .as_ref()
+                .map(TransactionReturnData::try_from)
+                .transpose()?,
+            compute_units_consumed: meta.compute_units_consumed,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RocksDBTransactionMessageHeader {
     pub num_required_signatures: i16,
@@ -329,8 +554,33 @@ impl From<&MessageHeader> for RocksDBTransactionMessageHeader {
     }
 }
 
+impl From<&RocksDBTransactionMessageHeader> for MessageHeader {
+    fn from(header: &RocksDBTransactionMessageHeader) -> Self {
+        Self {
+            num_required_signatures: header.num_required_signatures as u8,
+            num_readonly_signed_accounts: header.num_readonly_signed_accounts as u8,
+            num_readonly_unsigned_accounts: header.num_readonly_unsigned_accounts as u8,
+        }
+    }
+}
+
+fn parse_pubkeys(keys: &[String]) -> Result<Vec<Pubkey>, Box<dyn Error>> {
+    keys.iter()
+        .map(|key| Pubkey::from_str(key).map_err(|e| e.into()))
+        .collect()
+}
+
+fn parse_instructions(
+    instructions: &[RocksDBCompiledInstruction],
+) -> Result<Vec<CompiledInstruction>, Box<dyn Error>> {
+    instructions
+        .iter()
+        .map(CompiledInstruction::try_from)
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-struct RocksDBLegacy {
+pub struct RocksDBLegacy {
     pub header: RocksDBTransactionMessageHeader,
     pub account_keys: Vec<String>,
     pub recent_blockhash: String,
@@ -356,6 +606,19 @@ impl From<&Message> for RocksDBLegacy {
     }
 }
 
+impl TryFrom<&RocksDBLegacy> for Message {
+    type Error = Box<dyn Error>;
+
+    fn try_from(message: &RocksDBLegacy) -> Result<Self, Self::Error> {
+        Ok(Self {
+            header: MessageHeader::from(&message.header),
+            account_keys: parse_pubkeys(&message.account_keys)?,
+            recent_blockhash: Hash::from_str(&message.recent_blockhash)?,
+            instructions: parse_instructions(&message.instructions)?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RocksDBTransactionMessageAddressTableLookup {
     pub account_key: String,
@@ -381,8 +644,30 @@ impl From<&MessageAddressTableLookup> for RocksDBTransactionMessageAddressTableL
     }
 }
 
+impl TryFrom<&RocksDBTransactionMessageAddressTableLookup> for MessageAddressTableLookup {
+    type Error = Box<dyn Error>;
+
+    fn try_from(
+        address_table_lookup: &RocksDBTransactionMessageAddressTableLookup,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            account_key: Pubkey::from_str(&address_table_lookup.account_key)?,
+            writable_indexes: address_table_lookup
+                .writable_indexes
+                .iter()
+                .map(|idx| *idx as u8)
+                .collect(),
+            readonly_indexes: address_table_lookup
+                .readonly_indexes
+                .iter()
+                .map(|idx| *idx as u8)
+                .collect(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-struct RocksDBV0 {
+pub struct RocksDBV0 {
     pub header: RocksDBTransactionMessageHeader,
     pub account_keys: Vec<String>,
     pub recent_blockhash: String,
@@ -414,7 +699,25 @@ impl From<&v0::Message> for RocksDBV0 {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl TryFrom<&RocksDBV0> for v0::Message {
+    type Error = Box<dyn Error>;
+
+    fn try_from(message: &RocksDBV0) -> Result<Self, Self::Error> {
+        Ok(Self {
+            header: MessageHeader::from(&message.header),
+            account_keys: parse_pubkeys(&message.account_keys)?,
+            recent_blockhash: Hash::from_str(&message.recent_blockhash)?,
+            instructions: parse_instructions(&message.instructions)?,
+            address_table_lookups: message
+                .address_table_lookups
+                .iter()
+                .map(MessageAddressTableLookup::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct RocksDBLoadedAddresses {
     pub writable: Vec<Vec<u8>>,
     pub readonly: Vec<Vec<u8>>,
@@ -437,6 +740,23 @@ impl From<&LoadedAddresses> for RocksDBLoadedAddresses {
     }
 }
 
+impl TryFrom<&RocksDBLoadedAddresses> for LoadedAddresses {
+    type Error = Box<dyn Error>;
+
+    fn try_from(loaded_addresses: &RocksDBLoadedAddresses) -> Result<Self, Self::Error> {
+        let parse = |pubkeys: &[Vec<u8>]| -> Result<Vec<Pubkey>, Box<dyn Error>> {
+            pubkeys
+                .iter()
+                .map(|pubkey| Pubkey::try_from(pubkey.as_slice()).map_err(|e| e.into()))
+                .collect()
+        };
+        Ok(Self {
+            writable: parse(&loaded_addresses.writable)?,
+            readonly: parse(&loaded_addresses.readonly)?,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RocksDBLoadedMessageV0 {
     pub message: RocksDBV0,
@@ -454,24 +774,92 @@ impl From<&v0::LoadedMessage<'_>> for RocksDBLoadedMessageV0 {
     }
 }
 
+/// An account written by a transaction
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RocksDBAccountWrite {
+    pub pubkey: String,
+    pub owner: String,
+    pub lamports: u64,
+    /// Hash of the account data after the write
+    pub data_hash: String,
+    /// Position of the write among the writes of its transaction once recorded.
+    /// The accounts-db write version is local to the node, only its order is kept
+    /// so that every node records the same pods.
+    pub write_version: u64,
+    pub txn_signature: String,
+}
+
+impl RocksDBAccountWrite {
+    pub fn from_replica_account(account: &ReplicaAccountInfoV3) -> Option<Self> {
+        let txn = account.txn?;
+        Some(Self {
+            pubkey: bs58::encode(account.pubkey).into_string(),
+            owner: bs58::encode(account.owner).into_string(),
+            lamports: account.lamports,
+            data_hash: hash(account.data).to_string(),
+            write_version: account.write_version,
+            txn_signature: txn.signature().to_string(),
+        })
+    }
+}
+
+/// The final state of every account written by the transactions, ordered by pubkey.
+/// Writes are ordered by slot, transaction index in the block, then write order.
+pub fn account_state_diff(transactions: &[TxnStoreStruct]) -> Vec<RocksDBAccountWrite> {
+    let mut state_diff: BTreeMap<&str, ((i64, i64, u64), &RocksDBAccountWrite)> = BTreeMap::new();
+    for (transaction, account_write) in transactions.iter().flat_map(|transaction| {
+        transaction
+            .account_writes
+            .iter()
+            .map(move |account_write| (transaction, account_write))
+    }) {
+        let order = (
+            transaction.slot,
+            transaction.index,
+            account_write.write_version,
+        );
+        match state_diff.entry(&account_write.pubkey) {
+            Entry::Vacant(entry) => {
+                entry.insert((order, account_write));
+            }
+            Entry::Occupied(mut entry) => {
+                if entry.get().0 <= order {
+                    entry.insert((order, account_write));
+                }
+            }
+        }
+    }
+    state_diff
+        .into_values()
+        .map(|(_, account_write)| account_write.clone())
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-enum RocksDBSanitizedMessage {
+pub enum RocksDBSanitizedMessage {
     Legacy(RocksDBLegacy),
     V0(RocksDBLoadedMessageV0),
 }
 
+/// Record of a pod transaction. The `#[serde(default)]` fields of the record
+/// types are only missing from the legacy JSON batches converted by
+/// `migrate_legacy_batches`, versioned records always hold every field.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TxnStoreStruct {
-    signature: String,
-    is_vote: bool,
-    slot: i64,
-    message_type: i64,
-    message: Option<RocksDBSanitizedMessage>,
-    message_hash: Vec<u8>,
-    meta: RocksDBTransactionStatusMeta,
-    signatures: Vec<String>,
-    write_version: i64,
-    index: i64,
+    pub signature: String,
+    pub is_vote: bool,
+    pub slot: i64,
+    pub message_type: i64,
+    pub message: Option<RocksDBSanitizedMessage>,
+    pub message_hash: Vec<u8>,
+    pub meta: RocksDBTransactionStatusMeta,
+    pub signatures: Vec<String>,
+    pub write_version: i64,
+    pub index: i64,
+    /// Accounts written by the transaction, only recorded when account diffs are enabled
+    #[serde(default)]
+    pub account_writes: Vec<RocksDBAccountWrite>,
 }
 
 impl TxnStoreStruct {
@@ -493,10 +881,14 @@ impl TxnStoreStruct {
                 pre_token_balances: None,
                 post_token_balances: None,
                 rewards: None,
+                loaded_addresses: RocksDBLoadedAddresses::default(),
+                return_data: None,
+                compute_units_consumed: None,
             },
             signatures: vec![],
             write_version: 0,
             index: 0,
+            account_writes: vec![],
         }
     }
 
@@ -523,6 +915,7 @@ impl TxnStoreStruct {
             signatures,
             write_version,
             index,
+            account_writes: vec![],
         }
     }
 
@@ -575,20 +968,30 @@ impl TxnStoreStruct {
     //     txn_data_struct
     // }
 
-    pub fn tx_batch_store(
-        rocksdb_connection: &RocksDBConnection,
-        txn_data: &ReplicaTransactionInfoV2,
+    pub fn from_replica_transaction(txn_data: &ReplicaTransactionInfoV2, slot: u64) -> Self {
+        Self::from_sanitized_transaction(
+            txn_data.transaction,
+            txn_data.transaction_status_meta,
+            slot,
+            txn_data.index,
+        )
+    }
+
+    pub fn from_sanitized_transaction(
+        transaction: &SanitizedTransaction,
+        transaction_status_meta: &TransactionStatusMeta,
         slot: u64,
-    ) -> Option<Self> {
-        let txn_data_struct = Self::from(
-            txn_data.signature.to_string(),
-            txn_data.is_vote,
+        index: usize,
+    ) -> Self {
+        Self::from(
+            transaction.signature().to_string(),
+            transaction.is_simple_vote_transaction(),
             slot as i64,
-            match txn_data.transaction.message() {
+            match transaction.message() {
                 SanitizedMessage::Legacy(_) => 0,
                 SanitizedMessage::V0(_) => 1,
             },
-            match txn_data.transaction.message() {
+            match transaction.message() {
                 SanitizedMessage::Legacy(legacy_message) => Some(RocksDBSanitizedMessage::Legacy(
                     RocksDBLegacy::from(legacy_message.message.as_ref()),
                 )),
@@ -596,32 +999,239 @@ impl TxnStoreStruct {
                     RocksDBLoadedMessageV0::from(v0_message),
                 )),
             },
-            txn_data.transaction.message_hash().as_ref().to_vec(),
-            RocksDBTransactionStatusMeta::from(txn_data.transaction_status_meta),
-            txn_data
-                .transaction
+            transaction.message_hash().as_ref().to_vec(),
+            RocksDBTransactionStatusMeta::from(transaction_status_meta),
+            transaction
                 .signatures()
                 .iter()
                 .map(|signature| signature.to_string())
                 .collect(),
             1,
-            txn_data.index as i64,
-        );
+            index as i64,
+        )
+    }
+
+    /// Sanitize a transaction read back from the ledger, its record matches the
+    /// one written when the transaction was notified, without the account writes
+    pub fn sanitize_ledger_transaction(
+        transaction: &VersionedTransactionWithStatusMeta,
+    ) -> Result<SanitizedTransaction, TransactionError> {
+        SanitizedTransaction::try_create(
+            transaction.transaction.clone(),
+            MessageHash::Compute,
+            None,
+            SimpleAddressLoader::Enabled(transaction.meta.loaded_addresses.clone()),
+        )
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.slot as u64
+    }
+
+    pub fn account_writes(&self) -> &[RocksDBAccountWrite] {
+        &self.account_writes
+    }
 
-        // println!("{}",txn_data_struct.signature);
+    /// Record the writes of the transaction, in the order of their accounts-db
+    /// write versions, which are replaced by their position
+    pub fn set_account_writes(&mut self, mut account_writes: Vec<RocksDBAccountWrite>) {
+        account_writes.sort_by_key(|account_write| account_write.write_version);
+        for (position, account_write) in account_writes.iter_mut().enumerate() {
+            account_write.write_version = position as u64;
+        }
+        self.account_writes = account_writes;
+    }
+
+    /// Serialize the transaction into the record stored in pods
+    pub fn to_record(&self) -> Vec<u8> {
+        encode_versioned(self).expect("Failed to serialize the struct to a pod record")
+    }
 
-        let root_txn_str = serde_json::to_string(&txn_data_struct)
-            .expect("Failed to serialize the struct to JSON");
+    pub fn from_record(record: &[u8]) -> Result<Self, Box<dyn Error>> {
+        decode_versioned(record)
+    }
 
-        match rocksdb_connection.save_transaction_batch(root_txn_str, txn_data_struct.slot as u64) {
-            Ok(_) => {
-                println!("Transaction saved: {:?}", txn_data_struct.signature);
+    /// Rebuild the transaction and its status meta as they were notified
+    pub fn to_confirmed_transaction(
+        &self,
+        block_time: Option<UnixTimestamp>,
+    ) -> Result<ConfirmedTransactionWithStatusMeta, Box<dyn Error>> {
+        let message = match &self.message {
+            Some(RocksDBSanitizedMessage::Legacy(message)) => {
+                VersionedMessage::Legacy(Message::try_from(message)?)
             }
-            Err(e) => {
-                println!("Error saving transaction to RocksDB: {}", e);
+            Some(RocksDBSanitizedMessage::V0(message)) => {
+                VersionedMessage::V0(v0::Message::try_from(&message.message)?)
             }
+            None => return Err("Transaction message not stored".into()),
         };
+        let signatures = self
+            .signatures
+            .iter()
+            .map(|signature| Signature::from_str(signature))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ConfirmedTransactionWithStatusMeta {
+            slot: self.slot(),
+            tx_with_meta: TransactionWithStatusMeta::Complete(VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction {
+                    signatures,
+                    message,
+                },
+                meta: TransactionStatusMeta::try_from(&self.meta)?,
+            }),
+            block_time,
+        })
+    }
+}
 
-        Some(txn_data_struct)
+#[cfg(test)]
+pub(crate) mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            instruction::InstructionError,
+            signature::{Keypair, Signer},
+            system_transaction,
+            transaction::SanitizedTransaction,
+        },
+        solana_transaction_status::UiTransactionEncoding,
+    };
+
+    #[test]
+    fn test_account_state_diff() {
+        let account_write = |pubkey: &str, lamports: u64, write_version: u64| RocksDBAccountWrite {
+            pubkey: pubkey.to_string(),
+            owner: "owner".to_string(),
+            lamports,
+            data_hash: Hash::default().to_string(),
+            write_version,
+            txn_signature: format!("txn-{pubkey}-{lamports}"),
+        };
+        let transaction = |index: i64, account_writes: Vec<RocksDBAccountWrite>| {
+            let mut transaction = TxnStoreStruct::new();
+            transaction.slot = 1;
+            transaction.index = index;
+            transaction.set_account_writes(account_writes);
+            transaction
+        };
+        // Only the order of the accounts-db write versions is recorded
+        let first = transaction(
+            0,
+            vec![account_write("b", 2, 13), account_write("a", 1, 11)],
+        );
+        assert_eq!(
+            first.account_writes(),
+            [account_write("a", 1, 0), account_write("b", 2, 1)]
+        );
+        let second = transaction(2, vec![account_write("a", 5, 4)]);
+        let record = TxnStoreStruct::from_record(&second.to_record()).unwrap();
+        assert_eq!(record.account_writes(), second.account_writes());
+        let third = transaction(1, vec![account_write("b", 0, 2)]);
+
+        assert_eq!(
+            account_state_diff(&[first, record, third]),
+            vec![account_write("a", 5, 0), account_write("b", 0, 0)]
+        );
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let from = Keypair::new();
+        let transaction =
+            system_transaction::transfer(&from, &Pubkey::new_unique(), 42, Hash::new_unique());
+        let sanitized = SanitizedTransaction::from_transaction_for_tests(transaction);
+        let token_balance = TransactionTokenBalance {
+            account_index: 1,
+            mint: Pubkey::new_unique().to_string(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: Some(12345678.901234567),
+                decimals: 9,
+                amount: "12345678901234567".to_string(),
+                ui_amount_string: "12345678.901234567".to_string(),
+            },
+            owner: Pubkey::new_unique().to_string(),
+            program_id: Pubkey::new_unique().to_string(),
+        };
+        let meta = TransactionStatusMeta {
+            status: Err(TransactionError::InstructionError(
+                0,
+                InstructionError::BorshIoError("x".repeat(2 * MAX_TRANSACTION_STATUS_LEN)),
+            )),
+            fee: 5000,
+            pre_balances: vec![u64::MAX, 0, 1],
+            post_balances: vec![u64::MAX - 5042, 42, 1],
+            inner_instructions: Some(vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(
+                        2,
+                        vec![1, 2, 3],
+                        vec![0, 1],
+                    ),
+                    stack_height: Some(2),
+                }],
+            }]),
+            log_messages: Some(vec!["Program log: transfer".to_string()]),
+            pre_token_balances: Some(vec![token_balance.clone()]),
+            post_token_balances: Some(vec![token_balance]),
+            rewards: Some(vec![]),
+            loaded_addresses: LoadedAddresses::default(),
+            return_data: Some(TransactionReturnData {
+                program_id: Pubkey::new_unique(),
+                data: vec![7; 16],
+            }),
+            compute_units_consumed: Some(150),
+        };
+        let replica = ReplicaTransactionInfoV2 {
+            signature: sanitized.signature(),
+            is_vote: false,
+            transaction: &sanitized,
+            transaction_status_meta: &meta,
+            index: 3,
+        };
+
+        let record = TxnStoreStruct::from_replica_transaction(&replica, 11).to_record();
+        let stored = TxnStoreStruct::from_record(&record).unwrap();
+        let error = stored.meta.error.as_ref().unwrap();
+        assert_eq!(
+            error.error_code,
+            RocksDBTransactionErrorCode::InstructionError
+        );
+        let error_detail = error.error_detail.as_ref().unwrap();
+        assert_eq!(error_detail.len(), MAX_TRANSACTION_STATUS_LEN);
+        assert!(error_detail.starts_with("InstructionError: idx (0)"));
+
+        let confirmed = stored
+            .to_confirmed_transaction(Some(1_700_000_000))
+            .unwrap();
+        let expected = ConfirmedTransactionWithStatusMeta {
+            slot: 11,
+            tx_with_meta: TransactionWithStatusMeta::Complete(VersionedTransactionWithStatusMeta {
+                transaction: sanitized.to_versioned_transaction(),
+                meta,
+            }),
+            block_time: Some(1_700_000_000),
+        };
+        assert_eq!(confirmed, expected);
+        assert_eq!(
+            confirmed
+                .encode(UiTransactionEncoding::Json, Some(0))
+                .unwrap(),
+            expected
+                .encode(UiTransactionEncoding::Json, Some(0))
+                .unwrap()
+        );
+
+        // The copy of the transaction kept in the ledger rebuilds the same record
+        let TransactionWithStatusMeta::Complete(ledger_transaction) = expected.tx_with_meta else {
+            unreachable!()
+        };
+        let sanitized = TxnStoreStruct::sanitize_ledger_transaction(&ledger_transaction).unwrap();
+        assert_eq!(
+            TxnStoreStruct::from_sanitized_transaction(&sanitized, &ledger_transaction.meta, 11, 3)
+                .to_record(),
+            record
+        );
     }
 }
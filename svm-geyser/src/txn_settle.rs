@@ -1,9 +1,168 @@
-use {log::*, solana_sdk::pubkey::Pubkey, std::collections::HashSet};
+//! Selection of the transactions recorded in pods.
+//!
+//! A transaction is selected when it passes the status and vote checks, is
+//! included by the legacy `mentions` list or the `include` filter, and isn't
+//! matched by the `exclude` filter. For instance everything users submit, but
+//! neither votes nor the maintenance traffic of a given program:
+//!
+//! ```json
+//! "transaction_selector": {
+//!     "mentions": ["*"],
+//!     "exclude_votes": true,
+//!     "exclude": { "programs": ["<program id>"] }
+//! }
+//! ```
+
+use {
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::{pubkey::Pubkey, transaction::SanitizedTransaction},
+    solana_transaction_status::TransactionStatusMeta,
+    std::{collections::HashSet, str::FromStr},
+};
+
+/// The `transaction_selector` section of the plugin config
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransactionSelectorConfig {
+    /// `["*"]` or `["all"]` selects all transactions, `["all_votes"]` all vote
+    /// transactions, otherwise the transactions mentioning one of the addresses
+    pub mentions: Vec<String>,
+    pub include: Option<TransactionFilterConfig>,
+    pub exclude: Option<TransactionFilterConfig>,
+    /// Drop vote transactions, whatever the other rules select
+    pub exclude_votes: bool,
+    pub status: TransactionStatusSelection,
+}
+
+/// Addresses a transaction is matched against, the filter matches when any of
+/// the lists does
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransactionFilterConfig {
+    /// Any account of the transaction, including the ones loaded from lookup tables
+    pub mentions: Vec<String>,
+    /// Accounts the transaction write locks
+    pub writable: Vec<String>,
+    pub signers: Vec<String>,
+    pub fee_payers: Vec<String>,
+    /// Programs invoked by a top level or an inner instruction
+    pub programs: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionStatusSelection {
+    #[default]
+    Any,
+    Success,
+    Failed,
+}
+
+impl TransactionStatusSelection {
+    fn matches(&self, succeeded: bool) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Success => succeeded,
+            Self::Failed => !succeeded,
+        }
+    }
+}
+
+fn parse_addresses(addresses: &[String], field: &str) -> Result<HashSet<Pubkey>, String> {
+    addresses
+        .iter()
+        .map(|address| {
+            Pubkey::from_str(address).map_err(|e| {
+                format!("Invalid address {address:?} in transaction_selector.{field}: {e}")
+            })
+        })
+        .collect()
+}
 
-pub(crate) struct SvmTxnSelector {
+struct TransactionFilter {
+    mentions: HashSet<Pubkey>,
+    writable: HashSet<Pubkey>,
+    signers: HashSet<Pubkey>,
+    fee_payers: HashSet<Pubkey>,
+    programs: HashSet<Pubkey>,
+}
+
+impl TransactionFilter {
+    fn new(config: &TransactionFilterConfig, section: &str) -> Result<Self, String> {
+        let parse = |addresses, field| parse_addresses(addresses, &format!("{section}.{field}"));
+        Ok(Self {
+            mentions: parse(&config.mentions, "mentions")?,
+            writable: parse(&config.writable, "writable")?,
+            signers: parse(&config.signers, "signers")?,
+            fee_payers: parse(&config.fee_payers, "fee_payers")?,
+            programs: parse(&config.programs, "programs")?,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.mentions.is_empty()
+            && self.writable.is_empty()
+            && self.signers.is_empty()
+            && self.fee_payers.is_empty()
+            && self.programs.is_empty()
+    }
+
+    /// Without `meta` the inner instructions aren't known yet, a program only
+    /// invoked through them counts as `unknown_inner_match`
+    fn matches(
+        &self,
+        transaction: &SanitizedTransaction,
+        meta: Option<&TransactionStatusMeta>,
+        unknown_inner_match: bool,
+    ) -> bool {
+        let message = transaction.message();
+        let account_keys = message.account_keys();
+        let is_program = |program_id_index: u8| {
+            account_keys
+                .get(usize::from(program_id_index))
+                .map_or(false, |program_id| self.programs.contains(program_id))
+        };
+
+        if self.fee_payers.contains(message.fee_payer())
+            || account_keys.iter().enumerate().any(|(index, key)| {
+                self.mentions.contains(key)
+                    || (self.writable.contains(key) && message.is_writable(index))
+                    || (self.signers.contains(key) && message.is_signer(index))
+            })
+        {
+            return true;
+        }
+        if self.programs.is_empty() {
+            return false;
+        }
+        if message
+            .instructions()
+            .iter()
+            .any(|instruction| is_program(instruction.program_id_index))
+        {
+            return true;
+        }
+        match meta {
+            Some(meta) => meta.inner_instructions.iter().flatten().any(|inner| {
+                inner
+                    .instructions
+                    .iter()
+                    .any(|inner| is_program(inner.instruction.program_id_index))
+            }),
+            None => unknown_inner_match,
+        }
+    }
+}
+
+pub struct SvmTxnSelector {
     pub mentioned_addresses: HashSet<Vec<u8>>,
     pub select_all_transactions: bool,
     pub select_all_vote_transactions: bool,
+    include: Option<TransactionFilter>,
+    exclude: Option<TransactionFilter>,
+    exclude_votes: bool,
+    status: TransactionStatusSelection,
 }
 
 #[allow(dead_code)]
@@ -13,6 +172,10 @@ impl SvmTxnSelector {
             mentioned_addresses: HashSet::default(),
             select_all_transactions: false,
             select_all_vote_transactions: false,
+            include: None,
+            exclude: None,
+            exclude_votes: false,
+            status: TransactionStatusSelection::Any,
         }
     }
 
@@ -20,62 +183,104 @@ impl SvmTxnSelector {
     /// To select all transactions use ["*"] or ["all"]
     /// To select all vote transactions, use ["all_votes"]
     /// To select transactions mentioning specific addresses use ["<pubkey1>", "<pubkey2>", ...]
-    pub fn new(mentioned_addresses: &[String]) -> Self {
-        info!(
-            "Creating SvmTxnSelector from addresses: {:?}",
-            mentioned_addresses
-        );
+    pub fn new(mentioned_addresses: &[String]) -> Result<Self, String> {
+        Self::from_config(&TransactionSelectorConfig {
+            mentions: mentioned_addresses.to_vec(),
+            ..TransactionSelectorConfig::default()
+        })
+    }
+
+    /// Create a selector from the `transaction_selector` section of the plugin config
+    pub fn from_config(config: &TransactionSelectorConfig) -> Result<Self, String> {
+        info!("Creating SvmTxnSelector from {:?}", config);
 
+        let mentioned_addresses = &config.mentions;
         let select_all_transactions = mentioned_addresses
             .iter()
             .any(|key| key == "*" || key == "all");
-        if select_all_transactions {
-            return Self {
-                mentioned_addresses: HashSet::default(),
-                select_all_transactions,
-                select_all_vote_transactions: true,
-            };
-        }
-        let select_all_vote_transactions = mentioned_addresses.iter().any(|key| key == "all_votes");
-        if select_all_vote_transactions {
-            return Self {
-                mentioned_addresses: HashSet::default(),
-                select_all_transactions,
-                select_all_vote_transactions: true,
-            };
-        }
-
-        let mentioned_addresses = mentioned_addresses
-            .iter()
-            .map(|key| bs58::decode(key).into_vec().unwrap())
-            .collect();
-
-        Self {
+        let select_all_vote_transactions =
+            select_all_transactions || mentioned_addresses.iter().any(|key| key == "all_votes");
+        let mentioned_addresses = if select_all_vote_transactions {
+            HashSet::default()
+        } else {
+            parse_addresses(mentioned_addresses, "mentions")?
+                .into_iter()
+                .map(|key| key.to_bytes().to_vec())
+                .collect()
+        };
+
+        let include = config
+            .include
+            .as_ref()
+            .map(|include| TransactionFilter::new(include, "include"))
+            .transpose()?
+            .filter(|include| !include.is_empty());
+        let exclude = config
+            .exclude
+            .as_ref()
+            .map(|exclude| TransactionFilter::new(exclude, "exclude"))
+            .transpose()?
+            .filter(|exclude| !exclude.is_empty());
+
+        Ok(Self {
             mentioned_addresses,
-            select_all_transactions: false,
-            select_all_vote_transactions: false,
-        }
+            select_all_transactions,
+            select_all_vote_transactions,
+            include,
+            exclude,
+            exclude_votes: config.exclude_votes,
+            status: config.status,
+        })
     }
 
-    /// Check if a transaction is of interest.
+    /// Check if an executed transaction is of interest.
     pub fn is_transaction_selected(
         &self,
-        is_vote: bool,
-        mentioned_addresses: Box<dyn Iterator<Item=&Pubkey> + '_>,
+        transaction: &SanitizedTransaction,
+        meta: &TransactionStatusMeta,
+    ) -> bool {
+        self.select(transaction, Some(meta))
+    }
+
+    /// Check if a transaction which is still executing may turn out to be of
+    /// interest. Rules depending on the execution outcome are assumed to pass.
+    pub fn may_select_transaction(&self, transaction: &SanitizedTransaction) -> bool {
+        self.select(transaction, None)
+    }
+
+    fn select(
+        &self,
+        transaction: &SanitizedTransaction,
+        meta: Option<&TransactionStatusMeta>,
     ) -> bool {
         if !self.is_enabled() {
             return false;
         }
 
-        if self.select_all_transactions || (self.select_all_vote_transactions && is_vote) {
-            return true;
+        let is_vote = transaction.is_simple_vote_transaction();
+        if is_vote && self.exclude_votes {
+            return false;
         }
-        for address in mentioned_addresses {
-            if self.mentioned_addresses.contains(address.as_ref()) {
-                return true;
-            }
+        if meta.map_or(false, |meta| !self.status.matches(meta.status.is_ok())) {
+            return false;
         }
-        false
+
+        let included = self.select_all_transactions
+            || (self.select_all_vote_transactions && is_vote)
+            || transaction
+                .message()
+                .account_keys()
+                .iter()
+                .any(|address| self.mentioned_addresses.contains(address.as_ref()))
+            || self
+                .include
+                .as_ref()
+                .map_or(false, |include| include.matches(transaction, meta, true));
+        included
+            && !self
+                .exclude
+                .as_ref()
+                .map_or(false, |exclude| exclude.matches(transaction, meta, false))
     }
 
     /// Check if any transaction is of interest at all
@@ -83,110 +288,232 @@ impl SvmTxnSelector {
         self.select_all_transactions
             || self.select_all_vote_transactions
             || !self.mentioned_addresses.is_empty()
+            || self.include.is_some()
     }
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use super::*;
+    use {
+        super::*,
+        solana_sdk::{
+            instruction::{AccountMeta, CompiledInstruction, Instruction},
+            message::{Message, SimpleAddressLoader},
+            transaction::{MessageHash, Transaction, TransactionError},
+        },
+        solana_transaction_status::{InnerInstruction, InnerInstructions},
+    };
+
+    fn transaction(
+        payer: &Pubkey,
+        program_id: Pubkey,
+        accounts: Vec<AccountMeta>,
+        is_vote: bool,
+    ) -> SanitizedTransaction {
+        let message = Message::new(
+            &[Instruction::new_with_bytes(program_id, &[], accounts)],
+            Some(payer),
+        );
+        SanitizedTransaction::try_create(
+            Transaction::new_unsigned(message).into(),
+            MessageHash::Compute,
+            Some(is_vote),
+            SimpleAddressLoader::Disabled,
+        )
+        .unwrap()
+    }
+
+    fn mentioning(addresses: &[Pubkey]) -> SanitizedTransaction {
+        let accounts = addresses
+            .iter()
+            .map(|address| AccountMeta::new_readonly(*address, false))
+            .collect();
+        transaction(&Pubkey::new_unique(), Pubkey::new_unique(), accounts, false)
+    }
+
+    fn vote_mentioning(addresses: &[Pubkey]) -> SanitizedTransaction {
+        let accounts = addresses
+            .iter()
+            .map(|address| AccountMeta::new_readonly(*address, false))
+            .collect();
+        transaction(&Pubkey::new_unique(), Pubkey::new_unique(), accounts, true)
+    }
+
+    fn selector_from(config: &str) -> SvmTxnSelector {
+        SvmTxnSelector::from_config(&serde_json::from_str(config).unwrap()).unwrap()
+    }
 
     #[test]
     fn test_select_transaction() {
         let pubkey1 = Pubkey::new_unique();
         let pubkey2 = Pubkey::new_unique();
+        let meta = TransactionStatusMeta::default();
 
-        let selector = SvmTxnSelector::new(&[pubkey1.to_string()]);
+        let selector = SvmTxnSelector::new(&[pubkey1.to_string()]).unwrap();
 
         assert!(selector.is_enabled());
 
-        let addresses = [pubkey1];
-
-        assert!(selector.is_transaction_selected(false, Box::new(addresses.iter())));
-
-        let addresses = [pubkey2];
-        assert!(!selector.is_transaction_selected(false, Box::new(addresses.iter())));
-
-        let addresses = [pubkey1, pubkey2];
-        assert!(selector.is_transaction_selected(false, Box::new(addresses.iter())));
+        assert!(selector.is_transaction_selected(&mentioning(&[pubkey1]), &meta));
+        assert!(!selector.is_transaction_selected(&mentioning(&[pubkey2]), &meta));
+        assert!(selector.is_transaction_selected(&mentioning(&[pubkey1, pubkey2]), &meta));
     }
 
     #[test]
     fn test_select_all_transaction_using_wildcard() {
         let pubkey1 = Pubkey::new_unique();
         let pubkey2 = Pubkey::new_unique();
+        let meta = TransactionStatusMeta::default();
 
-        let selector = SvmTxnSelector::new(&["*".to_string()]);
+        let selector = SvmTxnSelector::new(&["*".to_string()]).unwrap();
 
         assert!(selector.is_enabled());
 
-        let addresses = [pubkey1];
-
-        assert!(selector.is_transaction_selected(false, Box::new(addresses.iter())));
-
-        let addresses = [pubkey2];
-        assert!(selector.is_transaction_selected(false, Box::new(addresses.iter())));
-
-        let addresses = [pubkey1, pubkey2];
-        assert!(selector.is_transaction_selected(false, Box::new(addresses.iter())));
+        assert!(selector.is_transaction_selected(&mentioning(&[pubkey1]), &meta));
+        assert!(selector.is_transaction_selected(&mentioning(&[pubkey2]), &meta));
+        assert!(selector.is_transaction_selected(&vote_mentioning(&[pubkey1, pubkey2]), &meta));
     }
 
     #[test]
     fn test_select_all_transaction_all() {
         let pubkey1 = Pubkey::new_unique();
         let pubkey2 = Pubkey::new_unique();
+        let meta = TransactionStatusMeta::default();
 
-        let selector = SvmTxnSelector::new(&["all".to_string()]);
+        let selector = SvmTxnSelector::new(&["all".to_string()]).unwrap();
 
         assert!(selector.is_enabled());
 
-        let addresses = [pubkey1];
-
-        assert!(selector.is_transaction_selected(false, Box::new(addresses.iter())));
-
-        let addresses = [pubkey2];
-        assert!(selector.is_transaction_selected(false, Box::new(addresses.iter())));
-
-        let addresses = [pubkey1, pubkey2];
-        assert!(selector.is_transaction_selected(false, Box::new(addresses.iter())));
+        assert!(selector.is_transaction_selected(&mentioning(&[pubkey1]), &meta));
+        assert!(selector.is_transaction_selected(&mentioning(&[pubkey2]), &meta));
+        assert!(selector.is_transaction_selected(&mentioning(&[pubkey1, pubkey2]), &meta));
     }
 
     #[test]
     fn test_select_all_vote_transaction() {
         let pubkey1 = Pubkey::new_unique();
         let pubkey2 = Pubkey::new_unique();
+        let meta = TransactionStatusMeta::default();
 
-        let selector = SvmTxnSelector::new(&["all_votes".to_string()]);
+        let selector = SvmTxnSelector::new(&["all_votes".to_string()]).unwrap();
 
         assert!(selector.is_enabled());
 
-        let addresses = [pubkey1];
-
-        assert!(!selector.is_transaction_selected(false, Box::new(addresses.iter())));
-
-        let addresses = [pubkey2];
-        assert!(selector.is_transaction_selected(true, Box::new(addresses.iter())));
-
-        let addresses = [pubkey1, pubkey2];
-        assert!(selector.is_transaction_selected(true, Box::new(addresses.iter())));
+        assert!(!selector.is_transaction_selected(&mentioning(&[pubkey1]), &meta));
+        assert!(selector.is_transaction_selected(&vote_mentioning(&[pubkey2]), &meta));
+        assert!(selector.is_transaction_selected(&vote_mentioning(&[pubkey1, pubkey2]), &meta));
     }
 
     #[test]
     fn test_select_no_transaction() {
         let pubkey1 = Pubkey::new_unique();
         let pubkey2 = Pubkey::new_unique();
+        let meta = TransactionStatusMeta::default();
 
-        let selector = SvmTxnSelector::new(&[]);
+        let selector = SvmTxnSelector::new(&[]).unwrap();
 
         assert!(!selector.is_enabled());
 
-        let addresses = [pubkey1];
-
-        assert!(!selector.is_transaction_selected(false, Box::new(addresses.iter())));
+        assert!(!selector.is_transaction_selected(&mentioning(&[pubkey1]), &meta));
+        assert!(!selector.is_transaction_selected(&vote_mentioning(&[pubkey2]), &meta));
+        assert!(!selector.is_transaction_selected(&vote_mentioning(&[pubkey1, pubkey2]), &meta));
+    }
 
-        let addresses = [pubkey2];
-        assert!(!selector.is_transaction_selected(true, Box::new(addresses.iter())));
+    #[test]
+    fn test_invalid_selector_config() {
+        assert!(SvmTxnSelector::new(&["not-an-address".to_string()]).is_err());
+        let config = TransactionSelectorConfig {
+            exclude: Some(TransactionFilterConfig {
+                programs: vec![
+                    "11111111111111111111111111111111".to_string(),
+                    "0".to_string(),
+                ],
+                ..TransactionFilterConfig::default()
+            }),
+            ..TransactionSelectorConfig::default()
+        };
+        assert!(SvmTxnSelector::from_config(&config).is_err());
+    }
 
-        let addresses = [pubkey1, pubkey2];
-        assert!(!selector.is_transaction_selected(true, Box::new(addresses.iter())));
+    #[test]
+    fn test_select_with_filters() {
+        let payer = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let maintenance_program_id = Pubkey::new_unique();
+        let writable = Pubkey::new_unique();
+        let readonly = Pubkey::new_unique();
+        let user_transaction = transaction(
+            &payer,
+            program_id,
+            vec![
+                AccountMeta::new(writable, false),
+                AccountMeta::new_readonly(readonly, false),
+                AccountMeta::new_readonly(maintenance_program_id, false),
+            ],
+            false,
+        );
+        let succeeded = TransactionStatusMeta::default();
+        let failed = TransactionStatusMeta {
+            status: Err(TransactionError::AccountNotFound),
+            ..TransactionStatusMeta::default()
+        };
+        // The maintenance program is only invoked through a CPI
+        let maintenance_program_index = user_transaction
+            .message()
+            .account_keys()
+            .iter()
+            .position(|key| key == &maintenance_program_id)
+            .unwrap() as u8;
+        let invoking_maintenance_program = TransactionStatusMeta {
+            inner_instructions: Some(vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(
+                        maintenance_program_index,
+                        vec![],
+                        vec![],
+                    ),
+                    stack_height: Some(2),
+                }],
+            }]),
+            ..TransactionStatusMeta::default()
+        };
+
+        // Everything users submit, no votes and no maintenance traffic
+        let selector = selector_from(&format!(
+            "{{\"mentions\": [\"*\"], \"exclude_votes\": true, \
+              \"exclude\": {{\"programs\": [\"{maintenance_program_id}\"]}}}}"
+        ));
+        assert!(selector.is_transaction_selected(&user_transaction, &succeeded));
+        assert!(!selector.is_transaction_selected(&vote_mentioning(&[]), &succeeded));
+        assert!(!selector.is_transaction_selected(&user_transaction, &invoking_maintenance_program));
+        // The outcome isn't known before execution, exclusions only apply once it is
+        assert!(selector.may_select_transaction(&user_transaction));
+
+        let selector = selector_from(&format!(
+            "{{\"include\": {{\"writable\": [\"{writable}\", \"{readonly}\"]}}, \
+              \"status\": \"success\"}}"
+        ));
+        assert!(selector.is_transaction_selected(&user_transaction, &succeeded));
+        assert!(!selector.is_transaction_selected(&user_transaction, &failed));
+        assert!(!selector.is_transaction_selected(&mentioning(&[readonly]), &succeeded));
+
+        let selector = selector_from(&format!(
+            "{{\"include\": {{\"fee_payers\": [\"{payer}\"]}}, \"status\": \"failed\"}}"
+        ));
+        assert!(!selector.is_transaction_selected(&user_transaction, &succeeded));
+        assert!(selector.is_transaction_selected(&user_transaction, &failed));
+
+        let selector = selector_from(&format!(
+            "{{\"include\": {{\"signers\": [\"{payer}\", \"{writable}\"]}}}}"
+        ));
+        assert!(selector.is_transaction_selected(&user_transaction, &succeeded));
+        assert!(!selector.is_transaction_selected(&mentioning(&[writable]), &succeeded));
+
+        let selector = selector_from(&format!(
+            "{{\"include\": {{\"programs\": [\"{maintenance_program_id}\"]}}}}"
+        ));
+        assert!(!selector.is_transaction_selected(&user_transaction, &succeeded));
+        assert!(selector.is_transaction_selected(&user_transaction, &invoking_maintenance_program));
+        assert!(selector.may_select_transaction(&user_transaction));
     }
 }
@@ -0,0 +1,141 @@
+//! Two validators loading the plugin with the same config must write
+//! byte-identical pods.
+
+use {
+    serde_json::json,
+    serial_test::serial,
+    solana_core::validator::ValidatorConfig,
+    solana_local_cluster::{
+        integration_tests::{DEFAULT_CLUSTER_LAMPORTS, DEFAULT_NODE_STAKE},
+        local_cluster::{ClusterConfig, LocalCluster},
+    },
+    solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey},
+    solana_streamer::socket::SocketAddrSpace,
+    std::{
+        env::consts::{DLL_PREFIX, DLL_SUFFIX},
+        fs,
+        path::{Path, PathBuf},
+        thread::sleep,
+        time::{Duration, Instant},
+    },
+    svm_geyser::{
+        geyser_plugin::POD_STORE_DIR, pod::encode_versioned, rocksdb_client::RocksDBConnection,
+    },
+    tempfile::TempDir,
+};
+
+const NUM_NODES: usize = 2;
+// * Pods compared across the nodes, vote transactions fill them quickly
+const NUM_PODS: u64 = 8;
+const POD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The plugin library, built next to the test executable
+fn plugin_library_path() -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.join(format!("{DLL_PREFIX}svm_geyser{DLL_SUFFIX}"))
+}
+
+fn write_plugin_config(dir: &Path) -> PathBuf {
+    let config_file = dir.join("svm_geyser.json");
+    let config = json!({
+        "libpath": plugin_library_path(),
+        "path": dir,
+        "deterministic_pods": true,
+        "account_diffs": true,
+        "transaction_selector": { "mentions": ["*"] },
+        "pod_sealing": { "max_transactions": 4, "seal_on_root": true },
+    });
+    fs::write(&config_file, config.to_string()).unwrap();
+    config_file
+}
+
+/// Wait until the first pods of a node are sealed and their headers complete
+fn wait_for_pods(pod_store: &RocksDBConnection) {
+    let start = Instant::now();
+    loop {
+        pod_store.try_catch_up_with_primary().unwrap();
+        let latest_pod_number = pod_store.get_latest_batch_number().unwrap();
+        if latest_pod_number >= NUM_PODS
+            && (1..=NUM_PODS).all(|pod_number| {
+                pod_store
+                    .get_pod_header(pod_number)
+                    .unwrap()
+                    .map_or(false, |header| header.is_complete())
+            })
+        {
+            return;
+        }
+        assert!(
+            start.elapsed() < POD_TIMEOUT,
+            "only {latest_pod_number} pods sealed"
+        );
+        sleep(Duration::from_secs(1));
+    }
+}
+
+#[test]
+#[serial]
+fn test_pods_are_identical_across_nodes() {
+    solana_logger::setup_with_default("error");
+    let plugin_dirs: Vec<_> = (0..NUM_NODES).map(|_| TempDir::new().unwrap()).collect();
+    let validator_configs = plugin_dirs
+        .iter()
+        .map(|plugin_dir| ValidatorConfig {
+            on_start_geyser_plugin_config_files: Some(vec![write_plugin_config(plugin_dir.path())]),
+            ..ValidatorConfig::default_for_test()
+        })
+        .collect();
+    let mut cluster_config = ClusterConfig {
+        validator_configs,
+        node_stakes: vec![DEFAULT_NODE_STAKE; NUM_NODES],
+        cluster_lamports: DEFAULT_CLUSTER_LAMPORTS,
+        ..ClusterConfig::default()
+    };
+    let mut cluster = LocalCluster::new(&mut cluster_config, SocketAddrSpace::Unspecified);
+    for _ in 0..4 {
+        cluster.transfer(
+            &cluster.funding_keypair,
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL,
+        );
+    }
+
+    let secondary_dir = TempDir::new().unwrap();
+    let pod_stores: Vec<_> = plugin_dirs
+        .iter()
+        .enumerate()
+        .map(|(node, plugin_dir)| {
+            let pod_store = RocksDBConnection::open_secondary(
+                &plugin_dir.path().join(POD_STORE_DIR),
+                &secondary_dir.path().join(node.to_string()),
+            )
+            .unwrap();
+            wait_for_pods(&pod_store);
+            pod_store
+        })
+        .collect();
+
+    for pod_number in 1..=NUM_PODS {
+        let pods: Vec<_> = pod_stores
+            .iter()
+            .map(|pod_store| {
+                let header = pod_store.get_pod_header(pod_number).unwrap().unwrap();
+                (
+                    encode_versioned(&header).unwrap(),
+                    pod_store.get_pod_records(pod_number).unwrap(),
+                )
+            })
+            .collect();
+        assert!(
+            pods.windows(2).all(|pods| pods[0] == pods[1]),
+            "pod {pod_number} differs across the nodes"
+        );
+    }
+
+    drop(pod_stores);
+    cluster.exit();
+}
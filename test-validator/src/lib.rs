@@ -16,7 +16,8 @@ use {
         validator::{Validator, ValidatorConfig, ValidatorStartProgress},
     },
     solana_geyser_plugin_manager::{
-        geyser_plugin_manager::GeyserPluginManager, GeyserPluginManagerRequest,
+        geyser_plugin_manager::{GeyserPluginManager, InProcessGeyserPlugin},
+        GeyserPluginManagerRequest,
     },
     solana_gossip::{
         cluster_info::{ClusterInfo, Node},
@@ -69,6 +70,7 @@ use {
         sync::{Arc, RwLock},
         time::Duration,
     },
+    svm_geyser::geyser_plugin::{GeyserPluginRocksDB, POD_STORE_DIR},
     tokio::time::sleep,
 };
 
@@ -140,6 +142,8 @@ pub struct TestValidatorGenesis {
     pub max_ledger_shreds: Option<u64>,
     pub max_genesis_archive_unpacked_size: Option<u64>,
     pub geyser_plugin_config_files: Option<Vec<PathBuf>>,
+    in_process_geyser_plugins: Vec<InProcessGeyserPlugin>,
+    pod_store_config: Option<serde_json::Value>,
     deactivate_feature_set: HashSet<Pubkey>,
     compute_unit_limit: Option<u64>,
     pub log_messages_bytes_limit: Option<usize>,
@@ -174,6 +178,8 @@ impl Default for TestValidatorGenesis {
             max_ledger_shreds: Option::<u64>::default(),
             max_genesis_archive_unpacked_size: Option::<u64>::default(),
             geyser_plugin_config_files: Option::<Vec<PathBuf>>::default(),
+            in_process_geyser_plugins: Vec::<InProcessGeyserPlugin>::default(),
+            pod_store_config: Option::<serde_json::Value>::default(),
             deactivate_feature_set: HashSet::<Pubkey>::default(),
             compute_unit_limit: Option::<u64>::default(),
             log_messages_bytes_limit: Option::<usize>::default(),
@@ -591,6 +597,21 @@ impl TestValidatorGenesis {
         self
     }
 
+    /// Load a geyser plugin linked into the test, without going through a plugin library
+    pub fn add_geyser_plugin(&mut self, plugin: InProcessGeyserPlugin) -> &mut Self {
+        self.in_process_geyser_plugins.push(plugin);
+        self
+    }
+
+    /// Write pods with the `GeyserPluginRocksDB` plugin to a store in the ledger directory,
+    /// see [`TestValidator::pod_store_path`]. `config` holds the plugin config entries other
+    /// than `path`. Unless configured, every transaction is selected and the open pod is
+    /// sealed on every root.
+    pub fn add_pod_store(&mut self, config: serde_json::Value) -> &mut Self {
+        self.pod_store_config = Some(config);
+        self
+    }
+
     /// Start a test validator with the address of the mint account that will receive tokens
     /// created at genesis.
     ///
@@ -680,6 +701,7 @@ impl TestValidatorGenesis {
 pub struct TestValidator {
     ledger_path: PathBuf,
     preserve_ledger: bool,
+    pod_store_path: Option<PathBuf>,
     rpc_pubsub_url: String,
     rpc_url: String,
     tpu: SocketAddr,
@@ -929,6 +951,35 @@ impl TestValidator {
         Ok(ledger_path)
     }
 
+    /// Write the config of the pod store plugin to the ledger directory.
+    /// Returns the plugin and the path of its pod store.
+    fn pod_store_plugin(
+        pod_store_config: &serde_json::Value,
+        ledger_path: &Path,
+    ) -> Result<(InProcessGeyserPlugin, PathBuf), Box<dyn std::error::Error>> {
+        let mut plugin_config = pod_store_config
+            .as_object()
+            .cloned()
+            .ok_or("The pod store config must be a JSON object")?;
+        // The RPC service reads the pod store of the ledger directory
+        plugin_config.insert("path".to_string(), serde_json::json!(ledger_path));
+        plugin_config
+            .entry("transaction_selector")
+            .or_insert_with(|| serde_json::json!({ "mentions": ["*"] }));
+        plugin_config
+            .entry("pod_sealing")
+            .or_insert_with(|| serde_json::json!({ "seal_on_root": true }));
+        let config_file = ledger_path.join("svm_geyser.json");
+        fs::write(
+            &config_file,
+            serde_json::Value::Object(plugin_config).to_string(),
+        )?;
+
+        let plugin =
+            InProcessGeyserPlugin::new(|| Box::new(GeyserPluginRocksDB::new()), config_file);
+        Ok((plugin, ledger_path.join(POD_STORE_DIR)))
+    }
+
     /// Starts a TestValidator at the provided ledger directory
     fn start(
         mint_address: Pubkey,
@@ -939,6 +990,17 @@ impl TestValidator {
         let preserve_ledger = config.ledger_path.is_some();
         let ledger_path = TestValidator::initialize_ledger(mint_address, config)?;
 
+        let mut in_process_geyser_plugins = config.in_process_geyser_plugins.clone();
+        let pod_store_path = match &config.pod_store_config {
+            Some(pod_store_config) => {
+                let (plugin, pod_store_path) =
+                    TestValidator::pod_store_plugin(pod_store_config, &ledger_path)?;
+                in_process_geyser_plugins.push(plugin);
+                Some(pod_store_path)
+            }
+            None => None,
+        };
+
         let validator_identity =
             read_keypair_file(ledger_path.join("validator-keypair.json").to_str().unwrap())?;
         let validator_vote_account = read_keypair_file(
@@ -997,6 +1059,7 @@ impl TestValidator {
 
         let mut validator_config = ValidatorConfig {
             on_start_geyser_plugin_config_files: config.geyser_plugin_config_files.clone(),
+            on_start_in_process_geyser_plugins: in_process_geyser_plugins,
             rpc_addrs: Some((
                 SocketAddr::new(
                     IpAddr::V4(Ipv4Addr::UNSPECIFIED),
@@ -1065,6 +1128,7 @@ impl TestValidator {
         let test_validator = TestValidator {
             ledger_path,
             preserve_ledger,
+            pod_store_path,
             rpc_pubsub_url,
             rpc_url,
             tpu,
@@ -1148,6 +1212,12 @@ impl TestValidator {
         self.vote_account_address
     }
 
+    /// Return the pod store written by the validator, if it was started with one. The store is
+    /// held open by the validator, open it as a secondary instance to read it.
+    pub fn pod_store_path(&self) -> Option<&Path> {
+        self.pod_store_path.as_deref()
+    }
+
     /// Return an RpcClient for the validator.  As a convenience, also return a recent blockhash and
     /// associated fee calculator
     #[deprecated(since = "1.9.0", note = "Please use `get_rpc_client` instead")]
@@ -1213,7 +1283,7 @@ impl Drop for TestValidator {
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use {super::*, solana_sdk::system_transaction, svm_geyser::rocksdb_client::RocksDBConnection};
 
     #[test]
     fn get_health() {
@@ -1231,6 +1301,61 @@ mod test {
         rpc_client.get_health().await.expect("health");
     }
 
+    #[test]
+    fn test_pod_store() {
+        let (test_validator, payer) = TestValidatorGenesis::default()
+            .add_pod_store(serde_json::json!({}))
+            .start();
+        let rpc_client = test_validator.get_rpc_client();
+        let blockhash = rpc_client.get_latest_blockhash().unwrap();
+        let transaction =
+            system_transaction::transfer(&payer, &Pubkey::new_unique(), 1_000_000, blockhash);
+        let signature = rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .unwrap();
+
+        let start = std::time::Instant::now();
+        let signature_pod = loop {
+            if let Some(signature_pod) = rpc_client.get_signature_pod(&signature).unwrap() {
+                if signature_pod.sealed {
+                    break signature_pod;
+                }
+            }
+            assert!(start.elapsed() < Duration::from_secs(60));
+            std::thread::sleep(Duration::from_millis(DEFAULT_MS_PER_SLOT));
+        };
+
+        let pod = rpc_client.get_pod(signature_pod.pod_number).unwrap();
+        assert_eq!(pod.pod_header.pod_number, signature_pod.pod_number);
+        let pod_transaction = &pod.pod_data[signature_pod.index as usize];
+        assert_eq!(pod_transaction.slot, signature_pod.slot);
+        assert_eq!(
+            pod_transaction
+                .transaction
+                .transaction
+                .decode()
+                .unwrap()
+                .signatures[0],
+            signature
+        );
+
+        let proof = rpc_client
+            .get_pod_transaction_proof(&signature)
+            .unwrap()
+            .unwrap();
+        assert_eq!(proof.pod_number, signature_pod.pod_number);
+        assert_eq!(proof.transactions_root, pod.pod_header.transactions_root);
+
+        // The RPC service and the test read the same store
+        let pod_store = RocksDBConnection::open_secondary(
+            test_validator.pod_store_path().unwrap(),
+            &test_validator.ledger_path.join("pods_secondary"),
+        )
+        .unwrap();
+        let records = pod_store.get_pod_records(signature_pod.pod_number).unwrap();
+        assert_eq!(records.len(), pod.pod_data.len());
+    }
+
     #[tokio::test]
     #[should_panic]
     async fn document_tokio_panic() {
@@ -556,7 +556,7 @@ mod tests {
             ));
         // make sure this tx is really a good one to execute.
         assert_matches!(
-            bank.simulate_transaction_unchecked(good_tx_after_bad_tx, false)
+            bank.simulate_transaction_unchecked(good_tx_after_bad_tx, false, false, None)
                 .result,
             Ok(_)
         );
@@ -35,6 +35,7 @@ use {
         thread::{self, Builder},
         time::{Duration, SystemTime},
     },
+    svm_geyser::{geyser_plugin::GeyserPluginRocksDB, pod_retention::write_settled_pod_file},
 };
 
 #[derive(Clone)]
@@ -48,6 +49,7 @@ pub struct AdminRpcRequestMetadata {
     pub staked_nodes_overrides: Arc<RwLock<HashMap<Pubkey, u64>>>,
     pub post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
     pub rpc_to_plugin_manager_sender: Option<Sender<GeyserPluginManagerRequest>>,
+    pub pod_settled_marker_dirs: Vec<PathBuf>,
 }
 
 impl Metadata for AdminRpcRequestMetadata {}
@@ -205,6 +207,9 @@ pub trait AdminRpc {
     #[rpc(meta, name = "setStakedNodesOverrides")]
     fn set_staked_nodes_overrides(&self, meta: Self::Metadata, path: String) -> Result<()>;
 
+    #[rpc(meta, name = "settlePods")]
+    fn settle_pods(&self, meta: Self::Metadata, pod_number: u64) -> Result<()>;
+
     #[rpc(meta, name = "contactInfo")]
     fn contact_info(&self, meta: Self::Metadata) -> Result<AdminRpcContactInfo>;
 
@@ -493,6 +498,24 @@ impl AdminRpc for AdminRpcImpl {
         Ok(())
     }
 
+    fn settle_pods(&self, meta: Self::Metadata, pod_number: u64) -> Result<()> {
+        debug!("settle_pods admin rpc request received");
+
+        if meta.pod_settled_marker_dirs.is_empty() {
+            return Err(jsonrpc_core::error::Error::invalid_params(
+                "No svm geyser plugin was loaded on start",
+            ));
+        }
+        for dir in &meta.pod_settled_marker_dirs {
+            write_settled_pod_file(dir, pod_number).map_err(|err| {
+                error!("Failed to mark pod {pod_number} as settled in {dir:?}: {err}");
+                jsonrpc_core::error::Error::internal_error()
+            })?;
+        }
+        info!("Pods up to {pod_number} are settled");
+        Ok(())
+    }
+
     fn contact_info(&self, meta: Self::Metadata) -> Result<AdminRpcContactInfo> {
         meta.with_post_init(|post_init| Ok(post_init.cluster_info.my_contact_info().into()))
     }
@@ -853,6 +876,17 @@ pub fn load_staked_nodes_overrides(
     }
 }
 
+/// Directories holding the settled marker of the svm geyser plugins among
+/// `geyser_plugin_config_files`, other plugins are skipped
+pub fn pod_settled_marker_dirs(geyser_plugin_config_files: &[PathBuf]) -> Vec<PathBuf> {
+    geyser_plugin_config_files
+        .iter()
+        .filter_map(|config_file| {
+            GeyserPluginRocksDB::load_settled_marker_dir(config_file.to_str()?).ok()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -938,6 +972,7 @@ mod tests {
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
+                pod_settled_marker_dirs: Vec::new(),
             };
             let mut io = MetaIoHandler::default();
             io.extend_with(AdminRpcImpl.to_delegate());
@@ -967,6 +1002,30 @@ mod tests {
         (BankForks::new_rw_arc(bank), Arc::new(voting_keypair))
     }
 
+    #[test]
+    fn test_settle_pods() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let RpcHandler { io, mut meta, .. } = rpc;
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"settlePods","params":[5]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"].is_object());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        meta.pod_settled_marker_dirs = vec![temp_dir.path().to_path_buf()];
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"], Value::Null);
+        assert!(result["error"].is_null());
+        assert_eq!(
+            svm_geyser::pod_retention::read_settled_pod_file(temp_dir.path()).unwrap(),
+            Some(5)
+        );
+    }
+
     #[test]
     fn test_secondary_index_key_sizes() {
         for secondary_index_enabled in [true, false] {
@@ -405,6 +405,14 @@ fn main() {
             post_init: admin_service_post_init,
             tower_storage: tower_storage.clone(),
             rpc_to_plugin_manager_sender,
+            pod_settled_marker_dirs: matches
+                .values_of("geyser_plugin_config")
+                .map(|config_files| {
+                    admin_rpc_service::pod_settled_marker_dirs(
+                        &config_files.map(PathBuf::from).collect::<Vec<_>>(),
+                    )
+                })
+                .unwrap_or_default(),
         },
     );
     let dashboard = if output == Output::Dashboard {
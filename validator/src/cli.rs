@@ -1668,6 +1668,28 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 )
                 .after_help("Note: the new filter only applies to the currently running validator instance")
         )
+        .subcommand(
+            SubCommand::with_name("settle-pods")
+                .about("Mark the pods of the svm geyser plugin as settled, up to a pod number")
+                .arg(
+                    Arg::with_name("pod_number")
+                        .value_name("POD_NUMBER")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_parsable::<u64>)
+                        .help("Last settled pod"),
+                )
+                .arg(
+                    Arg::with_name("pod_store_path")
+                        .long("pod-store-path")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .help("The `path` of the plugin config \
+                               [default: the `path` of the plugin loaded by the running validator]"),
+                )
+                .after_help("Note: settled pods are pruned according to the `pod_retention` \
+                         config of the plugin, the marker never moves back")
+        )
         .subcommand(
             SubCommand::with_name("staked-nodes-overrides")
                 .about("Overrides stakes of specific node identities.")
@@ -81,6 +81,7 @@ use {
         sync::{Arc, RwLock},
         time::{Duration, SystemTime},
     },
+    svm_geyser::pod_retention::write_settled_pod_file,
 };
 
 #[cfg(not(target_env = "msvc"))]
@@ -682,6 +683,27 @@ pub fn main() {
             monitor_validator(&ledger_path);
             return;
         }
+        ("settle-pods", Some(subcommand_matches)) => {
+            let pod_number = value_t_or_exit!(subcommand_matches, "pod_number", u64);
+            if let Ok(pod_store_path) = value_t!(subcommand_matches, "pod_store_path", String) {
+                write_settled_pod_file(Path::new(&pod_store_path), pod_number).unwrap_or_else(
+                    |err| {
+                        println!("Unable to mark pod {pod_number} as settled: {err}");
+                        exit(1);
+                    },
+                );
+            } else {
+                let admin_client = admin_rpc_service::connect(&ledger_path);
+                admin_rpc_service::runtime()
+                    .block_on(async move { admin_client.await?.settle_pods(pod_number).await })
+                    .unwrap_or_else(|err| {
+                        println!("Unable to mark pod {pod_number} as settled: {err}");
+                        exit(1);
+                    });
+            }
+            println!("Pods up to {pod_number} are settled");
+            return;
+        }
         ("staked-nodes-overrides", Some(subcommand_matches)) => {
             if !subcommand_matches.is_present("path") {
                 println!(
@@ -1237,6 +1259,10 @@ pub fn main() {
         None
     };
     let starting_with_geyser_plugins: bool = on_start_geyser_plugin_config_files.is_some();
+    let pod_settled_marker_dirs = on_start_geyser_plugin_config_files
+        .as_deref()
+        .map(admin_rpc_service::pod_settled_marker_dirs)
+        .unwrap_or_default();
 
     let rpc_bigtable_config = if matches.is_present("enable_rpc_bigtable_ledger_storage")
         || matches.is_present("enable_bigtable_ledger_upload")
@@ -1742,6 +1768,7 @@ pub fn main() {
             tower_storage: validator_config.tower_storage.clone(),
             staked_nodes_overrides,
             rpc_to_plugin_manager_sender,
+            pod_settled_marker_dirs,
         },
     );
 